@@ -0,0 +1,262 @@
+use std::{collections::BTreeMap, fmt, fs::{self, File}, io::{BufRead, BufReader, BufWriter, Write}, path::{Path, PathBuf}};
+
+use chrono::{DateTime, Utc};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+
+use crate::{backtest::{fetch::{fetch_candles_between, find_gaps, CandleGap, CandleInterval, FetchError}, ledger::parse_backtest_date},
+core::candle::{Candle, CandleTrait}, upbit_api::{client::UpbitClient, error::UpbitError}};
+
+const COVERAGE_FILE: &str = "coverage.json";
+
+/// 캔들 저장소 에러
+#[derive(Debug)]
+pub enum CandleStoreError {
+    Io(std::io::Error),
+    Api(UpbitError),
+    /// 해석할 수 없는 날짜 문자열
+    InvalidDate(String),
+    /// 업비트가 지원하지 않는 간격
+    UnsupportedInterval(CandleInterval),
+}
+
+impl fmt::Display for CandleStoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CandleStoreError::Io(e) => write!(f, "io error: {}", e),
+            CandleStoreError::Api(e) => write!(f, "api error: {}", e),
+            CandleStoreError::InvalidDate(date) => write!(f, "invalid date: {}", date),
+            CandleStoreError::UnsupportedInterval(interval) => write!(f, "unsupported interval: {}", interval.name()),
+        }
+    }
+}
+
+impl std::error::Error for CandleStoreError {}
+
+impl From<std::io::Error> for CandleStoreError {
+    fn from(e: std::io::Error) -> Self {
+        CandleStoreError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for CandleStoreError {
+    fn from(e: serde_json::Error) -> Self {
+        CandleStoreError::Io(e.into())
+    }
+}
+
+impl From<UpbitError> for CandleStoreError {
+    fn from(e: UpbitError) -> Self {
+        CandleStoreError::Api(e)
+    }
+}
+
+impl From<FetchError> for CandleStoreError {
+    fn from(e: FetchError) -> Self {
+        match e {
+            FetchError::Api(e) => CandleStoreError::Api(e),
+            FetchError::InvalidDate(date) => CandleStoreError::InvalidDate(date),
+            FetchError::UnsupportedInterval(interval) => CandleStoreError::UnsupportedInterval(interval),
+        }
+    }
+}
+
+/// 마켓/간격별로 나눠 저장하는 로컬 캔들 저장소
+///
+/// `{root}/{market}/{interval}/{partition}.jsonl.gz`에 캔들을 시간 순서로 저장함.
+/// 파티션은 일봉 이상이면 연도 (`2024`), 그 외에는 UTC 날짜 (`2024-01-01`).
+/// 이미 받아온 시간 구간은 `coverage.json`에 기록해서 거래가 없어 캔들이 없는 구간을 다시 받지 않음
+pub struct CandleStore {
+    pub root: PathBuf,
+}
+
+impl CandleStore {
+    pub fn new<P: AsRef<Path>>(root: P) -> Self {
+        Self { root: root.as_ref().to_path_buf() }
+    }
+
+    fn dir(&self, market: &str, interval: CandleInterval) -> PathBuf {
+        self.root.join(market).join(interval.name())
+    }
+
+    fn partition(interval: CandleInterval, time: i64) -> String {
+        let format = match interval {
+            CandleInterval::Days | CandleInterval::Weeks | CandleInterval::Months | CandleInterval::Years => "%Y",
+            CandleInterval::Seconds | CandleInterval::Minutes(_) => "%Y-%m-%d",
+        };
+        DateTime::<Utc>::from_timestamp_millis(time).map(|datetime| datetime.format(format).to_string()).unwrap_or_default()
+    }
+
+    fn partition_path(&self, market: &str, interval: CandleInterval, partition: &str) -> PathBuf {
+        self.dir(market, interval).join(format!("{}.jsonl.gz", partition))
+    }
+
+    fn read_partition(path: &Path) -> Result<BTreeMap<i64, Candle>, CandleStoreError> {
+        let mut candles = BTreeMap::new();
+        if !path.exists() {
+            return Ok(candles);
+        }
+        let reader = BufReader::new(GzDecoder::new(File::open(path)?));
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let candle = serde_json::from_str::<Candle>(&line)?;
+            if let Some(time) = parse_backtest_date(candle.get_candle_date_time_utc()) {
+                candles.insert(time, candle);
+            }
+        }
+        Ok(candles)
+    }
+
+    // 임시 파일에 쓴 뒤 교체해서 중간에 중단되어도 기존 파일이 깨지지 않게 함
+    fn write_partition(path: &Path, candles: &BTreeMap<i64, Candle>) -> Result<(), CandleStoreError> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let temp_path = path.with_extension("tmp");
+        let mut writer = GzEncoder::new(BufWriter::new(File::create(&temp_path)?), Compression::default());
+        for candle in candles.values() {
+            writer.write_all(serde_json::to_string(candle)?.as_bytes())?;
+            writer.write_all(b"\n")?;
+        }
+        writer.finish()?.flush()?;
+        fs::rename(temp_path, path)?;
+        Ok(())
+    }
+
+    /// 캔들을 파티션에 합쳐 저장. 같은 시간의 캔들은 새 값으로 바꿈. 새로 추가된 캔들 수를 반환
+    pub fn save(&self, market: &str, interval: CandleInterval, candles: &[Candle]) -> Result<usize, CandleStoreError> {
+        let mut partitions: BTreeMap<String, Vec<(i64, &Candle)>> = BTreeMap::new();
+        for candle in candles {
+            if let Some(time) = parse_backtest_date(candle.get_candle_date_time_utc()) {
+                partitions.entry(Self::partition(interval, time)).or_default().push((time, candle));
+            }
+        }
+
+        let mut added = 0;
+        for (partition, candles) in partitions {
+            let path = self.partition_path(market, interval, &partition);
+            let mut stored = Self::read_partition(&path)?;
+            for (time, candle) in candles {
+                if stored.insert(time, candle.clone()).is_none() {
+                    added += 1;
+                }
+            }
+            Self::write_partition(&path, &stored)?;
+        }
+        Ok(added)
+    }
+
+    /// [from, to) 구간의 저장된 캔들 (오래된 순서). 네트워크를 사용하지 않음
+    pub fn load(&self, market: &str, interval: CandleInterval, from: &str, to: &str) -> Result<Vec<Candle>, CandleStoreError> {
+        let (from, to) = (parse_date(from)?, parse_date(to)?);
+        self.load_range(market, interval, from, to)
+    }
+
+    fn load_range(&self, market: &str, interval: CandleInterval, from: i64, to: i64) -> Result<Vec<Candle>, CandleStoreError> {
+        let dir = self.dir(market, interval);
+        if from >= to || !dir.exists() {
+            return Ok(Vec::new());
+        }
+        let (first, last) = (Self::partition(interval, from), Self::partition(interval, to - 1));
+        let mut partitions = fs::read_dir(&dir)?
+            .filter_map(|entry| entry.ok()?.file_name().to_str()?.strip_suffix(".jsonl.gz").map(str::to_string))
+            .filter(|partition| *partition >= first && *partition <= last)
+            .collect::<Vec<String>>();
+        partitions.sort();
+
+        let mut candles = Vec::new();
+        for partition in partitions {
+            let stored = Self::read_partition(&self.partition_path(market, interval, &partition))?;
+            candles.extend(stored.range(from..to).map(|(_, candle)| candle.clone()));
+        }
+        Ok(candles)
+    }
+
+    /// 이미 받아온 시간 구간 목록 ([시작, 끝) ms, 정렬됨)
+    pub fn coverage(&self, market: &str, interval: CandleInterval) -> Result<Vec<(i64, i64)>, CandleStoreError> {
+        let path = self.dir(market, interval).join(COVERAGE_FILE);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    /// [start, end) 구간을 받아온 것으로 기록 (겹치거나 이어지는 구간은 합침)
+    pub fn mark_covered(&self, market: &str, interval: CandleInterval, start: i64, end: i64) -> Result<(), CandleStoreError> {
+        if start >= end {
+            return Ok(());
+        }
+        let mut ranges = self.coverage(market, interval)?;
+        ranges.push((start, end));
+        ranges.sort();
+        let mut merged: Vec<(i64, i64)> = Vec::new();
+        for (start, end) in ranges {
+            match merged.last_mut() {
+                Some(last) if start <= last.1 => last.1 = last.1.max(end),
+                _ => merged.push((start, end)),
+            }
+        }
+        let dir = self.dir(market, interval);
+        fs::create_dir_all(&dir)?;
+        fs::write(dir.join(COVERAGE_FILE), serde_json::to_string(&merged)?)?;
+        Ok(())
+    }
+
+    /// [from, to) 중 아직 받아오지 않은 구간
+    pub fn missing_ranges(&self, market: &str, interval: CandleInterval, from: i64, to: i64) -> Result<Vec<(i64, i64)>, CandleStoreError> {
+        let mut missing = Vec::new();
+        let mut cursor = from;
+        for (start, end) in self.coverage(market, interval)? {
+            if end <= cursor || start >= to {
+                continue;
+            }
+            if start > cursor {
+                missing.push((cursor, start.min(to)));
+            }
+            cursor = cursor.max(end);
+        }
+        if cursor < to {
+            missing.push((cursor, to));
+        }
+        Ok(missing)
+    }
+
+    /// [from, to) 구간의 저장된 캔들 사이에 빠진 구간
+    ///
+    /// 업비트는 거래가 없던 시간의 캔들을 만들지 않으므로 거래가 적은 마켓에서는 빠진 구간이 정상일 수 있음
+    pub fn find_gaps(&self, market: &str, interval: CandleInterval, from: &str, to: &str) -> Result<Vec<CandleGap>, CandleStoreError> {
+        let candles = self.load(market, interval, from, to)?;
+        Ok(find_gaps(&candles, interval))
+    }
+
+    /// [from, to) 구간 중 받아오지 않은 부분만 업비트에서 받아 저장. 새로 저장한 캔들 수를 반환
+    ///
+    /// 아직 끝나지 않은 현재 캔들은 받지 않음
+    pub async fn sync(&self, client: &UpbitClient, market: &str, interval: CandleInterval, from: &str, to: &str) -> Result<usize, CandleStoreError> {
+        if !interval.is_supported() {
+            return Err(CandleStoreError::UnsupportedInterval(interval));
+        }
+        let now = Utc::now().timestamp_millis();
+        let (from, to) = (parse_date(from)?, parse_date(to)?.min(interval.floor(now)));
+
+        let mut added = 0;
+        for (start, end) in self.missing_ranges(market, interval, from, to)? {
+            let candles = fetch_candles_between(client, market, interval, start, end).await?;
+            added += self.save(market, interval, &candles)?;
+            self.mark_covered(market, interval, start, end)?;
+        }
+        Ok(added)
+    }
+
+    /// 받아오지 않은 구간을 동기화한 뒤 [from, to) 구간 캔들을 오래된 순서로 반환
+    pub async fn load_synced(&self, client: &UpbitClient, market: &str, interval: CandleInterval, from: &str, to: &str) -> Result<Vec<Candle>, CandleStoreError> {
+        self.sync(client, market, interval, from, to).await?;
+        self.load(market, interval, from, to)
+    }
+}
+
+fn parse_date(date: &str) -> Result<i64, CandleStoreError> {
+    parse_backtest_date(date).ok_or_else(|| CandleStoreError::InvalidDate(date.to_string()))
+}
@@ -0,0 +1,39 @@
+use crate::core::order_event::Liquidity;
+
+/// 체결 수수료율 (소수, 0.0005 = 0.05%)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FeeSchedule {
+    pub maker_pct: f64,
+    pub taker_pct: f64,
+}
+
+impl FeeSchedule {
+    pub fn new(maker_pct: f64, taker_pct: f64) -> Self {
+        Self { maker_pct, taker_pct }
+    }
+
+    /// maker/taker 구분 없이 같은 수수료
+    pub fn flat(fee_pct: f64) -> Self {
+        Self::new(fee_pct, fee_pct)
+    }
+
+    /// 업비트 마켓별 기본 수수료
+    ///
+    /// - KRW 마켓: 0.05%
+    /// - BTC, USDT 마켓: 0.25%
+    ///
+    /// 알 수 없는 마켓은 KRW 마켓 수수료를 사용
+    pub fn for_market(code: &str) -> Self {
+        match code.split('-').next().unwrap_or("") {
+            "BTC" | "USDT" => Self::flat(0.0025),
+            _ => Self::flat(0.0005),
+        }
+    }
+
+    pub fn fee_pct(&self, liquidity: Liquidity) -> f64 {
+        match liquidity {
+            Liquidity::Maker => self.maker_pct,
+            Liquidity::Taker => self.taker_pct,
+        }
+    }
+}
@@ -1,22 +1,247 @@
-use std::time::Duration;
+use std::{collections::BTreeMap, fmt};
 
-use crate::{core::candle::CandleTrait, upbit_api::candle::{get_candle_days, get_candle_minutes, get_candle_seconds}};
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
 
-pub async fn fetch_n_seconds_candles(market: &str, mut count: u32, to: &str) 
+use crate::{backtest::ledger::parse_backtest_date, core::candle::{Candle, CandleTrait},
+upbit_api::{candle::{get_candle_days, get_candle_minutes, get_candle_months, get_candle_seconds, get_candle_weeks, get_candle_years}, client::UpbitClient, error::UpbitError}};
+
+// 업비트 캔들 API 한 번에 가져올 수 있는 최대 개수
+const PAGE_SIZE: u32 = 200;
+const DAY_MS: i64 = 86_400_000;
+
+/// 업비트가 지원하는 분봉 단위
+pub const MINUTE_UNITS: [u32; 8] = [1, 3, 5, 10, 15, 30, 60, 240];
+
+/// 캔들 간격
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CandleInterval {
+    Seconds,
+    /// 분 단위 (1, 3, 5, 10, 15, 30, 60, 240)
+    Minutes(u32),
+    Days,
+    /// 월요일 0시 (UTC) 시작
+    Weeks,
+    Months,
+    Years,
+}
+
+impl CandleInterval {
+    /// 저장 경로 등에 쓰는 이름 (`seconds`, `minutes_5`, `days`, `weeks`, `months`, `years`)
+    pub fn name(&self) -> String {
+        match self {
+            CandleInterval::Seconds => "seconds".to_string(),
+            CandleInterval::Minutes(unit) => format!("minutes_{}", unit),
+            CandleInterval::Days => "days".to_string(),
+            CandleInterval::Weeks => "weeks".to_string(),
+            CandleInterval::Months => "months".to_string(),
+            CandleInterval::Years => "years".to_string(),
+        }
+    }
+
+    /// 업비트 API가 지원하는 간격인지 여부 (분봉 단위 확인)
+    pub fn is_supported(&self) -> bool {
+        match self {
+            CandleInterval::Minutes(unit) => MINUTE_UNITS.contains(unit),
+            _ => true,
+        }
+    }
+
+    // 길이가 일정한 간격의 캔들 길이 (ms). 월봉, 연봉은 None
+    fn fixed_ms(&self) -> Option<i64> {
+        match self {
+            CandleInterval::Seconds => Some(1000),
+            CandleInterval::Minutes(unit) => Some(*unit as i64 * 60_000),
+            CandleInterval::Days => Some(DAY_MS),
+            CandleInterval::Weeks => Some(7 * DAY_MS),
+            CandleInterval::Months | CandleInterval::Years => None,
+        }
+    }
+
+    /// time (ms)이 속한 캔들의 시작 시간
+    pub fn floor(&self, time: i64) -> i64 {
+        let date = DateTime::<Utc>::from_timestamp_millis(time).map(|datetime| datetime.date_naive()).unwrap_or_default();
+        let start_of = |date: Option<NaiveDate>| date.and_then(|date| date.and_hms_opt(0, 0, 0)).map(|datetime| datetime.and_utc().timestamp_millis()).unwrap_or(time);
+        match self {
+            // 1970-01-01은 목요일이므로 3일을 더해 월요일 기준으로 맞춤
+            CandleInterval::Weeks => time - (time + 3 * DAY_MS).rem_euclid(7 * DAY_MS),
+            CandleInterval::Months => start_of(NaiveDate::from_ymd_opt(date.year(), date.month(), 1)),
+            CandleInterval::Years => start_of(NaiveDate::from_ymd_opt(date.year(), 1, 1)),
+            _ => {
+                let step = self.fixed_ms().unwrap_or(1);
+                time - time.rem_euclid(step)
+            }
+        }
+    }
+
+    /// start (ms)에 시작하는 캔들의 다음 캔들 시작 시간
+    pub fn next(&self, start: i64) -> i64 {
+        let add_months = |months: u32| DateTime::<Utc>::from_timestamp_millis(start)
+            .and_then(|datetime| datetime.checked_add_months(chrono::Months::new(months)))
+            .map(|datetime| datetime.timestamp_millis())
+            .unwrap_or(i64::MAX);
+        match self {
+            CandleInterval::Months => add_months(1),
+            CandleInterval::Years => add_months(12),
+            _ => start + self.fixed_ms().unwrap_or(1),
+        }
+    }
+
+    /// [from, to) 구간에 시작하는 캔들 수 (from은 캔들 시작 시간)
+    pub fn count_between(&self, from: i64, to: i64) -> usize {
+        if from >= to {
+            return 0;
+        }
+        if let Some(step) = self.fixed_ms() {
+            return ((to - from + step - 1) / step) as usize;
+        }
+        let mut count = 0;
+        let mut time = from;
+        while time < to {
+            count += 1;
+            time = self.next(time);
+        }
+        count
+    }
+}
+
+/// 구간 캔들 다운로드 에러
+#[derive(Debug)]
+pub enum FetchError {
+    Api(UpbitError),
+    /// 해석할 수 없는 날짜 문자열
+    InvalidDate(String),
+    /// 업비트가 지원하지 않는 간격 (예: 7분봉)
+    UnsupportedInterval(CandleInterval),
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FetchError::Api(e) => write!(f, "api error: {}", e),
+            FetchError::InvalidDate(date) => write!(f, "invalid date: {}", date),
+            FetchError::UnsupportedInterval(interval) => write!(f, "unsupported interval: {}", interval.name()),
+        }
+    }
+}
+
+impl std::error::Error for FetchError {}
+
+impl From<UpbitError> for FetchError {
+    fn from(e: UpbitError) -> Self {
+        FetchError::Api(e)
+    }
+}
+
+/// 캔들 사이에 빠진 구간
+#[derive(Debug, Clone, PartialEq)]
+pub struct CandleGap {
+    /// 빠진 첫 캔들 시간
+    pub from: String,
+    /// 빠진 구간 다음에 있는 캔들 시간
+    pub to: String,
+    /// 빠진 캔들 수
+    pub missing: usize,
+}
+
+/// 구간 캔들과 빠진 구간
+#[derive(Debug, Clone)]
+pub struct CandleRange {
+    /// 중복 없이 오래된 순서
+    pub candles: Vec<Candle>,
+    /// 업비트는 거래가 없던 시간의 캔들을 만들지 않으므로 거래가 적은 마켓에서는 빠진 구간이 정상일 수 있음
+    pub gaps: Vec<CandleGap>,
+}
+
+/// 오래된 순서의 캔들 사이에 빠진 구간
+pub fn find_gaps(candles: &[Candle], interval: CandleInterval) -> Vec<CandleGap> {
+    candles.windows(2).filter_map(|w| {
+        let previous = parse_backtest_date(w[0].get_candle_date_time_utc())?;
+        let next = parse_backtest_date(w[1].get_candle_date_time_utc())?;
+        let expected = interval.next(previous);
+        (next > expected).then(|| CandleGap {
+            from: format_candle_date(expected),
+            to: w[1].get_candle_date_time_utc().to_string(),
+            missing: interval.count_between(expected, next),
+        })
+    }).collect()
+}
+
+/// ms 타임스탬프를 캔들 시간 형식 (`2024-01-01T00:00:00`)으로 변환
+pub fn format_candle_date(time: i64) -> String {
+    DateTime::<Utc>::from_timestamp_millis(time).map(|datetime| datetime.format("%Y-%m-%dT%H:%M:%S").to_string()).unwrap_or_default()
+}
+
+/// to 이전의 캔들을 최대 count개 (최대 200) 가져옴. 최신 순서
+pub async fn fetch_candle_page(client: &UpbitClient, market: &str, interval: CandleInterval, to: &str, count: u32) -> Result<Vec<Candle>, UpbitError> {
+    let candles = match interval {
+        CandleInterval::Seconds => get_candle_seconds(client, market, Some(to), count).await?,
+        CandleInterval::Minutes(unit) => get_candle_minutes(client, market, Some(to), count, unit).await?
+            .into_iter().map(|candle| Candle { base: candle.base }).collect(),
+        CandleInterval::Days => get_candle_days(client, market, Some(to), count).await?
+            .into_iter().map(|candle| Candle { base: candle.base }).collect(),
+        CandleInterval::Weeks => get_candle_weeks(client, market, Some(to), count).await?
+            .into_iter().map(|candle| Candle { base: candle.base }).collect(),
+        CandleInterval::Months => get_candle_months(client, market, Some(to), count).await?
+            .into_iter().map(|candle| Candle { base: candle.base }).collect(),
+        CandleInterval::Years => get_candle_years(client, market, Some(to), count).await?
+            .into_iter().map(|candle| Candle { base: candle.base }).collect(),
+    };
+    Ok(candles)
+}
+
+/// [from, to) ms 구간의 캔들을 to부터 과거 방향으로 200개씩 받아 중복 없이 오래된 순서로 반환
+pub async fn fetch_candles_between(client: &UpbitClient, market: &str, interval: CandleInterval, from: i64, to: i64)
+-> Result<Vec<Candle>, FetchError> {
+    if !interval.is_supported() {
+        return Err(FetchError::UnsupportedInterval(interval));
+    }
+    let mut candles = BTreeMap::new();
+    let mut cursor = to;
+    while cursor > from {
+        let page = fetch_candle_page(client, market, interval, &format!("{}Z", format_candle_date(cursor)), PAGE_SIZE).await?;
+        let mut oldest = None;
+        for candle in &page {
+            let Some(time) = parse_backtest_date(candle.get_candle_date_time_utc()) else { continue };
+            oldest = Some(oldest.map_or(time, |oldest: i64| oldest.min(time)));
+            if time >= from && time < to {
+                candles.insert(time, candle.clone());
+            }
+        }
+
+        // 페이지가 가득 차지 않았으면 더 이전 캔들이 없음
+        match oldest {
+            Some(oldest) if page.len() == PAGE_SIZE as usize && oldest < cursor => cursor = oldest,
+            _ => break,
+        }
+    }
+    Ok(candles.into_values().collect())
+}
+
+/// [from, to) 구간의 캔들을 받아 오래된 순서로 반환하고 빠진 구간을 표시
+///
+/// 날짜는 UTC 기준 (`2024-01-01T00:00:00`, `2024-01-01 00:00:00`). 모든 간격을 같은 `Candle` 타입으로 반환함
+pub async fn fetch_candles_range(client: &UpbitClient, market: &str, interval: CandleInterval, from: &str, to: &str)
+-> Result<CandleRange, FetchError> {
+    let from_time = parse_backtest_date(from).ok_or_else(|| FetchError::InvalidDate(from.to_string()))?;
+    let to_time = parse_backtest_date(to).ok_or_else(|| FetchError::InvalidDate(to.to_string()))?;
+    let candles = fetch_candles_between(client, market, interval, from_time, to_time).await?;
+    let gaps = find_gaps(&candles, interval);
+    Ok(CandleRange { candles, gaps })
+}
+
+pub async fn fetch_n_seconds_candles(client: &UpbitClient, market: &str, mut count: u32, to: &str) 
 -> Result<Vec<Box<dyn CandleTrait>>, Box<dyn std::error::Error>> {
     let mut candles = Vec::new();
     let mut to = to.to_string();
     while count >= 200 {
-        let new_candles = get_candle_seconds(market, Some(&to), count).await?;
-        // 0.15초 대기
-        tokio::time::sleep(Duration::from_millis(120)).await;
+        let new_candles = get_candle_seconds(client, market, Some(&to), count).await?;
         candles.extend(new_candles.clone().into_iter().map(|c| Box::new(c) as Box<dyn CandleTrait>));
         to = new_candles.last().ok_or("No candles")?.get_candle_date_time_utc().to_string();
         count -= 200;
     }
 
     if count > 0 && count < 200 {
-        let new_candles = get_candle_seconds(market, Some(&to), count).await?;
+        let new_candles = get_candle_seconds(client, market, Some(&to), count).await?;
         candles.extend(new_candles.clone().into_iter().map(|c| Box::new(c) as Box<dyn CandleTrait>));
     }
     
@@ -26,41 +251,38 @@ pub async fn fetch_n_seconds_candles(market: &str, mut count: u32, to: &str)
 
 /// upbit의 경우 최대 200개의 데이터만 가져올 수 있음
 /// 따라서 200개 이상의 데이터를 가져오기 위해서는 여러 번 호출해야 함
-pub async fn fetch_n_minute_candles(market: &str, mut count: u32, to: &str, unit: u32) 
+/// 호출 간격은 client의 요청 수 제한이 조절함
+pub async fn fetch_n_minute_candles(client: &UpbitClient, market: &str, mut count: u32, to: &str, unit: u32) 
 -> Result<Vec<Box<dyn CandleTrait>>, Box<dyn std::error::Error>> {
     let mut candles = Vec::new();
     let mut to = to.to_string();
     while count >= 200 {
-        let new_candles = get_candle_minutes(market, Some(&to), count, unit).await?;
-        // 0.15초 대기
-        tokio::time::sleep(Duration::from_millis(120)).await;
+        let new_candles = get_candle_minutes(client, market, Some(&to), count, unit).await?;
         candles.extend(new_candles.clone().into_iter().map(|c| Box::new(c) as Box<dyn CandleTrait>));
         to = new_candles.last().ok_or("No candles")?.get_candle_date_time_utc().to_string();
         count -= 200;
     }
 
     if count > 0 && count < 200 {
-        let new_candles = get_candle_minutes(market, Some(&to), count, unit).await?;
+        let new_candles = get_candle_minutes(client, market, Some(&to), count, unit).await?;
         candles.extend(new_candles.clone().into_iter().map(|c| Box::new(c) as Box<dyn CandleTrait>));
     }
     Ok(candles)
 } 
 
-pub async fn fetch_n_day_candles(market: &str, mut count: u32, to: &str) 
+pub async fn fetch_n_day_candles(client: &UpbitClient, market: &str, mut count: u32, to: &str) 
 -> Result<Vec<Box<dyn CandleTrait>>, Box<dyn std::error::Error>> {
     let mut candles = Vec::new();
     let mut to = to.to_string();
     while count >= 200 {
-        let new_candles = get_candle_days(market, Some(&to), count).await?;
-        // 0.15초 대기
-        tokio::time::sleep(Duration::from_millis(120)).await;
+        let new_candles = get_candle_days(client, market, Some(&to), count).await?;
         candles.extend(new_candles.clone().into_iter().map(|c| Box::new(c) as Box<dyn CandleTrait>));
         to = new_candles.last().ok_or("No candles")?.get_candle_date_time_utc().to_string();
         count -= 200;
     }
 
     if count > 0 && count < 200 {
-        let new_candles = get_candle_days(market, Some(&to), count).await?;
+        let new_candles = get_candle_days(client, market, Some(&to), count).await?;
         candles.extend(new_candles.clone().into_iter().map(|c| Box::new(c) as Box<dyn CandleTrait>));
     }
 
@@ -0,0 +1,57 @@
+use crate::{backtest::ledger::ExitReason, core::candle::{Candle, CandleTrait}};
+
+/// 한 봉에서 익절가와 스탑 가격에 모두 닿은 경우 어느 쪽이 먼저 체결됐다고 볼지
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum IntrabarPolicy {
+    /// 스탑이 먼저 체결됐다고 봄
+    #[default]
+    Pessimistic,
+    /// 익절이 먼저 체결됐다고 봄
+    Optimistic,
+    /// 하위 타임프레임 캔들로 순서를 확인. 하위 캔들에서도 구분할 수 없으면 Pessimistic
+    LowerTimeframe,
+}
+
+/// 봉 안에서 발생한 청산
+#[derive(Debug, Clone, PartialEq)]
+pub struct IntrabarFill {
+    pub price: f64,
+    pub exit_reason: ExitReason,
+}
+
+/// 롱 포지션이 캔들 하나 동안 익절/스탑에 닿았는지 확인
+///
+/// 시가가 이미 스탑 아래 (또는 익절가 위)에서 시작하면 시가에 체결된 것으로 봄 (갭).
+/// lower_candles는 `IntrabarPolicy::LowerTimeframe`에서만 사용하며 오래된 순서로 정렬되어 있어야 함
+pub fn resolve_intrabar_exit(candle: &Candle, take_profit_price: f64, stop_price: f64, policy: IntrabarPolicy, lower_candles: &[Candle])
+-> Option<IntrabarFill> {
+    let open = candle.get_opening_price();
+
+    // 갭으로 시가부터 가격을 넘어선 경우
+    if open <= stop_price {
+        return Some(IntrabarFill { price: open, exit_reason: ExitReason::TrailingStop });
+    }
+    if open >= take_profit_price {
+        return Some(IntrabarFill { price: open, exit_reason: ExitReason::TakeProfit });
+    }
+
+    let take_profit = IntrabarFill { price: take_profit_price, exit_reason: ExitReason::TakeProfit };
+    let stop = IntrabarFill { price: stop_price, exit_reason: ExitReason::TrailingStop };
+    let take_profit_hit = candle.get_high_price() >= take_profit_price;
+    let stop_hit = candle.get_low_price() <= stop_price;
+
+    match (take_profit_hit, stop_hit) {
+        (true, true) => match policy {
+            IntrabarPolicy::Pessimistic => Some(stop),
+            IntrabarPolicy::Optimistic => Some(take_profit),
+            IntrabarPolicy::LowerTimeframe => {
+                lower_candles.iter()
+                    .find_map(|lower| resolve_intrabar_exit(lower, take_profit_price, stop_price, IntrabarPolicy::Pessimistic, &[]))
+                    .or(Some(stop))
+            }
+        },
+        (true, false) => Some(take_profit),
+        (false, true) => Some(stop),
+        (false, false) => None,
+    }
+}
@@ -0,0 +1,139 @@
+use std::{fmt, fs::File, io::{BufWriter, Write}, path::Path};
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/// 청산 이유
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExitReason {
+    TakeProfit,
+    TrailingStop,
+    StrategySell,
+    EndOfTest,
+    /// 분할 익절 목표에 닿아 일부 청산
+    PartialTakeProfit,
+    /// 전략의 `Signal::ReducePosition`으로 일부 청산
+    ReducePosition,
+}
+
+impl fmt::Display for ExitReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ExitReason::TakeProfit => "take_profit",
+            ExitReason::TrailingStop => "trailing_stop",
+            ExitReason::StrategySell => "strategy_sell",
+            ExitReason::EndOfTest => "end_of_test",
+            ExitReason::PartialTakeProfit => "partial_take_profit",
+            ExitReason::ReducePosition => "reduce_position",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// 청산된 거래 한 건
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeRecord {
+    pub code: String,
+    pub entry_date: String,
+    pub exit_date: String,
+    pub entry_price: f64,
+    pub exit_price: f64,
+    /// 진입 금액
+    pub entry_asset: f64,
+    /// 진입 수량 (진입 금액 / 진입가)
+    pub size: f64,
+    /// 진입과 청산에 낸 수수료 합계
+    pub fees: f64,
+    /// 수수료를 포함한 실현 손익 금액
+    pub pnl: f64,
+    /// 수수료를 포함한 실현 손익률
+    pub pnl_pct: f64,
+    pub exit_reason: ExitReason,
+    /// 보유 중 진입가 대비 최대 불리 변동률 (0 이하)
+    pub mae_pct: f64,
+    /// 보유 중 진입가 대비 최대 유리 변동률 (0 이상)
+    pub mfe_pct: f64,
+    /// 진입 신호의 이유
+    pub entry_reason: String,
+    /// 전략 매도인 경우 매도 신호의 이유
+    pub sell_reason: Option<String>,
+}
+
+impl TradeRecord {
+    /// 보유 시간 (ms). 날짜를 해석할 수 없으면 None
+    pub fn holding_ms(&self) -> Option<i64> {
+        Some(parse_backtest_date(&self.exit_date)? - parse_backtest_date(&self.entry_date)?)
+    }
+}
+
+/// 자산 곡선의 한 점. 포지션은 현재가로 평가함
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EquityPoint {
+    pub date: String,
+    pub equity: f64,
+}
+
+/// 백테스트에서 사용하는 날짜 문자열을 ms 타임스탬프로 변환
+///
+/// 캔들 시간 (`2024-01-01T00:00:00`, 끝의 `Z` 허용)과 체결 타임스탬프 문자열 (`1704067200000`)을 지원
+pub fn parse_backtest_date(date: &str) -> Option<i64> {
+    if !date.is_empty() && date.chars().all(|c| c.is_ascii_digit()) {
+        return date.parse::<i64>().ok();
+    }
+    let date = date.trim_end_matches('Z');
+    ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M:%S"].iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(date, format).ok())
+        .map(|datetime| datetime.and_utc().timestamp_millis())
+}
+
+const TRADE_CSV_HEADER: &str = "code,entry_date,exit_date,entry_price,exit_price,entry_asset,size,fees,pnl,pnl_pct,exit_reason,mae_pct,mfe_pct,entry_reason,sell_reason";
+
+// 쉼표, 따옴표, 줄바꿈이 있으면 따옴표로 감쌈
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// 거래 기록을 CSV로 작성
+pub fn write_trades_csv<W: Write>(writer: &mut W, trades: &[TradeRecord]) -> std::io::Result<()> {
+    writeln!(writer, "{}", TRADE_CSV_HEADER)?;
+    for trade in trades {
+        writeln!(writer, "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+            csv_field(&trade.code), csv_field(&trade.entry_date), csv_field(&trade.exit_date),
+            trade.entry_price, trade.exit_price, trade.entry_asset, trade.size, trade.fees,
+            trade.pnl, trade.pnl_pct, trade.exit_reason, trade.mae_pct, trade.mfe_pct,
+            csv_field(&trade.entry_reason), csv_field(trade.sell_reason.as_deref().unwrap_or("")))?;
+    }
+    Ok(())
+}
+
+/// 자산 곡선을 CSV로 작성
+pub fn write_equity_curve_csv<W: Write>(writer: &mut W, equity_curve: &[EquityPoint]) -> std::io::Result<()> {
+    writeln!(writer, "date,equity")?;
+    for point in equity_curve {
+        writeln!(writer, "{},{}", csv_field(&point.date), point.equity)?;
+    }
+    Ok(())
+}
+
+pub fn save_trades_csv<P: AsRef<Path>>(path: P, trades: &[TradeRecord]) -> std::io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_trades_csv(&mut writer, trades)?;
+    writer.flush()
+}
+
+pub fn save_trades_json<P: AsRef<Path>>(path: P, trades: &[TradeRecord]) -> std::io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    serde_json::to_writer_pretty(&mut writer, trades)?;
+    writer.flush()
+}
+
+pub fn save_equity_curve_csv<P: AsRef<Path>>(path: P, equity_curve: &[EquityPoint]) -> std::io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_equity_curve_csv(&mut writer, equity_curve)?;
+    writer.flush()
+}
@@ -1,4 +1,16 @@
-use crate::core::signal::Signal;
+use crate::backtest::fee::FeeSchedule;
+use crate::backtest::fill::{resolve_intrabar_exit, IntrabarPolicy};
+use crate::backtest::ledger::{parse_backtest_date, EquityPoint, ExitReason, TradeRecord};
+use crate::backtest::report::BacktestReport;
+use crate::backtest::risk::{RiskDecision, RiskManager};
+use crate::backtest::sizing::{PositionSizing, SizingContext};
+use crate::backtest::slippage::{MarketContext, SlippageModel};
+use crate::core::candle::{Candle, CandleTrait};
+use crate::core::order_event::Liquidity;
+use crate::core::position::{Lot, OpenTrade, PositionState};
+use crate::core::signal::{Signal, SignalReason, TakeProfitTarget};
+use crate::upbit_api::market_rules::MarketRules;
+use crate::upbit_api::order::OrderSide;
 use crate::webhook::lib as webhook_lib;
 use std::ops::Add;
 use tokio::spawn;
@@ -6,39 +18,52 @@ use tokio::spawn;
 #[derive(Clone, Debug)]
 pub struct BacktestParams {
     pub code: String,
-    pub fees_pct: f64,
+    pub fees: FeeSchedule, // 진입, 트레일링 스탑, 전략 매도는 taker, 익절은 maker (지정가)
+    pub slippage: SlippageModel, // taker 주문에만 적용
+    pub market_rules: MarketRules, // 신호 가격을 호가 단위에 맞추고 최소 주문 금액 미만 진입을 무시
     pub enable_webhook_log: bool,
+    pub enable_trade_log: bool, // 진입/청산마다 콘솔 출력 (최적화처럼 여러 번 실행할 때는 끔)
     pub strategy_name: String,
+    pub intrabar_policy: IntrabarPolicy, // 캔들 백테스트에서 한 봉에 익절/스탑이 모두 닿은 경우 처리 방식
+    pub sizing: PositionSizing, // 진입 신호의 asset_pct를 정하는 방식 (기본값은 신호 그대로)
 }
 
 impl BacktestParams {
-    pub fn new(code: String, fees_pct: f64, enable_webhook_log: bool, strategy_name: String) -> Self {
-        Self { code, fees_pct, enable_webhook_log, strategy_name }  
+    pub fn new(code: String, fees: FeeSchedule, enable_webhook_log: bool, strategy_name: String) -> Self {
+        let market_rules = MarketRules::for_market(&code);
+        Self { code, fees, slippage: SlippageModel::None, market_rules, enable_webhook_log, enable_trade_log: true, strategy_name, intrabar_policy: IntrabarPolicy::Pessimistic, sizing: PositionSizing::SignalPct }  
     }
 
+    /// 마켓별 업비트 기본 수수료, 슬리피지 없음
     pub fn default(code: &str, strategy_name: &str) -> Self {
         Self {
             code: code.to_string(),
-            fees_pct: 0.0005,
+            fees: FeeSchedule::for_market(code),
+            slippage: SlippageModel::None,
+            market_rules: MarketRules::for_market(code),
             enable_webhook_log: true,
+            enable_trade_log: true,
             strategy_name: strategy_name.to_string(),
+            intrabar_policy: IntrabarPolicy::Pessimistic,
+            sizing: PositionSizing::SignalPct,
         }
     }
 }
 
-// 가상 포지션의 상태
-#[derive(Debug, Clone, Copy)]
-pub enum PositionState {
-    None, // 포지션 없음
-    InPosition {
-        entry_price: f64,
-        entry_asset: f64,
-        take_profit_price: f64,
-        trailing_stop_price: f64, // 트레일링 스탑 가격
-    },
+// 청산 주문 한 건의 체결 정보
+struct ExitFill<'a> {
+    price: f64,
+    liquidity: Liquidity,
+    reason: ExitReason,
+    sell_reason: Option<String>,
+    date: &'a str,
 }
 
-const INITIAL_ASSET: f64 = 1000000.0;
+/// 백테스트 시작 자산
+pub const INITIAL_ASSET: f64 = 1000000.0;
+
+// 자산 곡선에서 한 점으로 합치는 시간 단위 (ms)
+const EQUITY_INTERVAL_MS: i64 = 60_000;
 
 #[derive(Clone, Debug)]
 // 백테스터의 전체 상태와 결과를 관리
@@ -52,6 +77,18 @@ pub struct BacktesterState {
     
     // -- 현재 자산 --
     pub current_asset: f64,
+
+    // -- 거래 기록 --
+    pub trades: Vec<TradeRecord>, // 포지션 진입부터 전체 청산까지 하나씩 기록
+    pub equity_curve: Vec<EquityPoint>,
+    // 현재 포지션에서 지금까지 청산한 부분 (전체 청산되면 trades에 추가)
+    round_trip: Option<TradeRecord>,
+
+    // -- 슬리피지 계산용 시장 상태 --
+    pub market: MarketContext,
+
+    // -- 진입 신호 제한 (None이면 신호 그대로 처리) --
+    pub risk: Option<RiskManager>,
 }
 
 impl BacktesterState {
@@ -63,44 +100,322 @@ impl BacktesterState {
             loss_count: 0,
             total_pnl_pct: 0.0,
             current_asset: INITIAL_ASSET,
+            trades: Vec::new(),
+            equity_curve: Vec::new(),
+            round_trip: None,
+            market: MarketContext::new(),
+            risk: None,
         }
     }
 
     pub fn get_position(&mut self) -> &mut PositionState {
         &mut self.position
     }
+
+    /// 보유 포지션을 현재가로 평가한 총 자산
+    pub fn equity(&self, current_price: f64) -> f64 {
+        self.current_asset + self.position.lots().iter().map(|lot| {
+            if lot.entry_price > 0.0 { lot.entry_asset * current_price / lot.entry_price } else { lot.entry_asset }
+        }).sum::<f64>()
+    }
+
+    /// 자산 곡선에 현재 평가 자산 추가. 같은 분이면 마지막 점을 갱신
+    ///
+    /// 실시간/모의 거래에서는 체결마다 호출되므로 분 단위로 합쳐 곡선이 계속 늘어나지 않게 함
+    fn record_equity(&mut self, current_price: f64, current_date: &str) {
+        let equity = self.equity(current_price);
+        match self.equity_curve.last_mut() {
+            Some(last) if same_equity_interval(&last.date, current_date) => {
+                last.date = current_date.to_string();
+                last.equity = equity;
+            }
+            _ => self.equity_curve.push(EquityPoint { date: current_date.to_string(), equity }),
+        }
+    }
+
+    /// 청산된 lot (부분 청산이면 청산한 부분)을 현재 포지션의 거래에 합침
+    ///
+    /// 진입가/청산가는 수량 가중 평균, 청산 이유는 마지막 청산 기준
+    fn record_trade(&mut self, lot: &Lot, pnl_pct: f64, exit: &ExitFill) {
+        let exit_price = exit.price;
+        let mut open_trade = lot.trade.clone();
+        open_trade.update(exit_price);
+        let entry_price = lot.entry_price;
+        let (mae_pct, mfe_pct) = (open_trade.mae_pct(entry_price), open_trade.mfe_pct(entry_price));
+        let record = TradeRecord {
+            code: self.params.code.clone(),
+            entry_date: open_trade.entry_date,
+            exit_date: exit.date.to_string(),
+            entry_price,
+            exit_price,
+            entry_asset: lot.entry_asset,
+            size: lot.size,
+            fees: lot.entry_asset * self.round_trip_fee_pct(exit.liquidity),
+            pnl: lot.entry_asset * pnl_pct,
+            pnl_pct,
+            exit_reason: exit.reason,
+            mae_pct,
+            mfe_pct,
+            entry_reason: open_trade.entry_reason,
+            sell_reason: exit.sell_reason.clone(),
+        };
+        self.round_trip = Some(match self.round_trip.take() {
+            Some(round_trip) => merge_trade(round_trip, record),
+            None => record,
+        });
+    }
+
+    // 진입 (taker)과 청산 수수료율 합계
+    fn round_trip_fee_pct(&self, exit_liquidity: Liquidity) -> f64 {
+        self.params.fees.fee_pct(Liquidity::Taker) + self.params.fees.fee_pct(exit_liquidity)
+    }
+
+    /// 슬리피지를 적용한 시장가 주문 체결가
+    fn market_fill_price(&self, side: OrderSide, price: f64, quantity: f64) -> f64 {
+        self.params.slippage.fill_price(side, price, quantity, &self.market)
+    }
+
+    /// 현재 현금의 asset_pct만큼 시장가 매수한 lot. 최소 주문 금액 미만이면 None
+    fn open_lot(&mut self, reason: &str, asset_pct: f64, current_price: f64, current_date: &str) -> Option<Lot> {
+        let entry_asset = self.current_asset * asset_pct;
+        // 최소 주문 금액 미만이면 실제 주문이 거절되므로 진입하지 않음
+        if let Err(e) = self.params.market_rules.check_amount(entry_asset) {
+            if self.params.enable_trade_log {
+                println!("[진입 무시] {} - 날짜: {}, {}", self.params.code, current_date, e);
+            }
+            return None;
+        }
+        let entry_price = self.market_fill_price(OrderSide::Bid, current_price, entry_asset / current_price);
+        self.current_asset -= entry_asset;
+        Some(Lot {
+            entry_price,
+            entry_asset,
+            size: entry_asset / entry_price,
+            trade: OpenTrade::new(current_date, reason, entry_price),
+        })
+    }
+
+    /// 보유 중인 lot들의 가격 범위 갱신
+    fn update_lots(&mut self, high_price: f64, low_price: f64) {
+        if let PositionState::InPosition { lots, .. } = &mut self.position {
+            for lot in lots {
+                lot.trade.update(high_price);
+                lot.trade.update(low_price);
+            }
+        }
+    }
+
+    /// 남은 수량의 fraction 비율을 exit_price에 청산하고, 포지션이 모두 청산되면 거래를 기록
+    ///
+    /// 오래된 lot부터 청산함 (FIFO). 청산한 진입 금액 합계와 실현 손익 금액 반환
+    fn exit_lots(&mut self, fraction: f64, exit: &ExitFill) -> (f64, f64) {
+        let exit_price = exit.price;
+        let lots = match &mut self.position {
+            PositionState::InPosition { lots, .. } => std::mem::take(lots),
+            PositionState::None => return (0.0, 0.0),
+        };
+        let fraction = fraction.clamp(0.0, 1.0);
+        let close_all = fraction >= 1.0;
+        let total_size = lots.iter().map(|lot| lot.size).sum::<f64>();
+        let fee_pct = self.round_trip_fee_pct(exit.liquidity);
+
+        let mut remaining_size = total_size * fraction;
+        let mut exited_asset = 0.0;
+        let mut pnl = 0.0;
+        let mut kept_lots = Vec::new();
+        for mut lot in lots {
+            let exit_size = if close_all { lot.size } else { remaining_size.min(lot.size) };
+            if exit_size <= 0.0 {
+                kept_lots.push(lot);
+                continue;
+            }
+
+            let exit_asset = if lot.size > 0.0 { lot.entry_asset * exit_size / lot.size } else { lot.entry_asset };
+            let pnl_pct = (exit_price / lot.entry_price - 1.0) - fee_pct;
+            let exited = Lot { entry_asset: exit_asset, size: exit_size, ..lot.clone() };
+            self.record_trade(&exited, pnl_pct, exit);
+            self.current_asset += exit_asset * (1.0 + pnl_pct);
+            exited_asset += exit_asset;
+            pnl += exit_asset * pnl_pct;
+            remaining_size -= exit_size;
+
+            lot.size -= exit_size;
+            lot.entry_asset -= exit_asset;
+            // 부동소수점 오차로 남은 아주 작은 수량은 버림
+            if !close_all && lot.size > total_size * 1e-9 {
+                kept_lots.push(lot);
+            }
+        }
+        self.total_pnl_pct = (self.current_asset / INITIAL_ASSET) - 1.0;
+
+        if kept_lots.is_empty() {
+            self.position = PositionState::None; // 포지션 청산
+            if let Some(trade) = self.round_trip.take() {
+                if trade.pnl_pct > 0.0 {
+                    self.win_count += 1;
+                } else {
+                    self.loss_count += 1;
+                }
+                self.trades.push(trade);
+            }
+        } else {
+            if let PositionState::InPosition { lots, .. } = &mut self.position {
+                *lots = kept_lots;
+            }
+            self.position.recalculate();
+        }
+
+        let equity = self.equity(exit_price);
+        let closed = matches!(self.position, PositionState::None);
+        if let Some(risk) = &mut self.risk {
+            risk.record_exit(pnl, equity, closed, exit.date);
+        }
+        (exited_asset, pnl)
+    }
+
+    /// 테스트 종료 시 보유 중인 포지션을 현재가로 청산
+    pub fn close_at_end(&mut self, current_price: f64, current_date: &str) {
+        let trade_count = self.trades.len();
+        self.handle_signal(&Signal::Sell(SignalReason {
+            reason: "End of test".to_string(),
+        }), current_price, current_date);
+        for trade in self.trades.iter_mut().skip(trade_count) {
+            trade.exit_reason = ExitReason::EndOfTest;
+            trade.sell_reason = None;
+        }
+    }
+
+    /// 거래 기록과 자산 곡선으로 성과 지표 계산
+    pub fn report(&self) -> BacktestReport {
+        BacktestReport::new(INITIAL_ASSET, &self.trades, &self.equity_curve)
+    }
+
+    // price에 닿은 첫 번째 분할 익절 목표를 꺼냄
+    fn pop_reached_target(&mut self, price: f64) -> Option<TakeProfitTarget> {
+        match &mut self.position {
+            PositionState::InPosition { take_profit_targets, .. }
+                if take_profit_targets.first().is_some_and(|target| target.price <= price) => Some(take_profit_targets.remove(0)),
+            _ => None,
+        }
+    }
     
     /// 매 프레임마다 현재 가격을 체크하여 포지션을 청산할 지 결정
+    ///
+    /// 분할 익절 목표는 목표가에 일부 청산되고, 익절은 목표가에 체결되며,
+    /// 트레일링 스탑은 가격이 스탑을 뚫고 내려간 경우 현재가에 체결됨
     pub fn check_and_close_position(&mut self, current_price: f64, current_date: &str) {
-        if let PositionState::InPosition { entry_price, entry_asset, take_profit_price, trailing_stop_price } = self.position {
-            let mut pnl_pct = 0.0; // 손익률
-            let mut closed = false;
+        self.update_lots(current_price, current_price);
 
+        // 분할 익절
+        while let Some(target) = self.pop_reached_target(current_price) {
+            self.reduce_position(target.pct, target.price, ExitReason::PartialTakeProfit, None, current_price, current_date);
+        }
+
+        if let PositionState::InPosition { take_profit_price, trailing_stop_price, .. } = self.position {
             // 익절 조건
             if current_price >= take_profit_price {
-                pnl_pct = (take_profit_price / entry_price - 1.0) - self.params.fees_pct * 2.0;
-                closed = true;
+                self.close_position(take_profit_price, ExitReason::TakeProfit, current_price, current_date);
             }
-            
             // 트레일링 스탑
             else if current_price <= trailing_stop_price {
-                let exit_price = trailing_stop_price;
-                pnl_pct = (exit_price / entry_price - 1.0) - self.params.fees_pct * 2.0;
-                closed = true;
+                self.close_position(current_price, ExitReason::TrailingStop, current_price, current_date);
             }
+        }
+        self.record_equity(current_price, current_date);
+    }
 
-            if closed {
-                self.current_asset += entry_asset * (1.0 + pnl_pct);
-                self.total_pnl_pct = (self.current_asset / INITIAL_ASSET) - 1.0;
-                self.position = PositionState::None; // 포지션 청산
-                if pnl_pct > 0.0 {
-                    self.win_count += 1;
+    /// 캔들의 고가/저가로 봉 안에서 분할 익절/익절/트레일링 스탑에 닿았는지 확인하여 청산
+    ///
+    /// 한 봉에서 익절 쪽과 스탑이 모두 닿은 경우 policy로 결정하며, `IntrabarPolicy::LowerTimeframe`이면 lower_candles (해당 봉 구간의 하위 타임프레임 캔들)를 사용
+    pub fn check_and_close_position_with_candle(&mut self, candle: &Candle, policy: IntrabarPolicy, lower_candles: &[Candle]) {
+        let current_date = candle.get_candle_date_time_utc().to_string();
+        let current_price = candle.get_trade_price();
+        self.update_lots(candle.get_high_price(), candle.get_low_price());
+
+        // 가까운 분할 익절 목표부터 하나씩 처리. 목표가 없으면 익절가
+        while let PositionState::InPosition { take_profit_price, trailing_stop_price, take_profit_targets, .. } = &self.position {
+            let (take_profit_price, trailing_stop_price) = (*take_profit_price, *trailing_stop_price);
+            let target = take_profit_targets.first().copied().filter(|target| target.price < take_profit_price);
+            let level = target.map(|target| target.price).unwrap_or(take_profit_price);
+
+            match (resolve_intrabar_exit(candle, level, trailing_stop_price, policy, lower_candles), target) {
+                (Some(fill), Some(target)) if fill.exit_reason == ExitReason::TakeProfit => {
+                    self.pop_reached_target(target.price);
+                    self.reduce_position(target.pct, fill.price, ExitReason::PartialTakeProfit, None, current_price, &current_date);
+                }
+                (Some(fill), _) => {
+                    self.close_position(fill.price, fill.exit_reason, current_price, &current_date);
+                    break;
+                }
+                (None, _) => break,
+            }
+        }
+        self.record_equity(current_price, &current_date);
+    }
+
+    /// 남은 포지션의 pct만큼 청산 (분할 익절, 전략의 부분 청산)
+    ///
+    /// 분할 익절은 지정가 (maker) 주문으로 보고 슬리피지 없이 체결, 그 외는 시장가 (taker) 주문으로 체결
+    fn reduce_position(&mut self, pct: f64, exit_price: f64, exit_reason: ExitReason, sell_reason: Option<String>,
+        current_price: f64, current_date: &str) {
+        let PositionState::InPosition { entry_price, .. } = self.position else { return };
+        let (exit_price, exit_liquidity) = match exit_reason {
+            ExitReason::PartialTakeProfit => (exit_price, Liquidity::Maker),
+            _ => (self.market_fill_price(OrderSide::Ask, exit_price, self.position.size() * pct), Liquidity::Taker),
+        };
+        let exit = ExitFill { price: exit_price, liquidity: exit_liquidity, reason: exit_reason, sell_reason: sell_reason.clone(), date: current_date };
+        let (exited_asset, pnl) = self.exit_lots(pct, &exit);
+        if exited_asset <= 0.0 {
+            return;
+        }
+        let pnl_pct = pnl / exited_asset;
+        let reason = sell_reason.unwrap_or_else(|| exit_reason.to_string());
+
+        if self.params.enable_trade_log {
+            println!("\x1b[36m[부분 청산] {} - 날짜: {}, 평균 진입가: {:.4}, 체결가: {:.4}, 청산 비율: {:.2}%, 실현 손익: {:.4}%, 이유: {}\x1b[0m",
+                    self.params.code, current_date, entry_price, exit_price, pct.min(1.0) * 100.0, pnl_pct * 100.0, reason);
+        }
+
+        // 웹훅 로그가 활성화된 경우 매도 신호 전송
+        if self.params.enable_webhook_log {
+            let code = self.params.code.clone();
+            let strategy_name = self.params.strategy_name.clone();
+            spawn(async move {
+                let _ = webhook_lib::send_sell_signal(
+                    &code,
+                    current_price,
+                    exited_asset,
+                    pnl,
+                    pnl_pct * 100.0,
+                    &strategy_name,
+                    &reason
+                ).await;
+            });
+        }
+    }
+
+    /// 익절/트레일링 스탑으로 남은 포지션 전체 청산
+    ///
+    /// 익절은 지정가 (maker) 주문으로 보고 슬리피지 없이 체결, 트레일링 스탑은 시장가 (taker) 주문으로 체결
+    fn close_position(&mut self, exit_price: f64, exit_reason: ExitReason, current_price: f64, current_date: &str) {
+        if let PositionState::InPosition { entry_price, take_profit_price, trailing_stop_price, .. } = self.position {
+            let (exit_price, exit_liquidity) = match exit_reason {
+                ExitReason::TakeProfit => (exit_price, Liquidity::Maker),
+                _ => (self.market_fill_price(OrderSide::Ask, exit_price, self.position.size()), Liquidity::Taker),
+            };
+            let exit = ExitFill { price: exit_price, liquidity: exit_liquidity, reason: exit_reason, sell_reason: None, date: current_date };
+            let (entry_asset, pnl) = self.exit_lots(1.0, &exit);
+            let pnl_pct = if entry_asset > 0.0 { pnl / entry_asset } else { 0.0 }; // 손익률
+
+            if pnl_pct > 0.0 {
+                if self.params.enable_trade_log {
                     println!("\x1b[32m[익절] {} - 날짜: {}, 진입가: {:.4}, 목표가: {:.4}, 현재가: {:.4}, 수익률: {:.4}%\x1b[0m", 
                             self.params.code,
                             current_date,
                             entry_price, take_profit_price, current_price, pnl_pct * 100.0);
-                    
-                                    // 웹훅 로그가 활성화된 경우 매도 신호 전송
+                }
+            
+                // 웹훅 로그가 활성화된 경우 매도 신호 전송
                 if self.params.enable_webhook_log {
                     let code = self.params.code.clone();
                     let strategy_name = self.params.strategy_name.clone();
@@ -119,95 +434,163 @@ impl BacktesterState {
                         ).await;
                     });
                 }
-                } else {
-                    self.loss_count += 1;
+            } else {
+                if self.params.enable_trade_log {
                     println!("\x1b[31m[손절] {} - 날짜: {}, 진입가: {:.4}, 트레일링스탑: {:.4}, 현재가: {:.4}, 손실률: {:.4}%\x1b[0m", 
                             self.params.code,
                             current_date,
                             entry_price, trailing_stop_price, current_price, pnl_pct * 100.0);
-                    
-                    // 웹훅 로그가 활성화된 경우 매도 신호 전송
+                }
+            
+                // 웹훅 로그가 활성화된 경우 매도 신호 전송
+                if self.params.enable_webhook_log {
+                    let code = self.params.code.clone();
+                    let strategy_name = self.params.strategy_name.clone();
+                    let current_price = current_price;
+                    let entry_asset = entry_asset;
+                    let pnl_pct = pnl_pct;
+                    spawn(async move {
+                        let _ = webhook_lib::send_sell_signal(
+                            &code,
+                            current_price,
+                            entry_asset,
+                            entry_asset * pnl_pct,
+                            pnl_pct * 100.0,
+                            &strategy_name,
+                            "손절"
+                        ).await;
+                    });
+                }
+            }
+            if self.params.enable_trade_log {
+                self.print_results(); // 중간 결과 출력
+            }
+        }
+    }
+
+    // 진입 신호의 asset_pct를 params.sizing으로 다시 계산
+    fn size_signal(&self, signal: Signal, current_price: f64) -> Signal {
+        if self.params.sizing == PositionSizing::SignalPct {
+            return signal;
+        }
+        let returns = self.trades.iter().map(|trade| trade.pnl_pct).collect::<Vec<f64>>();
+        let context = SizingContext {
+            equity: self.equity(current_price),
+            cash: self.current_asset,
+            price: current_price,
+            candles: &self.market.candles,
+            returns: &returns,
+        };
+        self.params.sizing.apply(&signal, &self.position, &context)
+    }
+
+    // 리스크 관리자로 진입 신호를 검사하고 줄이거나 거절한 이유를 출력
+    fn check_risk(&mut self, signal: Signal, current_price: f64, current_date: &str) -> Signal {
+        let equity = self.equity(current_price);
+        let Some(risk) = &mut self.risk else { return signal };
+        let (checked, decision) = risk.apply(&signal, &self.position, equity, self.current_asset, current_price, current_date);
+        if self.params.enable_trade_log {
+            match decision {
+                RiskDecision::Allow => {}
+                RiskDecision::Resize { asset_pct, reason } => {
+                    println!("\x1b[33m[진입 축소] {} - 날짜: {}, 비율: {:.2}%, 이유: {}\x1b[0m", self.params.code, current_date, asset_pct * 100.0, reason);
+                }
+                RiskDecision::Reject(reason) => {
+                    println!("\x1b[33m[진입 거절] {} - 날짜: {}, 이유: {}\x1b[0m", self.params.code, current_date, reason);
+                }
+            }
+        }
+        checked
+    }
+
+    /// 전략 신호에 따라 포지션을 관리 (진입, 추가 진입, 부분 청산, 청산)
+    ///
+    /// 신호의 익절가/스탑은 `params.market_rules`의 호가 단위에 맞춰 사용하고,
+    /// 진입 크기는 `params.sizing`으로 정한 뒤 `risk`가 있으면 리스크 한도에 맞게 줄이거나 거절함
+    pub fn handle_signal(&mut self, signal: &Signal, current_price: f64, current_date: &str) {
+        let signal = self.params.market_rules.align_signal(signal);
+        let signal = self.size_signal(signal, current_price);
+        let signal = &self.check_risk(signal, current_price, current_date);
+        let in_position = matches!(self.position, PositionState::InPosition { .. });
+
+        match signal {
+            Signal::Buy { reason, initial_trailing_stop, take_profit, asset_pct } if !in_position => {
+                if let Some(lot) = self.open_lot(reason, *asset_pct, current_price, current_date) {
+                    let trailing_stop_price = *initial_trailing_stop;
+                    let entry_price = lot.entry_price;
+
+                    self.position = PositionState::InPosition {
+                        entry_price,
+                        entry_asset: lot.entry_asset,
+                        take_profit_price: *take_profit,
+                        trailing_stop_price,
+                        lots: vec![lot],
+                        take_profit_targets: Vec::new(),
+                    };
+
+                    if self.params.enable_trade_log {
+                        println!("\x1b[34m[진입] {} - 날짜: {}, 가격: {:.4}, 목표가: {:.4}, 트레일링스탑: {:.4}, 이유: {}\x1b[0m", 
+                                self.params.code, current_date, entry_price, take_profit, trailing_stop_price, reason);
+                    }
+                
+                    // 웹훅 로그가 활성화된 경우 매수 신호 전송
                     if self.params.enable_webhook_log {
                         let code = self.params.code.clone();
                         let strategy_name = self.params.strategy_name.clone();
-                        let current_price = current_price;
-                        let entry_asset = entry_asset;
-                        let pnl_pct = pnl_pct;
+                        let current_price = entry_price;
+                        let asset_amount = self.current_asset * asset_pct;
+                        let trailing_stop_price = trailing_stop_price;
+                        let take_profit = *take_profit;
+                        let rr_ratio = (take_profit - current_price) / (current_price - trailing_stop_price);
                         spawn(async move {
-                            let _ = webhook_lib::send_sell_signal(
+                            let _ = webhook_lib::send_buy_signal(
                                 &code,
                                 current_price,
-                                entry_asset,
-                                entry_asset * pnl_pct,
-                                pnl_pct * 100.0,
-                                &strategy_name,
-                                "손절"
+                                asset_amount,
+                                trailing_stop_price,
+                                take_profit,
+                                rr_ratio,
+                                &strategy_name
                             ).await;
                         });
                     }
                 }
-                self.print_results(); // 중간 결과 출력
-            } else {
-                // 포지션 상태 갱신 (트레일링 스탑 가격 반영)
-                self.position = PositionState::InPosition {
-                    entry_price,
-                    entry_asset,
-                    take_profit_price,
-                    trailing_stop_price,
-                };
             }
-        }
-    }
 
-    /// 전략 신호에 따라 포지션을 관리 (진입 또는 청산)
-    pub fn handle_signal(&mut self, signal: &Signal, current_price: f64, current_date: &str) {
-        if let PositionState::None = self.position {
-            if let Signal::Buy { reason, initial_trailing_stop, take_profit, asset_pct } = signal {
-                let trailing_stop_price = *initial_trailing_stop;
-
-                self.position = PositionState::InPosition {
-                    entry_price: current_price,
-                    entry_asset: self.current_asset * asset_pct,
-                    take_profit_price: *take_profit,
-                    trailing_stop_price,
-                };
-
-                self.current_asset -= self.current_asset * asset_pct;
-
-                println!("\x1b[34m[진입] {} - 날짜: {}, 가격: {:.4}, 목표가: {:.4}, 트레일링스탑: {:.4}, 이유: {}\x1b[0m", 
-                        self.params.code, current_date, current_price, take_profit, trailing_stop_price, reason);
-                
-                // 웹훅 로그가 활성화된 경우 매수 신호 전송
-                if self.params.enable_webhook_log {
-                    let code = self.params.code.clone();
-                    let strategy_name = self.params.strategy_name.clone();
-                    let current_price = current_price;
-                    let asset_amount = self.current_asset * asset_pct;
-                    let trailing_stop_price = trailing_stop_price;
-                    let take_profit = *take_profit;
-                    let rr_ratio = (take_profit - current_price) / (current_price - trailing_stop_price);
-                    spawn(async move {
-                        let _ = webhook_lib::send_buy_signal(
-                            &code,
-                            current_price,
-                            asset_amount,
-                            trailing_stop_price,
-                            take_profit,
-                            rr_ratio,
-                            &strategy_name
-                        ).await;
-                    });
+            Signal::AddToPosition { reason, asset_pct } if in_position => {
+                if let Some(lot) = self.open_lot(reason, *asset_pct, current_price, current_date) {
+                    let lot_price = lot.entry_price;
+                    if let PositionState::InPosition { lots, .. } = &mut self.position {
+                        lots.push(lot);
+                    }
+                    self.position.recalculate();
+                    if let PositionState::InPosition { entry_price, .. } = self.position {
+                        if self.params.enable_trade_log {
+                            println!("\x1b[34m[추가 진입] {} - 날짜: {}, 가격: {:.4}, 평균 진입가: {:.4}, 이유: {}\x1b[0m",
+                                    self.params.code, current_date, lot_price, entry_price, reason);
+                        }
+                    }
                 }
             }
-        }
 
-        // 포지션이 있을 때, 매도 신호를 받으면 청산
-        if let PositionState::InPosition { entry_price, entry_asset, .. } = self.position {
-            if let Signal::Sell(reason) = signal {
-                let pnl_pct = (current_price / entry_price - 1.0) - self.params.fees_pct * 2.0;
-                println!("\x1b[35m[전략 매도] {} - 날짜: {}, 진입가: {:.4}, 현재가: {:.4}, 실현 손익: {:.4}%, 이유: {}\x1b[0m", 
-                        self.params.code, current_date,
-                        entry_price, current_price, pnl_pct * 100.0, reason.reason);
+            Signal::ReducePosition { reason, pct } if in_position => {
+                self.reduce_position(*pct, current_price, ExitReason::ReducePosition, Some(reason.clone()), current_price, current_date);
+            }
+
+            // 포지션이 있을 때, 매도 신호를 받으면 청산
+            Signal::Sell(reason) if in_position => {
+                let PositionState::InPosition { entry_price, .. } = self.position else { return };
+                let exit_price = self.market_fill_price(OrderSide::Ask, current_price, self.position.size());
+                let exit = ExitFill {
+                    price: exit_price, liquidity: Liquidity::Taker, reason: ExitReason::StrategySell, sell_reason: Some(reason.reason.clone()), date: current_date,
+                };
+                let (entry_asset, pnl) = self.exit_lots(1.0, &exit);
+                let pnl_pct = if entry_asset > 0.0 { pnl / entry_asset } else { 0.0 };
+                if self.params.enable_trade_log {
+                    println!("\x1b[35m[전략 매도] {} - 날짜: {}, 진입가: {:.4}, 체결가: {:.4}, 실현 손익: {:.4}%, 이유: {}\x1b[0m", 
+                            self.params.code, current_date,
+                            entry_price, exit_price, pnl_pct * 100.0, reason.reason);
+                }
                 
                 // 웹훅 로그가 활성화된 경우 매도 신호 전송
                 if self.params.enable_webhook_log {
@@ -229,29 +612,27 @@ impl BacktesterState {
                         ).await;
                     });
                 }
-                
-                if pnl_pct > 0.0 {
-                    self.win_count += 1;
-                } else {
-                    self.loss_count += 1;
-                }
-
-                self.current_asset += entry_asset * (1.0 + pnl_pct);
-                self.total_pnl_pct = (self.current_asset / INITIAL_ASSET) - 1.0;
-                self.position = PositionState::None; // 포지션 청산
                 // self.print_results(); // 중간 결과 출력
             }
-            
-            if let Signal::UpdateTrailingStop(new_trailing_stop) = signal {
-                if let PositionState::InPosition { entry_price, entry_asset, take_profit_price, trailing_stop_price: _ } = self.position {
-                    self.position = PositionState::InPosition {
-                        entry_price,
-                        entry_asset,
-                        take_profit_price,
-                        trailing_stop_price: *new_trailing_stop,
-                    };
+
+            Signal::UpdateTrailingStop(new_trailing_stop) => {
+                if let PositionState::InPosition { trailing_stop_price, .. } = &mut self.position {
+                    *trailing_stop_price = *new_trailing_stop;
+                }
+            }
+
+            Signal::SetTakeProfitTargets(targets) => {
+                if let PositionState::InPosition { take_profit_targets, .. } = &mut self.position {
+                    *take_profit_targets = targets.clone();
+                    take_profit_targets.sort_by(|a, b| a.price.total_cmp(&b.price));
                 }
             }
+
+            _ => {}
+        }
+
+        if !matches!(signal, Signal::Hold) {
+            self.record_equity(current_price, current_date);
         }
     }
 
@@ -261,6 +642,7 @@ impl BacktesterState {
         if total_trades == 0 { return; }
 
         let win_rate = (self.win_count as f64 / total_trades as f64) * 100.0;
+        let report = self.report();
         
         println!("--------------------------------------------------");
         println!(" [백테스팅 결과] - {}", self.params.code);
@@ -268,6 +650,7 @@ impl BacktesterState {
         println!(" > 총 거래: {} 회 (승: {}, 패: {})", total_trades, self.win_count, self.loss_count);
         println!(" > 승률: {:.2}%", win_rate);
         println!(" > 총 누적 손익률: {:.4}%", self.total_pnl_pct * 100.0);
+        report.print();
         println!("--------------------------------------------------");
         
         // 웹훅 로그가 활성화된 경우 거래 요약 전송
@@ -279,6 +662,7 @@ impl BacktesterState {
             let total_pnl_pct = self.total_pnl_pct;
             let win_rate = (win_count as f64 / total_trades as f64) * 100.0;
             let avg_profit = if total_trades > 0 { total_pnl_pct / total_trades as f64 } else { 0.0 };
+            let max_drawdown = report.max_drawdown_pct * 100.0;
             
             spawn(async move {
                 let _ = webhook_lib::send_trade_summary(
@@ -288,13 +672,50 @@ impl BacktesterState {
                     current_asset - INITIAL_ASSET,
                     win_rate,
                     avg_profit,
-                    0.0 // max_drawdown은 별도 계산 필요
+                    max_drawdown
                 ).await;
             });
         }
     }
 }
 
+// 두 날짜가 자산 곡선의 같은 구간 (분)에 속하는지. 날짜를 읽을 수 없으면 같은 문자열일 때만 같은 구간
+fn same_equity_interval(a: &str, b: &str) -> bool {
+    match (parse_backtest_date(a), parse_backtest_date(b)) {
+        (Some(a), Some(b)) => a.div_euclid(EQUITY_INTERVAL_MS) == b.div_euclid(EQUITY_INTERVAL_MS),
+        _ => a == b,
+    }
+}
+
+// 같은 포지션의 청산 기록 두 개를 하나로 합침
+fn merge_trade(first: TradeRecord, second: TradeRecord) -> TradeRecord {
+    let entry_asset = first.entry_asset + second.entry_asset;
+    let size = first.size + second.size;
+    let pnl = first.pnl + second.pnl;
+    let entry_price = if size > 0.0 { entry_asset / size } else { first.entry_price };
+    let exit_price = if size > 0.0 { (first.exit_price * first.size + second.exit_price * second.size) / size } else { second.exit_price };
+    // 두 기록의 보유 중 최저가/최고가로 가격 범위를 다시 만들어 합친 진입가 기준으로 계산
+    let mut range = OpenTrade::new(&first.entry_date, &first.entry_reason, first.entry_price * (1.0 + first.mae_pct));
+    for price in [first.entry_price * (1.0 + first.mfe_pct), second.entry_price * (1.0 + second.mae_pct), second.entry_price * (1.0 + second.mfe_pct)] {
+        range.update(price);
+    }
+    TradeRecord {
+        entry_price,
+        exit_price,
+        entry_asset,
+        size,
+        fees: first.fees + second.fees,
+        pnl,
+        pnl_pct: if entry_asset > 0.0 { pnl / entry_asset } else { 0.0 },
+        mae_pct: range.mae_pct(entry_price),
+        mfe_pct: range.mfe_pct(entry_price),
+        exit_date: second.exit_date,
+        exit_reason: second.exit_reason,
+        sell_reason: second.sell_reason,
+        ..first
+    }
+}
+
 impl Add for BacktesterState {
     type Output = BacktesterState;
 
@@ -306,6 +727,11 @@ impl Add for BacktesterState {
             loss_count: self.loss_count + rhs.loss_count,
             total_pnl_pct: self.total_pnl_pct + rhs.total_pnl_pct,
             current_asset: self.current_asset + rhs.current_asset,
+            trades: [self.trades, rhs.trades].concat(),
+            equity_curve: Vec::new(), // 합산 시 자산 곡선은 시간이 맞지 않아 의미 없음
+            round_trip: None,
+            market: MarketContext::new(),
+            risk: self.risk,
         }
     }
 }
@@ -1,3 +1,18 @@
 pub mod lib;
 pub mod simulate;
-pub mod fetch;
\ No newline at end of file
+pub mod fetch;
+pub mod ledger;
+pub mod report;
+pub mod fill;
+pub mod fee;
+pub mod slippage;
+pub mod portfolio;
+pub mod optimize;
+pub mod walk_forward;
+pub mod monte_carlo;
+pub mod candle_store;
+pub mod paper;
+pub mod risk;
+pub mod sizing;
+pub mod state_store;
+pub mod reconcile;
\ No newline at end of file
@@ -0,0 +1,252 @@
+use std::{fs::File, io::{BufWriter, Write}, path::Path};
+
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use serde::Serialize;
+
+use crate::backtest::{ledger::TradeRecord, lib::INITIAL_ASSET};
+
+/// 거래 순서를 다시 만드는 방식
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Resampling {
+    /// 같은 거래를 순서만 섞음 (최종 자산은 같고 낙폭 분포만 달라짐)
+    Shuffle,
+    /// 거래를 중복 허용으로 같은 수만큼 다시 뽑음
+    Bootstrap,
+}
+
+/// 몬테카를로 분석 설정
+#[derive(Debug, Clone)]
+pub struct MonteCarloConfig {
+    pub iterations: usize,
+    pub resampling: Resampling,
+    pub seed: u64,
+    pub initial_asset: f64,
+    /// 진입마다 추가로 불리하게 체결될 수 있는 최대 슬리피지 비율. 0~값 사이에서 균등하게 뽑음 (0이면 사용 안 함)
+    pub slippage_noise_pct: f64,
+    /// 자산이 초기 자산의 이 비율 이하로 떨어지면 파산으로 봄
+    pub ruin_equity_pct: f64,
+}
+
+impl MonteCarloConfig {
+    pub fn new() -> Self {
+        Self {
+            iterations: 1000,
+            resampling: Resampling::Shuffle,
+            seed: 0,
+            initial_asset: INITIAL_ASSET,
+            slippage_noise_pct: 0.0,
+            ruin_equity_pct: 0.5,
+        }
+    }
+}
+
+impl Default for MonteCarloConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 값 분포의 백분위수
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+pub struct Distribution {
+    pub mean: f64,
+    pub min: f64,
+    pub p5: f64,
+    pub p25: f64,
+    pub p50: f64,
+    pub p75: f64,
+    pub p95: f64,
+    pub max: f64,
+}
+
+impl Distribution {
+    pub fn new(values: &[f64]) -> Self {
+        let mut sorted = values.to_vec();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        let mean = if sorted.is_empty() { 0.0 } else { sorted.iter().sum::<f64>() / sorted.len() as f64 };
+        Self {
+            mean,
+            min: percentile(&sorted, 0.0),
+            p5: percentile(&sorted, 0.05),
+            p25: percentile(&sorted, 0.25),
+            p50: percentile(&sorted, 0.5),
+            p75: percentile(&sorted, 0.75),
+            p95: percentile(&sorted, 0.95),
+            max: percentile(&sorted, 1.0),
+        }
+    }
+}
+
+/// 정렬된 값의 백분위수 (선형 보간). 값이 없으면 0
+pub fn percentile(sorted: &[f64], p: f64) -> f64 {
+    match sorted.len() {
+        0 => 0.0,
+        1 => sorted[0],
+        len => {
+            let rank = p.clamp(0.0, 1.0) * (len - 1) as f64;
+            let lower = rank.floor() as usize;
+            let upper = rank.ceil() as usize;
+            sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64)
+        }
+    }
+}
+
+/// 시뮬레이션 한 번의 결과
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+pub struct MonteCarloRun {
+    pub final_equity: f64,
+    pub max_drawdown_pct: f64,
+    pub ruined: bool,
+}
+
+/// 몬테카를로 분석 결과
+#[derive(Debug, Clone, Serialize)]
+pub struct MonteCarloReport {
+    pub iterations: usize,
+    pub trades: usize,
+    pub initial_asset: f64,
+    pub final_equity: Distribution,
+    pub total_return_pct: Distribution,
+    pub max_drawdown_pct: Distribution,
+    /// 자산이 ruin_equity_pct 이하로 떨어진 시뮬레이션 비율
+    pub ruin_probability: f64,
+    /// 거래 n개 후 자산의 분포 (equity_bands[0]은 시작 자산)
+    pub equity_bands: Vec<Distribution>,
+    pub runs: Vec<MonteCarloRun>,
+}
+
+impl MonteCarloReport {
+    pub fn print(&self) {
+        let print_distribution = |name: &str, d: &Distribution, scale: f64, unit: &str| {
+            println!(" > {}: 평균 {:.2}{unit}, 5% {:.2}{unit}, 25% {:.2}{unit}, 50% {:.2}{unit}, 75% {:.2}{unit}, 95% {:.2}{unit}",
+                name, d.mean * scale, d.p5 * scale, d.p25 * scale, d.p50 * scale, d.p75 * scale, d.p95 * scale);
+        };
+        println!("--------------------------------------------------");
+        println!(" [몬테카를로 분석 결과] - 시뮬레이션: {} 회, 거래: {} 개", self.iterations, self.trades);
+        print_distribution("최종 자산", &self.final_equity, 1.0, "");
+        print_distribution("총 수익률", &self.total_return_pct, 100.0, "%");
+        print_distribution("최대 낙폭", &self.max_drawdown_pct, 100.0, "%");
+        println!(" > 파산 확률: {:.2}%", self.ruin_probability * 100.0);
+        println!("--------------------------------------------------");
+    }
+
+    /// 지표별 백분위수 요약 CSV
+    pub fn write_summary_csv<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        writeln!(writer, "metric,mean,min,p5,p25,p50,p75,p95,max")?;
+        for (name, d) in [("final_equity", &self.final_equity), ("total_return_pct", &self.total_return_pct), ("max_drawdown_pct", &self.max_drawdown_pct)] {
+            writeln!(writer, "{},{},{},{},{},{},{},{},{}", name, d.mean, d.min, d.p5, d.p25, d.p50, d.p75, d.p95, d.max)?;
+        }
+        writeln!(writer, "ruin_probability,{},,,,,,,", self.ruin_probability)
+    }
+
+    /// 거래 수별 자산 백분위수 밴드 CSV
+    pub fn write_bands_csv<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        writeln!(writer, "trade,mean,p5,p25,p50,p75,p95")?;
+        for (trade, d) in self.equity_bands.iter().enumerate() {
+            writeln!(writer, "{},{},{},{},{},{},{}", trade, d.mean, d.p5, d.p25, d.p50, d.p75, d.p95)?;
+        }
+        Ok(())
+    }
+
+    /// 시뮬레이션별 결과 CSV
+    pub fn write_runs_csv<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        writeln!(writer, "run,final_equity,max_drawdown_pct,ruined")?;
+        for (index, run) in self.runs.iter().enumerate() {
+            writeln!(writer, "{},{},{},{}", index, run.final_equity, run.max_drawdown_pct, run.ruined)?;
+        }
+        Ok(())
+    }
+
+    pub fn save_summary_csv<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_summary_csv(&mut writer)?;
+        writer.flush()
+    }
+
+    pub fn save_bands_csv<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_bands_csv(&mut writer)?;
+        writer.flush()
+    }
+
+    pub fn save_runs_csv<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_runs_csv(&mut writer)?;
+        writer.flush()
+    }
+}
+
+// 원래 거래 순서 기준으로 각 거래가 당시 자산에 준 수익률과 자산 대비 진입 비중
+fn trade_returns(trades: &[TradeRecord], initial_asset: f64) -> Vec<(f64, f64)> {
+    let mut equity = initial_asset;
+    trades.iter().map(|trade| {
+        let equity_before = equity;
+        equity += trade.pnl;
+        if equity_before > 0.0 {
+            (trade.pnl / equity_before, trade.entry_asset / equity_before)
+        } else {
+            (0.0, 0.0)
+        }
+    }).collect()
+}
+
+/// 거래 기록을 섞거나 다시 뽑아 최종 자산, 최대 낙폭, 파산 확률 분포를 계산
+///
+/// 거래마다 원래 순서에서 당시 자산에 준 수익률을 구하고, 새 순서에서 그 수익률을 복리로 적용함.
+/// trades는 청산 순서로 정렬되어 있어야 함
+pub fn run_monte_carlo(trades: &[TradeRecord], config: &MonteCarloConfig) -> MonteCarloReport {
+    let returns = trade_returns(trades, config.initial_asset);
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let ruin_equity = config.initial_asset * config.ruin_equity_pct;
+
+    let mut runs = Vec::with_capacity(config.iterations);
+    let mut paths = vec![Vec::with_capacity(config.iterations); returns.len() + 1];
+    let mut order = (0..returns.len()).collect::<Vec<usize>>();
+
+    for _ in 0..config.iterations {
+        match config.resampling {
+            Resampling::Shuffle => order.shuffle(&mut rng),
+            Resampling::Bootstrap => {
+                for index in order.iter_mut() {
+                    *index = rng.random_range(0..returns.len());
+                }
+            }
+        }
+
+        let mut equity = config.initial_asset;
+        let mut peak = equity;
+        let mut max_drawdown_pct: f64 = 0.0;
+        let mut ruined = equity <= ruin_equity;
+        paths[0].push(equity);
+        for (step, &index) in order.iter().enumerate() {
+            let (trade_return, exposure) = returns[index];
+            // 진입가가 더 불리하게 체결된 만큼 진입 비중에 비례해 수익률 감소
+            let slippage = if config.slippage_noise_pct > 0.0 { rng.random_range(0.0..config.slippage_noise_pct) } else { 0.0 };
+            equity = (equity * (1.0 + trade_return - exposure * slippage)).max(0.0);
+            peak = peak.max(equity);
+            if peak > 0.0 {
+                max_drawdown_pct = max_drawdown_pct.max(1.0 - equity / peak);
+            }
+            ruined |= equity <= ruin_equity;
+            paths[step + 1].push(equity);
+        }
+        runs.push(MonteCarloRun { final_equity: equity, max_drawdown_pct, ruined });
+    }
+
+    let final_equity = runs.iter().map(|run| run.final_equity).collect::<Vec<f64>>();
+    let total_return_pct = final_equity.iter().map(|equity| equity / config.initial_asset - 1.0).collect::<Vec<f64>>();
+    let max_drawdown_pct = runs.iter().map(|run| run.max_drawdown_pct).collect::<Vec<f64>>();
+    let ruin_count = runs.iter().filter(|run| run.ruined).count();
+
+    MonteCarloReport {
+        iterations: config.iterations,
+        trades: trades.len(),
+        initial_asset: config.initial_asset,
+        final_equity: Distribution::new(&final_equity),
+        total_return_pct: Distribution::new(&total_return_pct),
+        max_drawdown_pct: Distribution::new(&max_drawdown_pct),
+        ruin_probability: if runs.is_empty() { 0.0 } else { ruin_count as f64 / runs.len() as f64 },
+        equity_bands: paths.iter().map(|values| Distribution::new(values)).collect(),
+        runs,
+    }
+}
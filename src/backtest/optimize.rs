@@ -0,0 +1,285 @@
+use std::{collections::HashSet, fmt, fs::File, io::{BufWriter, Write}, path::Path};
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rayon::prelude::*;
+
+use crate::{backtest::{lib::{BacktestParams, BacktesterState}, report::BacktestReport, simulate::run_candles}, core::candle::Candle,
+strategy::registry::{create_candle_strategy, StrategyParamError}};
+
+/// 파라미터 이름과 값 목록. 전략 기본 설정에서 이 값들만 바뀜
+pub type ParamSet = Vec<(String, f64)>;
+
+/// 최적화할 파라미터와 후보 값
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParamRange {
+    pub name: String,
+    pub values: Vec<f64>,
+}
+
+impl ParamRange {
+    pub fn new(name: &str, values: Vec<f64>) -> Self {
+        Self { name: name.to_string(), values }
+    }
+
+    /// start부터 end까지 step 간격의 값 (end 포함)
+    pub fn step(name: &str, start: f64, end: f64, step: f64) -> Self {
+        let mut values = Vec::new();
+        if step > 0.0 {
+            let count = ((end - start) / step + 1e-9).floor() as usize;
+            for i in 0..=count {
+                // 부동소수점 누적 오차 제거
+                values.push(((start + step * i as f64) * 1e9).round() / 1e9);
+            }
+        }
+        Self::new(name, values)
+    }
+}
+
+/// 파라미터 탐색 공간 (각 파라미터 후보 값의 모든 조합)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParamSpace {
+    pub params: Vec<ParamRange>,
+}
+
+impl ParamSpace {
+    pub fn new() -> Self {
+        Self { params: Vec::new() }
+    }
+
+    pub fn add(&mut self, range: ParamRange) -> &mut Self {
+        self.params.push(range);
+        self
+    }
+
+    /// 전체 조합 수. 후보 값이 없는 파라미터가 있으면 0
+    pub fn size(&self) -> usize {
+        self.params.iter().map(|range| range.values.len()).product()
+    }
+
+    /// index번째 조합 (첫 번째 파라미터가 가장 느리게 바뀜)
+    pub fn get(&self, index: usize) -> ParamSet {
+        let mut rest = index;
+        let mut set = self.params.iter().rev().map(|range| {
+            let len = range.values.len().max(1);
+            let value = range.values[rest % len];
+            rest /= len;
+            (range.name.clone(), value)
+        }).collect::<ParamSet>();
+        set.reverse();
+        set
+    }
+
+    /// 모든 조합
+    pub fn grid(&self) -> Vec<ParamSet> {
+        (0..self.size()).map(|index| self.get(index)).collect()
+    }
+
+    /// 중복 없이 무작위로 고른 count개 조합. count가 전체 조합 수 이상이면 전체 조합
+    pub fn sample<R: Rng>(&self, count: usize, rng: &mut R) -> Vec<ParamSet> {
+        let size = self.size();
+        if count >= size {
+            return self.grid();
+        }
+        let mut picked = HashSet::new();
+        let mut sets = Vec::with_capacity(count);
+        while sets.len() < count {
+            let index = rng.random_range(0..size);
+            if picked.insert(index) {
+                sets.push(self.get(index));
+            }
+        }
+        sets
+    }
+}
+
+/// 결과 순위를 정하는 기준
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Objective {
+    Sharpe,
+    Sortino,
+    ProfitFactor,
+    /// 총 수익률 / 최대 낙폭. 낙폭이 없으면 총 수익률
+    ReturnOverDrawdown,
+    TotalReturn,
+}
+
+impl Objective {
+    /// 클수록 좋은 점수
+    pub fn score(&self, report: &BacktestReport) -> f64 {
+        match self {
+            Objective::Sharpe => report.sharpe_ratio,
+            Objective::Sortino => report.sortino_ratio,
+            Objective::ProfitFactor => report.profit_factor,
+            Objective::ReturnOverDrawdown => {
+                if report.max_drawdown_pct > 0.0 { report.total_return_pct / report.max_drawdown_pct } else { report.total_return_pct }
+            }
+            Objective::TotalReturn => report.total_return_pct,
+        }
+    }
+}
+
+impl fmt::Display for Objective {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Objective::Sharpe => "sharpe",
+            Objective::Sortino => "sortino",
+            Objective::ProfitFactor => "profit_factor",
+            Objective::ReturnOverDrawdown => "return_over_drawdown",
+            Objective::TotalReturn => "total_return",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// 파라미터 최적화 설정
+#[derive(Debug, Clone)]
+pub struct OptimizeConfig {
+    pub strategy_name: String,
+    /// 모든 실행에 공통으로 사용할 백테스트 설정 (수수료, 슬리피지 등). 웹훅/거래 로그는 항상 끔
+    pub params: BacktestParams,
+    pub objective: Objective,
+    /// 거래 수가 이보다 적은 결과는 순위 맨 뒤로 보냄 (거래 1~2회로 점수가 튀는 조합 제외)
+    pub min_trades: u32,
+}
+
+impl OptimizeConfig {
+    pub fn new(code: &str, strategy_name: &str, objective: Objective) -> Self {
+        Self {
+            strategy_name: strategy_name.to_string(),
+            params: BacktestParams::default(code, strategy_name),
+            objective,
+            min_trades: 0,
+        }
+    }
+}
+
+/// 파라미터 조합 하나의 백테스트 결과
+#[derive(Debug, Clone)]
+pub struct OptimizeResult {
+    pub params: ParamSet,
+    pub report: BacktestReport,
+    /// 목표 점수. 거래 수가 min_trades 미만이면 f64::NEG_INFINITY
+    pub score: f64,
+}
+
+/// 최적화 결과 목록 (기본은 목표 점수 내림차순)
+#[derive(Debug, Clone)]
+pub struct OptimizeResults {
+    pub objective: Objective,
+    pub results: Vec<OptimizeResult>,
+}
+
+impl OptimizeResults {
+    pub fn new(objective: Objective, results: Vec<OptimizeResult>) -> Self {
+        let mut results = Self { objective, results };
+        results.sort_by(objective);
+        results
+    }
+
+    /// 가장 점수가 높은 결과
+    pub fn best(&self) -> Option<&OptimizeResult> {
+        self.results.first()
+    }
+
+    /// 다른 지표 기준 내림차순으로 다시 정렬. NaN은 맨 뒤
+    pub fn sort_by(&mut self, objective: Objective) {
+        let current = self.objective;
+        let key = |result: &OptimizeResult| {
+            let value = if objective == current { result.score } else { objective.score(&result.report) };
+            if value.is_nan() { f64::NEG_INFINITY } else { value }
+        };
+        self.results.sort_by(|a, b| key(b).total_cmp(&key(a)));
+    }
+
+    /// 상위 limit개 결과를 표로 출력
+    pub fn print_table(&self, limit: usize) {
+        let Some(first) = self.results.first() else { return };
+        let param_names = first.params.iter().map(|(name, _)| format!("{:>12}", truncate(name, 12))).collect::<Vec<String>>().join(" ");
+
+        println!("--------------------------------------------------");
+        println!(" [파라미터 최적화 결과] - 목표: {}, 조합: {} 개", self.objective, self.results.len());
+        println!(" {:>4} {} {:>10} {:>9} {:>8} {:>7} {:>7} {:>7} {:>6}",
+            "순위", param_names, "점수", "수익률", "MDD", "샤프", "PF", "승률", "거래");
+        for (rank, result) in self.results.iter().take(limit).enumerate() {
+            let values = result.params.iter().map(|(_, value)| format!("{:>12.4}", value)).collect::<Vec<String>>().join(" ");
+            let report = &result.report;
+            println!(" {:>4} {} {:>10.4} {:>8.2}% {:>7.2}% {:>7.2} {:>7.2} {:>6.2}% {:>6}",
+                rank + 1, values, result.score, report.total_return_pct * 100.0, report.max_drawdown_pct * 100.0,
+                report.sharpe_ratio, report.profit_factor, report.win_rate * 100.0, report.total_trades);
+        }
+        println!("--------------------------------------------------");
+    }
+
+    /// 파라미터 값과 주요 지표를 CSV로 작성 (현재 정렬 순서)
+    pub fn write_csv<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        let param_names = self.results.first()
+            .map(|result| result.params.iter().map(|(name, _)| format!("{},", name)).collect::<String>())
+            .unwrap_or_default();
+        writeln!(writer, "{}score,total_return_pct,max_drawdown_pct,sharpe_ratio,sortino_ratio,profit_factor,win_rate,total_trades", param_names)?;
+        for result in &self.results {
+            let values = result.params.iter().map(|(_, value)| format!("{},", value)).collect::<String>();
+            let report = &result.report;
+            writeln!(writer, "{}{},{},{},{},{},{},{},{}", values, result.score, report.total_return_pct, report.max_drawdown_pct,
+                report.sharpe_ratio, report.sortino_ratio, report.profit_factor, report.win_rate, report.total_trades)?;
+        }
+        Ok(())
+    }
+
+    pub fn save_csv<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_csv(&mut writer)?;
+        writer.flush()
+    }
+}
+
+fn truncate(value: &str, len: usize) -> &str {
+    match value.char_indices().nth(len) {
+        Some((index, _)) => &value[..index],
+        None => value,
+    }
+}
+
+/// 파라미터 조합 하나로 캔들 백테스트
+pub fn evaluate(candles: &[Candle], params: &ParamSet, config: &OptimizeConfig) -> Result<OptimizeResult, StrategyParamError> {
+    let backtester = run_with_params(&[], candles, params, config)?;
+    let report = backtester.report();
+    let score = if report.total_trades < config.min_trades { f64::NEG_INFINITY } else { config.objective.score(&report) };
+    Ok(OptimizeResult { params: params.clone(), report, score })
+}
+
+/// 파라미터를 바꾼 전략을 warm_up 캔들로 준비한 뒤 candles로 백테스트 (warm_up 구간에서는 거래하지 않음)
+pub fn run_with_params(warm_up: &[Candle], candles: &[Candle], params: &ParamSet, config: &OptimizeConfig) -> Result<BacktesterState, StrategyParamError> {
+    let mut strategy = create_candle_strategy(&config.strategy_name, params, false)?;
+    strategy.warm_up(warm_up);
+
+    let mut backtest_params = config.params.clone();
+    backtest_params.strategy_name = config.strategy_name.clone();
+    backtest_params.enable_webhook_log = false;
+    backtest_params.enable_trade_log = false;
+    let mut backtester = BacktesterState::new(backtest_params);
+    run_candles(candles, &[], strategy.as_mut(), &mut backtester);
+    Ok(backtester)
+}
+
+/// 주어진 조합들을 병렬로 백테스트하고 목표 점수 순으로 정렬
+pub fn optimize(candles: &[Candle], param_sets: &[ParamSet], config: &OptimizeConfig) -> Result<OptimizeResults, StrategyParamError> {
+    let results = param_sets.par_iter()
+        .map(|params| evaluate(candles, params, config))
+        .collect::<Result<Vec<OptimizeResult>, StrategyParamError>>()?;
+    Ok(OptimizeResults::new(config.objective, results))
+}
+
+/// 탐색 공간의 모든 조합을 백테스트
+///
+/// candles는 오래된 순서로 정렬되어 있어야 함
+pub fn grid_search(candles: &[Candle], space: &ParamSpace, config: &OptimizeConfig) -> Result<OptimizeResults, StrategyParamError> {
+    optimize(candles, &space.grid(), config)
+}
+
+/// 탐색 공간에서 무작위로 고른 count개 조합을 백테스트. 같은 seed면 같은 조합을 고름
+///
+/// candles는 오래된 순서로 정렬되어 있어야 함
+pub fn random_search(candles: &[Candle], space: &ParamSpace, count: usize, seed: u64, config: &OptimizeConfig) -> Result<OptimizeResults, StrategyParamError> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    optimize(candles, &space.sample(count, &mut rng), config)
+}
@@ -0,0 +1,890 @@
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::{backtest::{fee::FeeSchedule, risk::{RiskDecision, RiskManager}, sizing::{PositionSizing, SizingContext}, slippage::MarketContext, state_store::MarketState},
+core::{candle::{Candle, CandleTrait}, order_event::{Liquidity, OrderEvent}, orderbook::Orderbook, position::{Lot, OpenTrade, PositionState}, signal::Signal, ticker::Ticker, trade::{AskBid, Trade}},
+strategy::lib::Strategy,
+upbit_api::{account::Account, error::{UpbitError, UpbitErrorBody}, market_rules::{MarketRuleError, MarketRules},
+order::{Order, OrderKey, OrderRequest, OrderSide, OrderState, OrderTrade, OrderType, TimeInForce}}};
+
+// 남은 수량/금액이 이보다 작으면 전부 체결된 것으로 봄
+const EPSILON: f64 = 1e-9;
+
+// 호가에 올라가 있는 지정가 주문
+#[derive(Debug, Clone)]
+struct RestingOrder {
+    order: Order,
+    // 같은 가격에 먼저 대기 중인 수량 추정치. 이 수량이 체결된 뒤부터 주문이 체결됨
+    queue_ahead: f64,
+}
+
+/// 업비트 주문 API와 같은 주문을 받아 실시간 체결/호가로 체결하는 모의 거래소
+///
+/// - 시장가/최유리/즉시 체결 가능한 지정가 주문은 마지막 호가를 따라 taker로 체결
+/// - 남은 지정가 주문은 호가에 올려두고, 체결이 주문 가격을 뚫고 지나가거나
+///   같은 가격에서 앞선 대기 수량 (주문 시점 호가 잔량)보다 많이 체결된 경우에만 maker로 체결
+/// - 계좌는 `check_my_account`와 같은 형식으로 관리하며 주문 금액은 체결/취소 전까지 locked에 묶임
+#[derive(Debug, Clone)]
+pub struct PaperBroker {
+    accounts: BTreeMap<String, Account>,
+    open_orders: Vec<RestingOrder>,
+    closed_orders: Vec<Order>,
+    orderbooks: HashMap<String, Orderbook>,
+    last_prices: HashMap<String, f64>,
+    events: Vec<OrderEvent>,
+    // 마지막으로 받은 실시간 데이터 시간 (ms). 주문/체결 시간에 사용
+    now: i64,
+}
+
+impl PaperBroker {
+    /// currency 계좌에 balance를 넣은 상태로 시작
+    pub fn new(currency: &str, balance: f64) -> Self {
+        let mut broker = Self {
+            accounts: BTreeMap::new(),
+            open_orders: Vec::new(),
+            closed_orders: Vec::new(),
+            orderbooks: HashMap::new(),
+            last_prices: HashMap::new(),
+            events: Vec::new(),
+            now: 0,
+        };
+        broker.deposit(currency, balance);
+        broker
+    }
+
+    pub fn deposit(&mut self, currency: &str, amount: f64) {
+        self.account_mut(currency).balance += amount;
+    }
+
+    fn account_mut(&mut self, currency: &str) -> &mut Account {
+        self.accounts.entry(currency.to_string()).or_insert_with(|| Account {
+            currency: currency.to_string(),
+            balance: 0.0,
+            locked: 0.0,
+            avg_buy_price: 0.0,
+            avg_buy_price_modified: false,
+            unit_currency: "KRW".to_string(),
+        })
+    }
+
+    /// 잔고가 있는 계좌 목록 (`check_my_account`와 같은 형식)
+    pub fn accounts(&self) -> Vec<Account> {
+        self.accounts.values().filter(|account| account.balance + account.locked > 0.0).cloned().collect()
+    }
+
+    /// 주문 가능한 잔고 (locked 제외)
+    pub fn balance(&self, currency: &str) -> f64 {
+        self.accounts.get(currency).map(|account| account.balance).unwrap_or(0.0)
+    }
+
+    /// currency 기준 총 자산. 다른 통화는 `{currency}-{통화}` 마켓의 마지막 체결가 (없으면 평균 매수가)로 평가
+    pub fn equity(&self, currency: &str) -> f64 {
+        self.accounts.values().map(|account| {
+            let amount = account.balance + account.locked;
+            if account.currency == currency {
+                amount
+            } else {
+                let price = self.last_prices.get(&format!("{}-{}", currency, account.currency)).copied().unwrap_or(account.avg_buy_price);
+                amount * price
+            }
+        }).sum()
+    }
+
+    /// 마켓의 마지막 체결가
+    pub fn last_price(&self, market: &str) -> Option<f64> {
+        self.last_prices.get(market).copied()
+    }
+
+    /// 체결 대기 주문 (market이 None이면 전체)
+    pub fn open_orders(&self, market: Option<&str>) -> Vec<Order> {
+        self.open_orders.iter().map(|resting| &resting.order)
+            .filter(|order| market.is_none_or(|market| order.market == market))
+            .cloned()
+            .collect()
+    }
+
+    /// 체결 완료/취소된 주문 (market이 None이면 전체)
+    pub fn closed_orders(&self, market: Option<&str>) -> Vec<Order> {
+        self.closed_orders.iter()
+            .filter(|order| market.is_none_or(|market| order.market == market))
+            .cloned()
+            .collect()
+    }
+
+    /// 개별 주문 조회. 없으면 업비트와 같은 `order_not_found` 에러
+    pub fn get_order(&self, key: &OrderKey) -> Result<Order, UpbitError> {
+        self.open_orders.iter().map(|resting| &resting.order)
+            .chain(self.closed_orders.iter())
+            .find(|order| matches_key(order, key))
+            .cloned()
+            .ok_or_else(order_not_found)
+    }
+
+    /// 아직 가져가지 않은 market의 체결/취소 이벤트를 발생 순서로 꺼냄
+    pub fn take_events(&mut self, market: &str) -> Vec<OrderEvent> {
+        let (taken, kept) = std::mem::take(&mut self.events).into_iter().partition(|event| event.order().market == market);
+        self.events = kept;
+        taken
+    }
+
+    /// 주문하기 (`place_order`와 같은 규칙 확인)
+    ///
+    /// 즉시 체결 가능한 부분은 바로 체결하고, 반환하는 주문은 체결을 반영한 상태
+    pub fn place_order(&mut self, request: &OrderRequest) -> Result<Order, UpbitError> {
+        MarketRules::for_market(&request.market).validate_order(request)?;
+        if request.ord_type == OrderType::Best && request.time_in_force.is_none() {
+            return Err(MarketRuleError::MissingField("time_in_force").into());
+        }
+        let fees = FeeSchedule::for_market(&request.market);
+        let (quote, base) = split_market(&request.market);
+
+        // 매수는 주문 금액과 수수료, 매도는 수량을 묶어둠
+        let (currency, locked, reserved_fee) = match (request.side, request.ord_type) {
+            (OrderSide::Bid, OrderType::Limit) => {
+                let amount = request.price.unwrap_or(0.0) * request.volume.unwrap_or(0.0);
+                (quote, amount * (1.0 + fees.taker_pct), amount * fees.taker_pct)
+            }
+            (OrderSide::Bid, OrderType::Price | OrderType::Best) => {
+                let amount = request.price.ok_or(MarketRuleError::MissingField("price"))?;
+                (quote, amount * (1.0 + fees.taker_pct), amount * fees.taker_pct)
+            }
+            (OrderSide::Ask, OrderType::Limit | OrderType::Market | OrderType::Best) => {
+                let volume = request.volume.ok_or(MarketRuleError::MissingField("volume"))?;
+                (base, volume, request.price.filter(|_| request.ord_type == OrderType::Limit).unwrap_or(0.0) * volume * fees.taker_pct)
+            }
+            _ => return Err(api_error("invalid_ord_type", "주문 방향과 주문 타입이 맞지 않습니다.")),
+        };
+
+        // 시장가/최유리 주문은 체결할 가격이 있어야 함
+        let levels = self.levels(&request.market, request.side);
+        if request.ord_type != OrderType::Limit && levels.is_empty() {
+            return Err(api_error("market_not_ready", "체결할 호가 또는 체결가가 없습니다."));
+        }
+        if self.balance(&currency) < locked * (1.0 - EPSILON) {
+            let name = match request.side {
+                OrderSide::Bid => "insufficient_funds_bid",
+                OrderSide::Ask => "insufficient_funds_ask",
+            };
+            return Err(api_error(name, "주문가능한 금액이 부족합니다."));
+        }
+        let account = self.account_mut(&currency);
+        account.balance -= locked;
+        account.locked += locked;
+
+        let mut order = Order {
+            uuid: Uuid::new_v4().to_string(),
+            side: request.side,
+            ord_type: request.ord_type,
+            price: request.price,
+            state: OrderState::Wait,
+            market: request.market.clone(),
+            created_at: self.timestamp(),
+            volume: request.volume,
+            remaining_volume: request.volume,
+            reserved_fee,
+            remaining_fee: reserved_fee,
+            paid_fee: 0.0,
+            locked,
+            executed_volume: 0.0,
+            trades_count: 0,
+            time_in_force: request.time_in_force,
+            identifier: request.identifier.clone(),
+            trades: Vec::new(),
+        };
+
+        self.execute_on_placement(&mut order, levels);
+
+        if order.state == OrderState::Wait {
+            if order.ord_type == OrderType::Limit && order.time_in_force.is_none() {
+                let queue_ahead = self.orderbooks.get(&order.market)
+                    .and_then(|orderbook| level_size(orderbook, order.side, order.price.unwrap_or(0.0)))
+                    .unwrap_or(0.0);
+                self.open_orders.push(RestingOrder { order: order.clone(), queue_ahead });
+                return Ok(order);
+            }
+            // IOC, FOK, 최유리 주문의 남은 수량은 취소
+            self.close(&mut order, OrderState::Cancel);
+        }
+        self.closed_orders.push(order.clone());
+        Ok(order)
+    }
+
+    /// 체결 대기 주문 취소. 묶여 있던 남은 금액은 주문 가능 잔고로 돌아감
+    pub fn cancel_order(&mut self, key: &OrderKey) -> Result<Order, UpbitError> {
+        let Some(index) = self.open_orders.iter().position(|resting| matches_key(&resting.order, key)) else {
+            return match self.closed_orders.iter().any(|order| matches_key(order, key)) {
+                true => Err(api_error("order_not_cancellable", "취소할 수 없는 주문입니다.")),
+                false => Err(order_not_found()),
+            };
+        };
+        let mut order = self.open_orders.remove(index).order;
+        self.close(&mut order, OrderState::Cancel);
+        self.closed_orders.push(order.clone());
+        Ok(order)
+    }
+
+    /// 호가 갱신. 대기 주문 앞의 수량은 호가 잔량보다 많을 수 없으므로 잔량이 줄면 함께 줄임
+    pub fn on_orderbook(&mut self, orderbook: &Orderbook) {
+        self.now = self.now.max(orderbook.timestamp);
+        for resting in self.open_orders.iter_mut().filter(|resting| resting.order.market == orderbook.code) {
+            let price = resting.order.price.unwrap_or(0.0);
+            if let Some(size) = level_size(orderbook, resting.order.side, price) {
+                resting.queue_ahead = resting.queue_ahead.min(size);
+            } else if is_inside_book(orderbook, resting.order.side, price) {
+                // 호가 범위 안인데 가격이 없으면 앞선 주문이 모두 빠진 것
+                resting.queue_ahead = 0.0;
+            }
+        }
+        self.orderbooks.insert(orderbook.code.clone(), orderbook.clone());
+    }
+
+    /// 체결 반영. 대기 중인 지정가 주문 중 체결 조건을 만족하는 주문을 주문 가격에 체결
+    ///
+    /// - 체결가가 주문 가격을 지나간 경우 (매수: 더 낮음, 매도: 더 높음) 남은 수량 전부 체결
+    /// - 같은 가격에서 반대 방향 주문이 체결된 경우 앞선 대기 수량을 먼저 소진한 뒤 남은 체결량만큼 체결
+    pub fn on_trade(&mut self, trade: &Trade) {
+        self.now = self.now.max(trade.trade_timestamp);
+        self.last_prices.insert(trade.code.clone(), trade.trade_price);
+
+        // 같은 가격의 내 주문들이 나눠 가질 수 있는 체결량
+        let mut at_price_volume = trade.trade_volume;
+        let mut index = 0;
+        while index < self.open_orders.len() {
+            let resting = &mut self.open_orders[index];
+            if resting.order.market != trade.code {
+                index += 1;
+                continue;
+            }
+            let price = resting.order.price.unwrap_or(0.0);
+            let remaining = resting.order.remaining_volume.unwrap_or(0.0);
+            let (through, at_price) = match resting.order.side {
+                OrderSide::Bid => (trade.trade_price < price, same_price(trade.trade_price, price) && trade.ask_bid == AskBid::Ask),
+                OrderSide::Ask => (trade.trade_price > price, same_price(trade.trade_price, price) && trade.ask_bid == AskBid::Bid),
+            };
+            let volume = if through {
+                remaining
+            } else if at_price {
+                let available = (at_price_volume - resting.queue_ahead).max(0.0);
+                resting.queue_ahead = (resting.queue_ahead - at_price_volume).max(0.0);
+                let volume = available.min(remaining);
+                at_price_volume -= volume;
+                volume
+            } else {
+                0.0
+            };
+            if volume <= 0.0 {
+                index += 1;
+                continue;
+            }
+
+            let mut order = self.open_orders.remove(index).order;
+            self.fill(&mut order, price, volume, Liquidity::Maker);
+            if order.state == OrderState::Wait {
+                // 같은 가격에서 일부 체결됐으면 앞선 대기 수량은 이미 소진됨
+                self.open_orders.insert(index, RestingOrder { order, queue_ahead: 0.0 });
+                index += 1;
+            } else {
+                self.closed_orders.push(order);
+            }
+        }
+    }
+
+    // 반대 방향 호가 (가격, 잔량). 호가가 없으면 마지막 체결가에 잔량 제한 없이 체결된다고 봄
+    fn levels(&self, market: &str, side: OrderSide) -> Vec<(f64, f64)> {
+        let levels = self.orderbooks.get(market).map(|orderbook| orderbook.orderbook_units.iter().map(|unit| match side {
+            OrderSide::Bid => (unit.ask_price, unit.ask_size),
+            OrderSide::Ask => (unit.bid_price, unit.bid_size),
+        }).filter(|(price, size)| *price > 0.0 && *size > 0.0).collect::<Vec<(f64, f64)>>()).unwrap_or_default();
+        match (levels.is_empty(), self.last_prices.get(market)) {
+            (true, Some(price)) => vec![(*price, f64::INFINITY)],
+            _ => levels,
+        }
+    }
+
+    // 주문 즉시 체결 가능한 부분을 호가를 따라 체결
+    fn execute_on_placement(&mut self, order: &mut Order, levels: Vec<(f64, f64)>) {
+        let limit_price = order.price.unwrap_or(0.0);
+        let mut levels = match order.ord_type {
+            OrderType::Limit => levels.into_iter().filter(|(price, _)| match order.side {
+                OrderSide::Bid => *price <= limit_price,
+                OrderSide::Ask => *price >= limit_price,
+            }).collect::<Vec<(f64, f64)>>(),
+            // 최유리 주문은 최우선 호가에서만 체결
+            OrderType::Best => levels.into_iter().take(1).collect(),
+            // 시장가 주문은 호가 잔량이 부족하면 남은 수량을 마지막 호가에 체결
+            OrderType::Price | OrderType::Market => {
+                let mut levels = levels;
+                if let Some(last) = levels.last_mut() {
+                    last.1 = f64::INFINITY;
+                }
+                levels
+            }
+        };
+
+        // 금액 기준 주문 (시장가/최유리 매수)은 금액, 그 외는 수량으로 체결 가능 여부 확인
+        let by_funds = order.volume.is_none();
+        let target = if by_funds { limit_price } else { order.remaining_volume.unwrap_or(0.0) };
+        if order.time_in_force == Some(TimeInForce::Fok) {
+            let available = levels.iter().map(|(price, size)| if by_funds { price * size } else { *size }).sum::<f64>();
+            if available < target * (1.0 - EPSILON) {
+                return;
+            }
+        }
+
+        let mut remaining = target;
+        for (price, size) in levels.iter_mut() {
+            if remaining <= target * EPSILON {
+                break;
+            }
+            let volume = if by_funds { (remaining / *price).min(*size) } else { remaining.min(*size) };
+            if volume <= 0.0 {
+                continue;
+            }
+            remaining -= if by_funds { volume * *price } else { volume };
+            *size -= volume;
+            self.fill(order, *price, volume, Liquidity::Taker);
+        }
+    }
+
+    // 주문 일부 체결. 계좌와 주문 상태를 갱신하고 이벤트를 남김
+    fn fill(&mut self, order: &mut Order, price: f64, volume: f64, liquidity: Liquidity) {
+        let (quote, base) = split_market(&order.market);
+        let funds = price * volume;
+        let fee = funds * FeeSchedule::for_market(&order.market).fee_pct(liquidity);
+
+        match order.side {
+            OrderSide::Bid => {
+                let quote_account = self.account_mut(&quote);
+                quote_account.locked = (quote_account.locked - funds - fee).max(0.0);
+                order.locked = (order.locked - funds - fee).max(0.0);
+                let base_account = self.account_mut(&base);
+                let held = base_account.balance + base_account.locked;
+                base_account.avg_buy_price = (held * base_account.avg_buy_price + funds) / (held + volume);
+                base_account.balance += volume;
+            }
+            OrderSide::Ask => {
+                let base_account = self.account_mut(&base);
+                base_account.locked = (base_account.locked - volume).max(0.0);
+                order.locked = (order.locked - volume).max(0.0);
+                self.account_mut(&quote).balance += funds - fee;
+            }
+        }
+
+        let trade = OrderTrade {
+            market: order.market.clone(),
+            uuid: Uuid::new_v4().to_string(),
+            price,
+            volume,
+            funds,
+            side: order.side,
+            created_at: self.timestamp(),
+        };
+        order.executed_volume += volume;
+        order.remaining_volume = order.remaining_volume.map(|remaining| (remaining - volume).max(0.0));
+        order.paid_fee += fee;
+        order.remaining_fee = (order.reserved_fee - order.paid_fee).max(0.0);
+        order.trades_count += 1;
+        order.trades.push(trade.clone());
+
+        let filled = match order.remaining_volume {
+            Some(remaining) => remaining <= order.volume.unwrap_or(0.0) * EPSILON,
+            None => order.price.unwrap_or(0.0) - order.trades.iter().map(|trade| trade.funds).sum::<f64>() <= order.price.unwrap_or(0.0) * EPSILON,
+        };
+        if filled {
+            self.release(order);
+            order.state = OrderState::Done;
+        }
+        self.events.push(OrderEvent::Fill { order: order.clone(), trade, liquidity });
+    }
+
+    // 주문 종료 (취소). 남은 금액을 돌려주고 취소 이벤트를 남김
+    fn close(&mut self, order: &mut Order, state: OrderState) {
+        self.release(order);
+        order.state = state;
+        self.events.push(OrderEvent::Cancel(order.clone()));
+    }
+
+    // 주문에 묶여 있던 남은 금액을 주문 가능 잔고로 돌려줌
+    fn release(&mut self, order: &mut Order) {
+        let (quote, base) = split_market(&order.market);
+        let currency = match order.side {
+            OrderSide::Bid => quote,
+            OrderSide::Ask => base,
+        };
+        let locked = order.locked;
+        let account = self.account_mut(&currency);
+        account.locked = (account.locked - locked).max(0.0);
+        account.balance += locked;
+        order.locked = 0.0;
+        order.remaining_fee = 0.0;
+    }
+
+    fn timestamp(&self) -> String {
+        let datetime = match self.now {
+            0 => Utc::now(),
+            now => DateTime::<Utc>::from_timestamp_millis(now).unwrap_or_else(Utc::now),
+        };
+        datetime.to_rfc3339()
+    }
+}
+
+/// 마켓 코드 (`KRW-BTC`)를 (호가 통화, 거래 통화)로 나눔
+pub fn split_market(market: &str) -> (String, String) {
+    match market.split_once('-') {
+        Some((quote, base)) => (quote.to_string(), base.to_string()),
+        None => ("KRW".to_string(), market.to_string()),
+    }
+}
+
+fn matches_key(order: &Order, key: &OrderKey) -> bool {
+    match key {
+        OrderKey::Uuid(uuid) => order.uuid == *uuid,
+        OrderKey::Identifier(identifier) => order.identifier.as_deref() == Some(identifier.as_str()),
+    }
+}
+
+// 업비트가 주문 실패 시 내려주는 것과 같은 형식의 에러
+fn api_error(name: &str, message: &str) -> UpbitError {
+    UpbitError::Http {
+        status: 400,
+        error: Some(UpbitErrorBody { name: name.to_string(), message: message.to_string() }),
+        body: String::new(),
+    }
+}
+
+fn order_not_found() -> UpbitError {
+    UpbitError::Http {
+        status: 404,
+        error: Some(UpbitErrorBody { name: "order_not_found".to_string(), message: "주문을 찾지 못했습니다.".to_string() }),
+        body: String::new(),
+    }
+}
+
+fn same_price(a: f64, b: f64) -> bool {
+    (a - b).abs() <= a.abs().max(b.abs()) * 1e-12
+}
+
+// 주문과 같은 방향 호가에서 price의 잔량
+fn level_size(orderbook: &Orderbook, side: OrderSide, price: f64) -> Option<f64> {
+    orderbook.orderbook_units.iter().find_map(|unit| match side {
+        OrderSide::Bid if same_price(unit.bid_price, price) => Some(unit.bid_size),
+        OrderSide::Ask if same_price(unit.ask_price, price) => Some(unit.ask_size),
+        _ => None,
+    })
+}
+
+// price가 같은 방향 호가의 최우선 호가와 마지막 호가 사이에 있는지 여부
+fn is_inside_book(orderbook: &Orderbook, side: OrderSide, price: f64) -> bool {
+    let prices = orderbook.orderbook_units.iter().map(|unit| match side {
+        OrderSide::Bid => unit.bid_price,
+        OrderSide::Ask => unit.ask_price,
+    }).filter(|price| *price > 0.0).collect::<Vec<f64>>();
+    match (prices.first(), prices.last()) {
+        (Some(best), Some(last)) => price <= best.max(*last) && price >= best.min(*last),
+        _ => false,
+    }
+}
+
+// 진입 신호로 낸 매수 주문이 체결되면 사용할 익절가/스탑
+#[derive(Debug, Clone)]
+struct PendingEntry {
+    reason: String,
+    take_profit: f64,
+    trailing_stop: f64,
+}
+
+/// 전략 신호를 `PaperBroker` 주문으로 바꿔 실행하는 종목 하나의 모의 트레이더
+///
+/// - 진입/추가 진입은 시장가 매수, 청산/부분 청산/트레일링 스탑은 시장가 매도
+/// - 익절가에는 보유 수량 전체의 지정가 매도 주문을 올려둠
+/// - 포지션은 체결 이벤트로만 갱신하며, 이벤트는 `Strategy::on_order_event`로 전략에 전달
+/// - 진입 크기는 `sizing`으로 정함. 켈리 비율은 이 트레이더의 청산된 거래 손익률 (`returns`)로 계산
+/// - `risk`가 있으면 진입 신호를 리스크 한도에 맞게 줄이거나 거절하고, 매도 체결마다 실현 손익을 기록
+///
+/// 분할 익절 목표 (`Signal::SetTakeProfitTargets`)는 지원하지 않음
+pub struct PaperTrader {
+    pub code: String,
+    pub strategy: Box<dyn Strategy>,
+    pub position: PositionState,
+    pub market_rules: MarketRules,
+    pub enable_log: bool,
+    pub sizing: PositionSizing,
+    pub returns: Vec<f64>, // 청산된 거래의 손익률 (수수료 제외, 오래된 순서)
+    pub risk: Option<RiskManager>,
+    market: MarketContext, // 변동성 기준 진입 크기 계산용 최근 캔들
+    entry_funds: f64, // 현재 포지션의 매수 체결 금액 합계
+    exit_funds: f64, // 현재 포지션의 매도 체결 금액 합계
+    pending_entry: Option<PendingEntry>,
+    take_profit_order: Option<String>,
+    saved_state: Option<SavedStateKey>, // 마지막으로 저장한 상태 (`take_state_changed`)
+}
+
+// 저장이 필요한지 판단하는 포지션 값 (수량, 익절가, 트레일링 스탑, 익절 주문)
+type SavedStateKey = (f64, f64, f64, Option<String>);
+
+impl PaperTrader {
+    pub fn new(code: &str, strategy: Box<dyn Strategy>, enable_log: bool) -> Self {
+        Self {
+            code: code.to_string(),
+            strategy,
+            position: PositionState::None,
+            market_rules: MarketRules::for_market(code),
+            enable_log,
+            sizing: PositionSizing::SignalPct,
+            returns: Vec::new(),
+            risk: None,
+            market: MarketContext::new(),
+            entry_funds: 0.0,
+            exit_funds: 0.0,
+            pending_entry: None,
+            take_profit_order: None,
+            saved_state: None,
+        }
+    }
+
+    /// 익절가에 올려둔 지정가 매도 주문 uuid
+    pub fn take_profit_order(&self) -> Option<&str> {
+        self.take_profit_order.as_deref()
+    }
+
+    /// 재시작 후 복원할 포지션, 익절 주문 uuid와 전략 상태
+    pub fn market_state(&self) -> MarketState {
+        MarketState {
+            market: self.code.clone(),
+            strategy_name: self.strategy.name().to_string(),
+            position: self.position.clone(),
+            open_orders: self.take_profit_order.iter().cloned().collect(),
+            strategy_state: self.strategy.snapshot(),
+        }
+    }
+
+    /// 저장된 상태 복원. 전략은 warm_up을 마친 상태여야 함
+    ///
+    /// 저장된 익절 주문은 이전 broker의 주문이므로 사용하지 않고, broker의 보유 수량으로 익절 주문을 다시 올림
+    pub fn restore(&mut self, broker: &mut PaperBroker, state: &MarketState) {
+        self.position = state.position.clone();
+        self.cancel_take_profit(broker);
+        if let Some(snapshot) = &state.strategy_state {
+            self.strategy.restore(snapshot);
+        }
+        self.place_take_profit(broker);
+        self.saved_state = Some(self.state_key());
+    }
+
+    /// 마지막 호출 이후 포지션 (수량, 익절가, 트레일링 스탑)이나 익절 주문이 바뀌었는지 확인
+    ///
+    /// 바뀌었으면 `market_state`를 저장해야 함
+    pub fn take_state_changed(&mut self) -> bool {
+        let key = self.state_key();
+        if self.saved_state.as_ref() == Some(&key) {
+            return false;
+        }
+        self.saved_state = Some(key);
+        true
+    }
+
+    fn state_key(&self) -> SavedStateKey {
+        let (take_profit_price, trailing_stop_price) = match &self.position {
+            PositionState::InPosition { take_profit_price, trailing_stop_price, .. } => (*take_profit_price, *trailing_stop_price),
+            PositionState::None => (0.0, 0.0),
+        };
+        (self.position.size(), take_profit_price, trailing_stop_price, self.take_profit_order.clone())
+    }
+
+    /// 체결을 broker에 반영하고 트레일링 스탑 확인 후 전략 신호 처리
+    pub fn on_trade(&mut self, broker: &mut PaperBroker, trade: &Trade) {
+        let date = trade.trade_timestamp.to_string();
+        broker.on_trade(trade);
+        self.dispatch_events(broker, &date);
+        self.check_trailing_stop(broker, trade.trade_price, &date);
+        let signal = self.strategy.on_trade(trade, &mut self.position);
+        self.handle_signal(broker, &signal, &date);
+    }
+
+    pub fn on_orderbook(&mut self, broker: &mut PaperBroker, orderbook: &Orderbook) {
+        broker.on_orderbook(orderbook);
+        let signal = self.strategy.on_orderbook(orderbook, &mut self.position);
+        self.handle_signal(broker, &signal, &orderbook.timestamp.to_string());
+    }
+
+    pub fn on_candle(&mut self, broker: &mut PaperBroker, candle: &Candle) {
+        self.market.push_candle(candle);
+        let signal = self.strategy.on_candle(candle, &mut self.position);
+        self.handle_signal(broker, &signal, candle.get_candle_date_time_utc());
+    }
+
+    pub fn on_ticker(&mut self, broker: &mut PaperBroker, ticker: &Ticker) {
+        let signal = self.strategy.on_ticker(ticker, &mut self.position);
+        self.handle_signal(broker, &signal, &ticker.trade_timestamp.to_string());
+    }
+
+    /// 전략 신호를 주문으로 바꿔 실행하고 발생한 체결/취소 이벤트 처리
+    pub fn handle_signal(&mut self, broker: &mut PaperBroker, signal: &Signal, date: &str) {
+        let signal = self.market_rules.align_signal(signal);
+        let signal = self.size_signal(broker, signal);
+        let signal = self.check_risk(broker, signal, date);
+        let in_position = matches!(self.position, PositionState::InPosition { .. });
+
+        match &signal {
+            Signal::Buy { reason, initial_trailing_stop, take_profit, asset_pct } if !in_position && self.pending_entry.is_none() => {
+                self.pending_entry = Some(PendingEntry { reason: reason.clone(), take_profit: *take_profit, trailing_stop: *initial_trailing_stop });
+                if !self.buy(broker, *asset_pct) {
+                    self.pending_entry = None;
+                }
+            }
+            Signal::AddToPosition { asset_pct, .. } if in_position => {
+                self.buy(broker, *asset_pct);
+            }
+            Signal::ReducePosition { pct, .. } if in_position => {
+                self.cancel_take_profit(broker);
+                let volume = self.market_rules.round_volume(self.position.size() * pct.min(1.0))
+                    .min(self.market_rules.round_volume(broker.balance(&self.base())));
+                if volume > 0.0 {
+                    self.submit(broker, OrderRequest::market_sell(&self.code, volume));
+                }
+                self.place_take_profit(broker);
+            }
+            Signal::Sell(_) if in_position => self.exit(broker),
+            Signal::UpdateTrailingStop(new_trailing_stop) => {
+                if let PositionState::InPosition { trailing_stop_price, .. } = &mut self.position {
+                    *trailing_stop_price = *new_trailing_stop;
+                }
+            }
+            _ => {}
+        }
+        self.dispatch_events(broker, date);
+    }
+
+    // 진입 신호의 asset_pct를 sizing으로 다시 계산. 아직 체결가가 없으면 신호 그대로
+    fn size_signal(&self, broker: &PaperBroker, signal: Signal) -> Signal {
+        let Some(price) = broker.last_price(&self.code) else { return signal };
+        if self.sizing == PositionSizing::SignalPct {
+            return signal;
+        }
+        let quote = split_market(&self.code).0;
+        let context = SizingContext {
+            equity: broker.equity(&quote),
+            cash: broker.balance(&quote),
+            price,
+            candles: &self.market.candles,
+            returns: &self.returns,
+        };
+        self.sizing.apply(&signal, &self.position, &context)
+    }
+
+    // 리스크 관리자로 진입 신호를 검사하고 줄이거나 거절한 이유를 출력
+    fn check_risk(&mut self, broker: &PaperBroker, signal: Signal, date: &str) -> Signal {
+        let Some(risk) = &mut self.risk else { return signal };
+        let quote = split_market(&self.code).0;
+        let price = broker.last_price(&self.code).unwrap_or(0.0);
+        let (checked, decision) = risk.apply(&signal, &self.position, broker.equity(&quote), broker.balance(&quote), price, date);
+        if self.enable_log {
+            match decision {
+                RiskDecision::Allow => {}
+                RiskDecision::Resize { asset_pct, reason } => {
+                    println!("[모의 진입 축소] {} - 날짜: {}, 비율: {:.2}%, 이유: {}", self.code, date, asset_pct * 100.0, reason);
+                }
+                RiskDecision::Reject(reason) => println!("[모의 진입 거절] {} - 날짜: {}, 이유: {}", self.code, date, reason),
+            }
+        }
+        checked
+    }
+
+    // 가격이 트레일링 스탑 아래로 내려가면 시장가 청산
+    fn check_trailing_stop(&mut self, broker: &mut PaperBroker, price: f64, date: &str) {
+        let trailing_stop_price = match &mut self.position {
+            PositionState::InPosition { trailing_stop_price, lots, .. } => {
+                for lot in lots.iter_mut() {
+                    lot.trade.update(price);
+                }
+                *trailing_stop_price
+            }
+            PositionState::None => return,
+        };
+        if price <= trailing_stop_price {
+            self.exit(broker);
+            self.dispatch_events(broker, date);
+        }
+    }
+
+    // broker에 쌓인 이 종목의 이벤트를 포지션에 반영하고 전략에 전달. 전략이 반환한 신호도 처리
+    fn dispatch_events(&mut self, broker: &mut PaperBroker, date: &str) {
+        loop {
+            let events = broker.take_events(&self.code);
+            if events.is_empty() {
+                break;
+            }
+            for event in events {
+                self.apply_event(broker, &event, date);
+                let signal = self.strategy.on_order_event(&event, &mut self.position);
+                self.handle_signal(broker, &signal, date);
+            }
+        }
+    }
+
+    fn apply_event(&mut self, broker: &mut PaperBroker, event: &OrderEvent, date: &str) {
+        let order = event.order();
+        let finished = order.state != OrderState::Wait;
+        match event {
+            OrderEvent::Fill { trade, liquidity, .. } => {
+                if self.enable_log {
+                    println!("[모의 체결] {} - 날짜: {}, {:?} {:?}, 가격: {:.4}, 수량: {:.8}", self.code, date, order.side, liquidity, trade.price, trade.volume);
+                }
+                match order.side {
+                    OrderSide::Bid => {
+                        self.entry_funds += trade.funds;
+                        self.add_lot(trade, date);
+                    }
+                    OrderSide::Ask => {
+                        self.exit_funds += trade.funds;
+                        let cost = self.position_cost();
+                        self.reduce_lots(trade.volume);
+                        let cost = cost - self.position_cost();
+                        self.record_return();
+                        self.record_risk_exit(broker, trade, *liquidity, cost, date);
+                    }
+                }
+            }
+            OrderEvent::Cancel(_) => {
+                if self.enable_log {
+                    println!("[모의 취소] {} - 날짜: {}, {:?} 미체결 수량: {:?}", self.code, date, order.side, order.remaining_volume);
+                }
+            }
+        }
+
+        if finished && self.take_profit_order.as_deref() == Some(order.uuid.as_str()) {
+            self.take_profit_order = None;
+        }
+        // 매수 주문이 끝나면 보유 수량 전체로 익절 주문을 다시 올림
+        if finished && order.side == OrderSide::Bid {
+            self.pending_entry = None;
+            self.cancel_take_profit(broker);
+            self.place_take_profit(broker);
+        }
+    }
+
+    fn add_lot(&mut self, trade: &OrderTrade, date: &str) {
+        let reason = self.pending_entry.as_ref().map(|entry| entry.reason.clone()).unwrap_or_default();
+        let lot = Lot { entry_price: trade.price, entry_asset: trade.funds, size: trade.volume, trade: OpenTrade::new(date, &reason, trade.price) };
+        match &mut self.position {
+            PositionState::InPosition { lots, .. } => {
+                lots.push(lot);
+                self.position.recalculate();
+            }
+            PositionState::None => {
+                let Some(entry) = &self.pending_entry else { return };
+                self.position = PositionState::InPosition {
+                    entry_price: lot.entry_price,
+                    entry_asset: lot.entry_asset,
+                    take_profit_price: entry.take_profit,
+                    trailing_stop_price: entry.trailing_stop,
+                    lots: vec![lot],
+                    take_profit_targets: Vec::new(),
+                };
+            }
+        }
+    }
+
+    // 포지션이 모두 청산되면 매수/매도 체결 금액으로 거래 손익률을 기록
+    fn record_return(&mut self) {
+        if !matches!(self.position, PositionState::None) {
+            return;
+        }
+        if self.entry_funds > 0.0 {
+            self.returns.push(self.exit_funds / self.entry_funds - 1.0);
+        }
+        self.entry_funds = 0.0;
+        self.exit_funds = 0.0;
+    }
+
+    // 보유 중인 lot의 매수 체결 금액 합계
+    fn position_cost(&self) -> f64 {
+        self.position.lots().iter().map(|lot| lot.entry_asset).sum()
+    }
+
+    // 매도 체결의 실현 손익을 리스크 관리자에 기록. 진입은 시장가 매수이므로 매수 수수료는 taker 수수료율로 계산
+    fn record_risk_exit(&mut self, broker: &PaperBroker, trade: &OrderTrade, liquidity: Liquidity, cost: f64, date: &str) {
+        let Some(risk) = &mut self.risk else { return };
+        let fees = FeeSchedule::for_market(&self.code);
+        let pnl = trade.funds * (1.0 - fees.fee_pct(liquidity)) - cost * (1.0 + fees.taker_pct);
+        let closed = matches!(self.position, PositionState::None);
+        risk.record_exit(pnl, broker.equity(&split_market(&self.code).0), closed, date);
+    }
+
+    // 매도 체결 수량만큼 오래된 lot부터 줄임
+    fn reduce_lots(&mut self, volume: f64) {
+        let PositionState::InPosition { lots, .. } = &mut self.position else { return };
+        let total_size = lots.iter().map(|lot| lot.size).sum::<f64>();
+        let mut remaining = volume;
+        for lot in lots.iter_mut() {
+            let exit_size = remaining.min(lot.size);
+            if lot.size > 0.0 {
+                lot.entry_asset -= lot.entry_asset * exit_size / lot.size;
+            }
+            lot.size -= exit_size;
+            remaining -= exit_size;
+        }
+        lots.retain(|lot| lot.size > total_size * EPSILON);
+        if lots.is_empty() {
+            self.position = PositionState::None;
+        } else {
+            self.position.recalculate();
+        }
+    }
+
+    fn base(&self) -> String {
+        split_market(&self.code).1
+    }
+
+    // 주문 가능한 호가 통화의 asset_pct만큼 시장가 매수 (수수료 포함)
+    fn buy(&mut self, broker: &mut PaperBroker, asset_pct: f64) -> bool {
+        let fee_pct = FeeSchedule::for_market(&self.code).taker_pct;
+        let amount = broker.balance(&split_market(&self.code).0) * asset_pct.min(1.0) / (1.0 + fee_pct);
+        self.submit(broker, OrderRequest::market_buy(&self.code, self.market_rules.round_amount(amount)))
+    }
+
+    // 익절 주문을 취소하고 보유 수량 전체를 시장가 매도
+    fn exit(&mut self, broker: &mut PaperBroker) {
+        self.cancel_take_profit(broker);
+        let volume = self.market_rules.round_volume(broker.balance(&self.base()));
+        if volume > 0.0 {
+            self.submit(broker, OrderRequest::market_sell(&self.code, volume));
+        }
+    }
+
+    fn place_take_profit(&mut self, broker: &mut PaperBroker) {
+        let PositionState::InPosition { take_profit_price, .. } = self.position else { return };
+        if self.take_profit_order.is_some() {
+            return;
+        }
+        let volume = self.market_rules.round_volume(broker.balance(&self.base()));
+        if volume <= 0.0 || self.market_rules.check_amount(take_profit_price * volume).is_err() {
+            return;
+        }
+        match self.place(broker, OrderRequest::limit(&self.code, OrderSide::Ask, volume, take_profit_price)) {
+            Ok(order) if order.state == OrderState::Wait => self.take_profit_order = Some(order.uuid),
+            _ => {}
+        }
+    }
+
+    fn cancel_take_profit(&mut self, broker: &mut PaperBroker) {
+        if let Some(uuid) = self.take_profit_order.take() {
+            let _ = broker.cancel_order(&OrderKey::Uuid(uuid));
+        }
+    }
+
+    fn submit(&mut self, broker: &mut PaperBroker, request: OrderRequest) -> bool {
+        self.place(broker, request).is_ok()
+    }
+
+    fn place(&mut self, broker: &mut PaperBroker, request: OrderRequest) -> Result<Order, UpbitError> {
+        let result = broker.place_order(&request);
+        match &result {
+            Err(e) if self.enable_log => println!("[모의 주문 실패] {} - {:?} {:?}: {}", self.code, request.side, request.ord_type, e),
+            _ => {}
+        }
+        result
+    }
+}
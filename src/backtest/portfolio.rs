@@ -0,0 +1,358 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::{backtest::{ledger::{parse_backtest_date, EquityPoint, TradeRecord}, lib::{BacktestParams, BacktesterState}, report::BacktestReport},
+core::{candle::{Candle, CandleTrait}, position::PositionState, signal::Signal}, strategy::{lib::Strategy, registry::{create_candle_strategy, StrategyParamError}}};
+
+/// 진입 금액 결정 방식
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Allocation {
+    /// 신호의 asset_pct를 현재 현금에 적용
+    SignalPct,
+    /// 스탑에 걸렸을 때 손실이 포트폴리오 평가액의 risk_pct가 되도록 진입 (마켓마다 같은 위험)
+    EqualRisk { risk_pct: f64 },
+}
+
+/// 포트폴리오 백테스트 설정
+#[derive(Debug, Clone)]
+pub struct PortfolioConfig {
+    pub initial_asset: f64,
+    pub strategy_name: String,
+    pub max_concurrent_positions: usize, // 동시에 보유할 수 있는 최대 마켓 수
+    pub max_pct_per_market: f64, // 포트폴리오 평가액 대비 한 마켓 최대 비중
+    pub allocation: Allocation,
+    pub enable_webhook_log: bool,
+}
+
+impl PortfolioConfig {
+    pub fn new(strategy_name: &str) -> Self {
+        Self {
+            initial_asset: 1000000.0,
+            strategy_name: strategy_name.to_string(),
+            max_concurrent_positions: 3,
+            max_pct_per_market: 0.34,
+            allocation: Allocation::SignalPct,
+            enable_webhook_log: false,
+        }
+    }
+}
+
+/// 포트폴리오에 포함된 마켓
+///
+/// backtester의 current_asset은 진입 직전에 배정한 금액만 잠시 들고 있고, 처리 후에는 포트폴리오 현금으로 돌려놓음
+pub struct PortfolioMarket {
+    pub code: String,
+    pub backtester: BacktesterState,
+    pub strategy: Box<dyn Strategy>,
+    pub last_price: f64,
+    pub last_date: String,
+    pub closes: Vec<(i64, f64)>, // 상관계수 계산용 (시간, 종가)
+}
+
+/// 여러 마켓을 하나의 현금으로 운용하는 포트폴리오 백테스터
+pub struct Portfolio {
+    pub config: PortfolioConfig,
+    pub cash: f64,
+    pub markets: Vec<PortfolioMarket>,
+    pub equity_curve: Vec<EquityPoint>,
+}
+
+impl Portfolio {
+    pub fn new(config: PortfolioConfig) -> Self {
+        let cash = config.initial_asset;
+        Self { config, cash, markets: Vec::new(), equity_curve: Vec::new() }
+    }
+
+    /// 마켓과 해당 마켓에서 실행할 전략 추가
+    pub fn add_market(&mut self, code: &str, strategy: Box<dyn Strategy>) {
+        let mut params = BacktestParams::default(code, &self.config.strategy_name);
+        params.enable_webhook_log = self.config.enable_webhook_log;
+        let mut backtester = BacktesterState::new(params);
+        backtester.current_asset = 0.0;
+        self.markets.push(PortfolioMarket {
+            code: code.to_string(),
+            backtester,
+            strategy,
+            last_price: 0.0,
+            last_date: String::new(),
+            closes: Vec::new(),
+        });
+    }
+
+    /// 현금과 모든 마켓의 포지션을 현재가로 평가한 금액
+    pub fn equity(&self) -> f64 {
+        self.cash + self.markets.iter().map(|market| market.backtester.equity(market.last_price)).sum::<f64>()
+    }
+
+    /// 포지션을 보유 중인 마켓 수
+    pub fn open_positions(&self) -> usize {
+        self.markets.iter().filter(|market| matches!(market.backtester.position, PositionState::InPosition { .. })).count()
+    }
+
+    /// 한 마켓의 캔들 처리. 청산 확인 -> 전략 신호 -> 자금 배정 -> 신호 처리 순서
+    pub fn on_candle(&mut self, code: &str, candle: &Candle) {
+        let Some(index) = self.markets.iter().position(|market| market.code == code) else { return };
+        let current_price = candle.get_trade_price();
+        let current_date = candle.get_candle_date_time_utc().to_string();
+
+        let signal = {
+            let market = &mut self.markets[index];
+            market.last_price = current_price;
+            market.last_date = current_date.clone();
+            if let Some(time) = parse_backtest_date(&current_date) {
+                market.closes.push((time, current_price));
+            }
+
+            let backtester = &mut market.backtester;
+            let intrabar_policy = backtester.params.intrabar_policy;
+            backtester.check_and_close_position_with_candle(candle, intrabar_policy, &[]);
+            backtester.market.push_candle(candle);
+            market.strategy.on_candle(candle, market.backtester.get_position())
+        };
+        self.sweep(index);
+
+        let signal = self.allocate(index, signal, current_price);
+        self.markets[index].backtester.handle_signal(&signal, current_price, &current_date);
+        self.sweep(index);
+    }
+
+    // 마켓에 남은 현금 (청산 대금, 사용하지 않은 배정 금액)을 포트폴리오 현금으로 회수
+    fn sweep(&mut self, index: usize) {
+        let backtester = &mut self.markets[index].backtester;
+        self.cash += backtester.current_asset;
+        backtester.current_asset = 0.0;
+    }
+
+    // 진입 신호에 배정 규칙을 적용해 마켓에 현금을 배정하고, 배정한 금액 전부를 사용하도록 신호를 바꿈
+    fn allocate(&mut self, index: usize, signal: Signal, current_price: f64) -> Signal {
+        let in_position = matches!(self.markets[index].backtester.position, PositionState::InPosition { .. });
+        match signal {
+            Signal::Buy { reason, initial_trailing_stop, take_profit, asset_pct } if !in_position => {
+                if self.open_positions() >= self.config.max_concurrent_positions {
+                    return Signal::Hold;
+                }
+                let amount = self.entry_amount(index, asset_pct, current_price, initial_trailing_stop);
+                if amount <= 0.0 {
+                    return Signal::Hold;
+                }
+                self.assign(index, amount);
+                Signal::Buy { reason, initial_trailing_stop, take_profit, asset_pct: 1.0 }
+            }
+            Signal::AddToPosition { reason, asset_pct } if in_position => {
+                let PositionState::InPosition { trailing_stop_price, .. } = self.markets[index].backtester.position else { return Signal::Hold };
+                let amount = self.entry_amount(index, asset_pct, current_price, trailing_stop_price);
+                if amount <= 0.0 {
+                    return Signal::Hold;
+                }
+                self.assign(index, amount);
+                Signal::AddToPosition { reason, asset_pct: 1.0 }
+            }
+            signal => signal,
+        }
+    }
+
+    fn assign(&mut self, index: usize, amount: f64) {
+        self.cash -= amount;
+        self.markets[index].backtester.current_asset += amount;
+    }
+
+    /// 배정 규칙과 마켓별 최대 비중, 현금 한도를 적용한 진입 금액
+    pub fn entry_amount(&self, index: usize, asset_pct: f64, entry_price: f64, stop_price: f64) -> f64 {
+        let equity = self.equity();
+        let market = &self.markets[index];
+        let exposure = market.backtester.equity(market.last_price);
+        let room = equity * self.config.max_pct_per_market - exposure;
+
+        let desired = match self.config.allocation {
+            Allocation::SignalPct => self.cash * asset_pct,
+            Allocation::EqualRisk { risk_pct } => {
+                let stop_distance = (entry_price - stop_price) / entry_price;
+                if stop_distance > 0.0 { equity * risk_pct / stop_distance } else { 0.0 }
+            }
+        };
+        desired.min(room).min(self.cash).max(0.0)
+    }
+
+    /// 포트폴리오 평가 금액을 자산 곡선에 추가. 같은 시간이면 마지막 값을 갱신
+    pub fn record_equity(&mut self, date: &str) {
+        let equity = self.equity();
+        match self.equity_curve.last_mut() {
+            Some(last) if last.date == date => last.equity = equity,
+            _ => self.equity_curve.push(EquityPoint { date: date.to_string(), equity }),
+        }
+    }
+
+    /// 보유 중인 포지션을 마지막 가격으로 모두 청산
+    pub fn finish(&mut self) {
+        for index in 0..self.markets.len() {
+            let market = &mut self.markets[index];
+            if market.last_date.is_empty() {
+                continue;
+            }
+            market.backtester.close_at_end(market.last_price, &market.last_date);
+            self.sweep(index);
+        }
+        if let Some(date) = self.markets.iter().map(|market| market.last_date.clone()).max() {
+            self.record_equity(&date);
+        }
+    }
+
+    /// 모든 마켓의 거래 기록 (청산 시간 순서)
+    pub fn trades(&self) -> Vec<TradeRecord> {
+        let mut trades = self.markets.iter().flat_map(|market| market.backtester.trades.clone()).collect::<Vec<TradeRecord>>();
+        trades.sort_by_key(|trade| parse_backtest_date(&trade.exit_date).unwrap_or(i64::MAX));
+        trades
+    }
+
+    /// 포트폴리오 성과, 마켓별 기여도, 마켓 간 수익률 상관계수
+    pub fn report(&self) -> PortfolioReport {
+        let report = BacktestReport::new(self.config.initial_asset, &self.trades(), &self.equity_curve);
+
+        let markets = self.markets.iter().map(|market| {
+            let trades = &market.backtester.trades;
+            let wins = trades.iter().filter(|trade| trade.pnl > 0.0).count();
+            let pnl = trades.iter().map(|trade| trade.pnl).sum::<f64>();
+            MarketContribution {
+                code: market.code.clone(),
+                trades: trades.len(),
+                win_rate: if trades.is_empty() { 0.0 } else { wins as f64 / trades.len() as f64 },
+                pnl,
+                contribution_pct: pnl / self.config.initial_asset,
+            }
+        }).collect::<Vec<MarketContribution>>();
+
+        let returns = self.markets.iter().map(|market| returns_by_time(&market.closes)).collect::<Vec<HashMap<i64, f64>>>();
+        let correlation = returns.iter().map(|a| {
+            returns.iter().map(|b| {
+                let (xs, ys): (Vec<f64>, Vec<f64>) = a.iter()
+                    .filter_map(|(time, x)| b.get(time).map(|y| (*x, *y)))
+                    .unzip();
+                correlation(&xs, &ys)
+            }).collect::<Vec<f64>>()
+        }).collect::<Vec<Vec<f64>>>();
+
+        PortfolioReport {
+            report,
+            markets,
+            codes: self.markets.iter().map(|market| market.code.clone()).collect(),
+            correlation,
+        }
+    }
+}
+
+/// 마켓별 손익 기여
+#[derive(Debug, Clone, Serialize)]
+pub struct MarketContribution {
+    pub code: String,
+    pub trades: usize,
+    pub win_rate: f64,
+    /// 실현 손익 금액
+    pub pnl: f64,
+    /// 초기 자산 대비 실현 손익
+    pub contribution_pct: f64,
+}
+
+/// 포트폴리오 백테스트 결과
+#[derive(Debug, Clone, Serialize)]
+pub struct PortfolioReport {
+    /// 포트폴리오 자산 곡선과 전체 거래 기준 성과 지표
+    pub report: BacktestReport,
+    pub markets: Vec<MarketContribution>,
+    /// correlation의 행/열 순서
+    pub codes: Vec<String>,
+    /// 공통 시간의 캔들 종가 수익률 상관계수. 겹치는 구간이 2개 미만이면 0
+    pub correlation: Vec<Vec<f64>>,
+}
+
+impl PortfolioReport {
+    pub fn print(&self) {
+        println!("--------------------------------------------------");
+        println!(" [포트폴리오 백테스팅 결과]");
+        println!(" > 최종 자산: {:.0} ({:.4}%)", self.report.final_asset, self.report.total_return_pct * 100.0);
+        println!(" > 총 거래: {} 회, 승률: {:.2}%", self.report.total_trades, self.report.win_rate * 100.0);
+        self.report.print();
+        for market in &self.markets {
+            println!(" > {} - 거래: {} 회, 승률: {:.2}%, 손익: {:.0}, 기여: {:.4}%",
+                market.code, market.trades, market.win_rate * 100.0, market.pnl, market.contribution_pct * 100.0);
+        }
+        println!(" > 상관계수");
+        for (code, row) in self.codes.iter().zip(&self.correlation) {
+            println!("   {:<10} {}", code, row.iter().map(|value| format!("{:>6.2}", value)).collect::<Vec<String>>().join(" "));
+        }
+        println!("--------------------------------------------------");
+    }
+}
+
+// 직전 종가 대비 수익률 (시간 -> 수익률)
+fn returns_by_time(closes: &[(i64, f64)]) -> HashMap<i64, f64> {
+    closes.windows(2)
+        .filter(|w| w[0].1 > 0.0)
+        .map(|w| (w[1].0, w[1].1 / w[0].1 - 1.0))
+        .collect()
+}
+
+/// 피어슨 상관계수. 값이 2개 미만이거나 분산이 0이면 0
+pub fn correlation(xs: &[f64], ys: &[f64]) -> f64 {
+    let n = xs.len().min(ys.len());
+    if n < 2 {
+        return 0.0;
+    }
+    let mean_x = xs[..n].iter().sum::<f64>() / n as f64;
+    let mean_y = ys[..n].iter().sum::<f64>() / n as f64;
+    let mut covariance = 0.0;
+    let mut variance_x = 0.0;
+    let mut variance_y = 0.0;
+    for i in 0..n {
+        let dx = xs[i] - mean_x;
+        let dy = ys[i] - mean_y;
+        covariance += dx * dy;
+        variance_x += dx * dx;
+        variance_y += dy * dy;
+    }
+    if variance_x <= 0.0 || variance_y <= 0.0 {
+        return 0.0;
+    }
+    covariance / (variance_x * variance_y).sqrt()
+}
+
+/// 여러 마켓의 캔들을 시간 순서로 합쳐 하나의 현금으로 시뮬레이션
+///
+/// 마켓마다 `config.strategy_name` 전략을 새로 만들어 실행하며, 등록되지 않았거나 체결이 필요한 전략이면 에러.
+/// 각 캔들 목록은 오래된 순서로 정렬되어 있어야 함
+pub fn simulate_portfolio(markets: Vec<(String, Vec<Candle>)>, config: PortfolioConfig) -> Result<Portfolio, StrategyParamError> {
+    let mut portfolio = Portfolio::new(config);
+    for (code, _) in &markets {
+        let strategy = create_candle_strategy(&portfolio.config.strategy_name, &[], false)?;
+        portfolio.add_market(code, strategy);
+    }
+
+    run_portfolio(&mut portfolio, &markets);
+    portfolio.report().print();
+    Ok(portfolio)
+}
+
+/// 마켓을 추가한 포트폴리오에 캔들을 공통 시간 순서로 넣고 마지막에 모든 포지션을 청산
+///
+/// 같은 시간의 캔들은 markets 순서대로 처리하고, 시간마다 포트폴리오 평가 금액을 기록함
+pub fn run_portfolio(portfolio: &mut Portfolio, markets: &[(String, Vec<Candle>)]) {
+    let mut timeline = markets.iter().enumerate()
+        .flat_map(|(market_index, (_, candles))| candles.iter().enumerate().filter_map(move |(candle_index, candle)| {
+            parse_backtest_date(candle.get_candle_date_time_utc()).map(|time| (time, market_index, candle_index))
+        }))
+        .collect::<Vec<(i64, usize, usize)>>();
+    timeline.sort();
+
+    for (i, &(time, market_index, candle_index)) in timeline.iter().enumerate() {
+        let (code, candles) = &markets[market_index];
+        let candle = &candles[candle_index];
+        portfolio.on_candle(code, candle);
+
+        // 같은 시간의 마지막 캔들을 처리한 뒤 평가 금액 기록
+        if timeline.get(i + 1).is_none_or(|next| next.0 != time) {
+            portfolio.record_equity(candle.get_candle_date_time_utc());
+        }
+    }
+
+    portfolio.finish();
+}
@@ -0,0 +1,287 @@
+use std::{collections::{BTreeMap, HashSet}, fmt};
+
+use crate::{backtest::state_store::{StateStore, TradingState}, core::position::{Lot, OpenTrade, PositionState},
+upbit_api::{account::{check_my_account, Account}, client::UpbitClient, error::UpbitError, market_rules::MarketRules,
+order::{cancel_order, get_open_orders, place_order, Order, OrderKey, OrderRequest}},
+webhook::lib as webhook_lib};
+
+// GET /orders/open 한 번에 조회할 최대 주문 수
+const OPEN_ORDERS_LIMIT: u32 = 100;
+
+/// 저장된 상태와 계좌가 다를 때 처리 방식
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MismatchPolicy {
+    /// 계좌 보유 수량에 맞춰 저장된 포지션을 고치거나 새 포지션으로 채택
+    Adopt,
+    /// 계좌 보유 수량을 시장가로 모두 매도하고 포지션 제거
+    Flatten,
+    /// 상태를 바꾸지 않고 알림만 보냄
+    Alert,
+}
+
+/// 재시작 시 상태 맞추기 설정
+#[derive(Debug, Clone)]
+pub struct ReconcileConfig {
+    pub quote_currency: String,
+    pub markets: Vec<String>, // 관리하는 마켓. 비어 있으면 계좌의 모든 마켓
+    pub policy: MismatchPolicy, // 보유 수량이 다를 때
+    pub cancel_unknown_orders: bool, // 저장되지 않은 미체결 주문 취소 (false면 알림만)
+    pub volume_tolerance: f64, // 저장된 수량 대비 이 비율 이하의 차이는 같은 것으로 봄 (수수료, 반올림)
+    pub dust_value: f64, // 평가 금액이 이보다 작은 보유 수량은 무시 (최소 주문 금액 미만은 매도할 수 없음)
+    pub adopted_take_profit_pct: f64, // 채택한 보유 수량의 익절가 (평균 매수가 대비 상승률)
+    pub enable_webhook_log: bool,
+}
+
+impl ReconcileConfig {
+    pub fn new() -> Self {
+        Self {
+            quote_currency: "KRW".to_string(),
+            markets: Vec::new(),
+            policy: MismatchPolicy::Alert,
+            cancel_unknown_orders: false,
+            volume_tolerance: 0.001,
+            dust_value: 5000.0,
+            adopted_take_profit_pct: 0.05,
+            enable_webhook_log: false,
+        }
+    }
+
+    fn manages(&self, market: &str) -> bool {
+        self.markets.is_empty() || self.markets.iter().any(|m| m == market)
+    }
+}
+
+impl Default for ReconcileConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 저장된 상태와 거래소 상태의 차이
+#[derive(Debug, Clone, PartialEq)]
+pub enum Mismatch {
+    /// 저장된 포지션이 없는데 계좌에 보유 수량이 있음
+    UntrackedHolding { market: String, volume: f64, avg_buy_price: f64 },
+    /// 저장된 포지션 수량과 계좌 보유 수량이 다름 (actual이 0이면 포지션이 계좌에 없음)
+    VolumeMismatch { market: String, stored: f64, actual: f64, avg_buy_price: f64 },
+    /// 거래소에 있지만 저장되지 않은 미체결 주문
+    UnknownOrder { market: String, uuid: String },
+    /// 저장되어 있지만 거래소에 없는 미체결 주문 (중단된 동안 체결되었거나 취소됨)
+    MissingOrder { market: String, uuid: String },
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Mismatch::UntrackedHolding { market, volume, .. } => write!(f, "{}: untracked holding {:.8}", market, volume),
+            Mismatch::VolumeMismatch { market, stored, actual, .. } => write!(f, "{}: stored volume {:.8}, account volume {:.8}", market, stored, actual),
+            Mismatch::UnknownOrder { market, uuid } => write!(f, "{}: unknown open order {}", market, uuid),
+            Mismatch::MissingOrder { market, uuid } => write!(f, "{}: stored order {} is no longer open", market, uuid),
+        }
+    }
+}
+
+/// 차이를 처리한 결과
+#[derive(Debug, Clone, PartialEq)]
+pub enum Resolution {
+    /// 계좌 기준으로 저장된 상태를 고침
+    Adopted,
+    /// 시장가 매도 주문 uuid
+    Flattened(String),
+    /// 미체결 주문 취소
+    Cancelled,
+    /// 거래소에 없는 주문 uuid를 상태에서 제거
+    Removed,
+    /// 상태를 바꾸지 않고 알림
+    Alerted,
+    /// 취소/청산 주문 실패. 상태를 바꾸지 않고 알림
+    Failed(String),
+}
+
+/// 상태 맞추기 에러
+#[derive(Debug)]
+pub enum ReconcileError {
+    Io(std::io::Error),
+    Api(UpbitError),
+}
+
+impl fmt::Display for ReconcileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReconcileError::Io(e) => write!(f, "io error: {}", e),
+            ReconcileError::Api(e) => write!(f, "api error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ReconcileError {}
+
+impl From<std::io::Error> for ReconcileError {
+    fn from(e: std::io::Error) -> Self {
+        ReconcileError::Io(e)
+    }
+}
+
+impl From<UpbitError> for ReconcileError {
+    fn from(e: UpbitError) -> Self {
+        ReconcileError::Api(e)
+    }
+}
+
+// 계좌의 마켓별 (보유 수량 (주문에 묶인 수량 포함), 평균 매수가)
+fn holdings(accounts: &[Account], config: &ReconcileConfig) -> BTreeMap<String, (f64, f64)> {
+    accounts.iter()
+        .filter(|account| account.currency != config.quote_currency)
+        .map(|account| (format!("{}-{}", config.quote_currency, account.currency), (account.balance + account.locked, account.avg_buy_price)))
+        .filter(|(market, _)| config.manages(market))
+        .collect()
+}
+
+/// 저장된 상태와 계좌 잔고, 미체결 주문을 비교
+pub fn find_mismatches(state: &TradingState, accounts: &[Account], open_orders: &[Order], config: &ReconcileConfig) -> Vec<Mismatch> {
+    let holdings = holdings(accounts, config);
+    let mut mismatches = Vec::new();
+
+    for (market, (volume, avg_buy_price)) in &holdings {
+        let stored = state.markets.get(market).map(|state| state.position.size()).unwrap_or(0.0);
+        if stored <= 0.0 && volume * avg_buy_price >= config.dust_value {
+            mismatches.push(Mismatch::UntrackedHolding { market: market.clone(), volume: *volume, avg_buy_price: *avg_buy_price });
+        }
+    }
+    for (market, market_state) in &state.markets {
+        let stored = market_state.position.size();
+        if stored <= 0.0 || !config.manages(market) {
+            continue;
+        }
+        let (actual, avg_buy_price) = holdings.get(market).copied().unwrap_or((0.0, 0.0));
+        if (actual - stored).abs() > stored * config.volume_tolerance {
+            mismatches.push(Mismatch::VolumeMismatch { market: market.clone(), stored, actual, avg_buy_price });
+        }
+    }
+
+    let stored_orders = state.open_orders().map(|(_, uuid)| uuid).collect::<HashSet<&str>>();
+    let exchange_orders = open_orders.iter().map(|order| order.uuid.as_str()).collect::<HashSet<&str>>();
+    for order in open_orders {
+        if config.manages(&order.market) && !stored_orders.contains(order.uuid.as_str()) {
+            mismatches.push(Mismatch::UnknownOrder { market: order.market.clone(), uuid: order.uuid.clone() });
+        }
+    }
+    for (market, uuid) in state.open_orders() {
+        if !exchange_orders.contains(uuid) {
+            mismatches.push(Mismatch::MissingOrder { market: market.to_string(), uuid: uuid.to_string() });
+        }
+    }
+    mismatches
+}
+
+/// 계좌 보유 수량으로 만든 포지션. 스탑은 없으므로 (0) 전략이 다시 설정해야 함
+pub fn adopted_position(volume: f64, avg_buy_price: f64, take_profit_price: f64, date: &str) -> PositionState {
+    let entry_asset = volume * avg_buy_price;
+    PositionState::InPosition {
+        entry_price: avg_buy_price,
+        entry_asset,
+        take_profit_price,
+        trailing_stop_price: 0.0,
+        lots: vec![Lot { entry_price: avg_buy_price, entry_asset, size: volume, trade: OpenTrade::new(date, "reconciled", avg_buy_price) }],
+        take_profit_targets: Vec::new(),
+    }
+}
+
+// 저장된 lot들을 계좌 수량에 맞게 같은 비율로 줄이거나 늘림. 익절가/스탑은 유지
+fn resize_position(position: &mut PositionState, actual: f64) {
+    let stored = position.size();
+    if actual <= 0.0 || stored <= 0.0 {
+        *position = PositionState::None;
+        return;
+    }
+    if let PositionState::InPosition { lots, .. } = position {
+        let ratio = actual / stored;
+        for lot in lots.iter_mut() {
+            lot.size *= ratio;
+            lot.entry_asset *= ratio;
+        }
+    }
+    position.recalculate();
+}
+
+/// 재시작 시 저장된 상태를 계좌 (`check_my_account`), 미체결 주문 (GET /orders/open)과 비교해 맞추고 저장
+///
+/// - 거래소에 없는 저장된 주문은 상태에서 제거
+/// - 저장되지 않은 미체결 주문은 `cancel_unknown_orders`이면 취소, 아니면 알림
+/// - 보유 수량 차이는 `policy`에 따라 채택, 시장가 청산 또는 알림
+///
+/// 주문이 실패한 차이는 `Resolution::Failed`로 남기고 나머지를 계속 처리하며, 이미 처리한 결과는 항상 저장함.
+/// 처리한 차이와 결과를 반환
+pub async fn reconcile(client: &UpbitClient, store: &StateStore, config: &ReconcileConfig, date: &str)
+-> Result<Vec<(Mismatch, Resolution)>, ReconcileError> {
+    let mut state = store.load()?;
+    let accounts = check_my_account(client).await?;
+    let open_orders = get_open_orders(client, None, OPEN_ORDERS_LIMIT).await?;
+
+    let mut resolved = Vec::new();
+    for mismatch in find_mismatches(&state, &accounts, &open_orders, config) {
+        let resolution = match &mismatch {
+            Mismatch::MissingOrder { market, uuid } => {
+                if let Some(market_state) = state.markets.get_mut(market) {
+                    market_state.open_orders.retain(|open| open != uuid);
+                }
+                Resolution::Removed
+            }
+            Mismatch::UnknownOrder { uuid, .. } if config.cancel_unknown_orders => {
+                match cancel_order(client, &OrderKey::Uuid(uuid.clone())).await {
+                    Ok(_) => Resolution::Cancelled,
+                    Err(e) => Resolution::Failed(e.to_string()),
+                }
+            }
+            Mismatch::UnknownOrder { .. } => Resolution::Alerted,
+            Mismatch::UntrackedHolding { market, volume, avg_buy_price } => match config.policy {
+                MismatchPolicy::Adopt => {
+                    // 익절가는 계좌의 평균 매수가 기준
+                    let take_profit_price = MarketRules::for_market(market).round_price_up(avg_buy_price * (1.0 + config.adopted_take_profit_pct));
+                    state.market_mut(market, "").position = adopted_position(*volume, *avg_buy_price, take_profit_price, date);
+                    Resolution::Adopted
+                }
+                MismatchPolicy::Flatten => flatten(client, &mut state, &accounts, market, config).await,
+                MismatchPolicy::Alert => Resolution::Alerted,
+            },
+            Mismatch::VolumeMismatch { market, actual, .. } => match config.policy {
+                MismatchPolicy::Adopt => {
+                    resize_position(&mut state.market_mut(market, "").position, *actual);
+                    Resolution::Adopted
+                }
+                MismatchPolicy::Flatten if *actual > 0.0 => flatten(client, &mut state, &accounts, market, config).await,
+                MismatchPolicy::Flatten => {
+                    state.market_mut(market, "").position = PositionState::None;
+                    Resolution::Adopted
+                }
+                MismatchPolicy::Alert => Resolution::Alerted,
+            },
+        };
+
+        println!("[상태 맞추기] {} -> {:?}", mismatch, resolution);
+        if matches!(resolution, Resolution::Alerted | Resolution::Failed(_)) && config.enable_webhook_log {
+            webhook_lib::send_webhook("reconcile", &mismatch.to_string()).await;
+        }
+        resolved.push((mismatch, resolution));
+    }
+
+    store.save(&state)?;
+    Ok(resolved)
+}
+
+// 주문에 묶이지 않은 보유 수량 전체를 시장가 매도하고 포지션 제거. 매도할 수 없으면 상태를 바꾸지 않음
+async fn flatten(client: &UpbitClient, state: &mut TradingState, accounts: &[Account], market: &str, config: &ReconcileConfig) -> Resolution {
+    let currency = market.strip_prefix(&format!("{}-", config.quote_currency)).unwrap_or(market);
+    let balance = accounts.iter().find(|account| account.currency == currency).map(|account| account.balance).unwrap_or(0.0);
+    let volume = MarketRules::for_market(market).round_volume(balance);
+    if volume <= 0.0 {
+        return Resolution::Failed(format!("no unlocked {} balance to sell", currency));
+    }
+    match place_order(client, &OrderRequest::market_sell(market, volume)).await {
+        Ok(order) => {
+            state.market_mut(market, "").position = PositionState::None;
+            Resolution::Flattened(order.uuid)
+        }
+        Err(e) => Resolution::Failed(e.to_string()),
+    }
+}
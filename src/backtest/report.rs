@@ -0,0 +1,224 @@
+use serde::Serialize;
+
+use crate::backtest::ledger::{parse_backtest_date, EquityPoint, TradeRecord};
+
+const YEAR_MS: f64 = 365.0 * 24.0 * 3600.0 * 1000.0;
+
+/// 백테스트 성과 지표
+///
+/// 비율 값은 모두 소수 (0.1 = 10%). 기간 관련 값은 날짜를 해석할 수 없으면 None
+#[derive(Debug, Clone, Serialize)]
+pub struct BacktestReport {
+    pub initial_asset: f64,
+    pub final_asset: f64,
+    pub total_return_pct: f64,
+
+    pub total_trades: u32,
+    pub win_count: u32,
+    pub loss_count: u32,
+    pub win_rate: f64,
+
+    /// 자산 곡선 최고점 대비 최대 하락률
+    pub max_drawdown_pct: f64,
+    /// 최고점에서 회복까지 가장 오래 걸린 시간 (ms). 끝까지 회복하지 못한 구간 포함
+    pub max_drawdown_duration_ms: Option<i64>,
+    /// 연 환산 수익률
+    pub cagr: Option<f64>,
+    /// 자산 곡선 구간 수익률 기준. 기간을 알 수 있으면 연 환산
+    pub sharpe_ratio: f64,
+    pub sortino_ratio: f64,
+    /// CAGR / 최대 하락률
+    pub calmar_ratio: Option<f64>,
+
+    /// 총 이익 / 총 손실. 손실 거래가 없으면 무한대
+    pub profit_factor: f64,
+    /// 거래당 평균 손익 금액
+    pub expectancy: f64,
+    /// 거래당 평균 손익률
+    pub expectancy_pct: f64,
+    pub avg_win_pct: f64,
+    pub avg_loss_pct: f64,
+    pub longest_losing_streak: u32,
+
+    /// 평균 보유 시간 (ms)
+    pub avg_holding_ms: Option<i64>,
+    /// 전체 기간 중 포지션을 보유한 시간 비율. 여러 종목이 겹쳐 보유한 시간은 한 번만 셈
+    pub time_in_market_pct: Option<f64>,
+}
+
+impl BacktestReport {
+    pub fn new(initial_asset: f64, trades: &[TradeRecord], equity_curve: &[EquityPoint]) -> Self {
+        let final_asset = equity_curve.last().map(|point| point.equity).unwrap_or(initial_asset);
+        let total_return_pct = final_asset / initial_asset - 1.0;
+
+        // --- 거래 통계 ---
+        let wins = trades.iter().filter(|trade| trade.pnl_pct > 0.0).collect::<Vec<&TradeRecord>>();
+        let losses = trades.iter().filter(|trade| trade.pnl_pct <= 0.0).collect::<Vec<&TradeRecord>>();
+        let total_trades = trades.len() as u32;
+        let gross_profit = wins.iter().map(|trade| trade.pnl).sum::<f64>();
+        let gross_loss = -losses.iter().map(|trade| trade.pnl).sum::<f64>();
+        let profit_factor = if gross_loss > 0.0 {
+            gross_profit / gross_loss
+        } else if gross_profit > 0.0 {
+            f64::INFINITY
+        } else {
+            0.0
+        };
+
+        let mut longest_losing_streak = 0;
+        let mut losing_streak = 0;
+        for trade in trades {
+            if trade.pnl_pct > 0.0 {
+                losing_streak = 0;
+            } else {
+                losing_streak += 1;
+                longest_losing_streak = longest_losing_streak.max(losing_streak);
+            }
+        }
+
+        let holding_times = trades.iter().filter_map(|trade| trade.holding_ms()).collect::<Vec<i64>>();
+        let avg_holding_ms = if !holding_times.is_empty() && holding_times.len() == trades.len() {
+            Some(holding_times.iter().sum::<i64>() / holding_times.len() as i64)
+        } else {
+            None
+        };
+
+        // --- 자산 곡선 통계 ---
+        let (max_drawdown_pct, max_drawdown_duration_ms) = drawdown(equity_curve);
+        let period_ms = period_ms(equity_curve);
+        let cagr = period_ms.filter(|ms| *ms > 0).map(|ms| (final_asset / initial_asset).powf(YEAR_MS / ms as f64) - 1.0);
+        let calmar_ratio = cagr.filter(|_| max_drawdown_pct > 0.0).map(|cagr| cagr / max_drawdown_pct);
+
+        let returns = equity_curve.windows(2)
+            .filter(|w| w[0].equity > 0.0)
+            .map(|w| w[1].equity / w[0].equity - 1.0)
+            .collect::<Vec<f64>>();
+        // 구간 수 기준 연 환산 계수
+        let annualize = match period_ms {
+            Some(ms) if ms > 0 && !returns.is_empty() => (returns.len() as f64 * YEAR_MS / ms as f64).sqrt(),
+            _ => 1.0,
+        };
+        let mean_return = mean(&returns);
+        let std_return = (mean(&returns.iter().map(|r| (r - mean_return).powi(2)).collect::<Vec<f64>>())).sqrt();
+        let downside_std = (mean(&returns.iter().map(|r| r.min(0.0).powi(2)).collect::<Vec<f64>>())).sqrt();
+        let sharpe_ratio = if std_return > 0.0 { mean_return / std_return * annualize } else { 0.0 };
+        let sortino_ratio = if downside_std > 0.0 { mean_return / downside_std * annualize } else { 0.0 };
+
+        let time_in_market_pct = match (avg_holding_ms, period_ms) {
+            (Some(_), Some(period)) if period > 0 => time_in_market_ms(trades).map(|ms| ms as f64 / period as f64),
+            _ => None,
+        };
+
+        Self {
+            initial_asset,
+            final_asset,
+            total_return_pct,
+            total_trades,
+            win_count: wins.len() as u32,
+            loss_count: losses.len() as u32,
+            win_rate: if total_trades > 0 { wins.len() as f64 / total_trades as f64 } else { 0.0 },
+            max_drawdown_pct,
+            max_drawdown_duration_ms,
+            cagr,
+            sharpe_ratio,
+            sortino_ratio,
+            calmar_ratio,
+            profit_factor,
+            expectancy: mean(&trades.iter().map(|trade| trade.pnl).collect::<Vec<f64>>()),
+            expectancy_pct: mean(&trades.iter().map(|trade| trade.pnl_pct).collect::<Vec<f64>>()),
+            avg_win_pct: mean(&wins.iter().map(|trade| trade.pnl_pct).collect::<Vec<f64>>()),
+            avg_loss_pct: mean(&losses.iter().map(|trade| trade.pnl_pct).collect::<Vec<f64>>()),
+            longest_losing_streak,
+            avg_holding_ms,
+            time_in_market_pct,
+        }
+    }
+
+    /// 성과 지표 출력
+    pub fn print(&self) {
+        let format_ms = |ms: Option<i64>| ms.map(|ms| format!("{:.1}분", ms as f64 / 60_000.0)).unwrap_or("-".to_string());
+        let format_pct = |pct: Option<f64>| pct.map(|pct| format!("{:.2}%", pct * 100.0)).unwrap_or("-".to_string());
+
+        println!(" > 최대 낙폭: {:.2}% (기간: {})", self.max_drawdown_pct * 100.0, format_ms(self.max_drawdown_duration_ms));
+        println!(" > CAGR: {} | Sharpe: {:.2} | Sortino: {:.2} | Calmar: {}",
+            format_pct(self.cagr), self.sharpe_ratio, self.sortino_ratio,
+            self.calmar_ratio.map(|calmar| format!("{:.2}", calmar)).unwrap_or("-".to_string()));
+        println!(" > Profit Factor: {:.2} | 기대값: {:.0} ({:.4}%)", self.profit_factor, self.expectancy, self.expectancy_pct * 100.0);
+        println!(" > 평균 수익: {:.4}% | 평균 손실: {:.4}% | 최대 연속 손실: {} 회",
+            self.avg_win_pct * 100.0, self.avg_loss_pct * 100.0, self.longest_losing_streak);
+        println!(" > 평균 보유 시간: {} | 시장 노출: {}", format_ms(self.avg_holding_ms), format_pct(self.time_in_market_pct));
+    }
+}
+
+fn mean(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+// 자산 곡선 전체 기간 (ms)
+fn period_ms(equity_curve: &[EquityPoint]) -> Option<i64> {
+    let first = parse_backtest_date(&equity_curve.first()?.date)?;
+    let last = parse_backtest_date(&equity_curve.last()?.date)?;
+    Some(last - first)
+}
+
+// 거래 보유 구간의 합집합 길이 (ms). 날짜를 해석할 수 없는 거래가 있으면 None
+fn time_in_market_ms(trades: &[TradeRecord]) -> Option<i64> {
+    let mut intervals = trades.iter()
+        .map(|trade| Some((parse_backtest_date(&trade.entry_date)?, parse_backtest_date(&trade.exit_date)?)))
+        .collect::<Option<Vec<(i64, i64)>>>()?;
+    intervals.sort();
+
+    let mut total = 0;
+    let mut current: Option<(i64, i64)> = None;
+    for (start, end) in intervals {
+        current = match current {
+            Some((current_start, current_end)) if start <= current_end => Some((current_start, current_end.max(end))),
+            Some((current_start, current_end)) => {
+                total += current_end - current_start;
+                Some((start, end))
+            }
+            None => Some((start, end)),
+        };
+    }
+    Some(total + current.map(|(start, end)| end - start).unwrap_or(0))
+}
+
+// 최대 하락률과 최고점 회복까지 가장 오래 걸린 시간. 최고점 아래로 내려간 적이 없으면 0
+fn drawdown(equity_curve: &[EquityPoint]) -> (f64, Option<i64>) {
+    let mut max_drawdown_pct: f64 = 0.0;
+    let mut max_duration: Option<i64> = Some(0);
+    let mut peak = f64::MIN;
+    let mut peak_time: Option<i64> = None;
+    let mut under_water = false;
+
+    for point in equity_curve {
+        let time = parse_backtest_date(&point.date);
+        if time.is_none() {
+            max_duration = None;
+        }
+
+        if point.equity >= peak {
+            // 최고점을 회복한 시점까지의 구간
+            if let (true, Some(time), Some(peak_time)) = (under_water, time, peak_time) {
+                max_duration = max_duration.map(|max| max.max(time - peak_time));
+            }
+            peak = point.equity;
+            peak_time = time;
+            under_water = false;
+        } else {
+            under_water = true;
+            max_drawdown_pct = max_drawdown_pct.max(1.0 - point.equity / peak);
+        }
+    }
+
+    // 회복하지 못한 채 끝나는 구간
+    let last_time = equity_curve.last().and_then(|point| parse_backtest_date(&point.date));
+    if let (true, Some(last_time), Some(peak_time)) = (under_water, last_time, peak_time) {
+        max_duration = max_duration.map(|max| max.max(last_time - peak_time));
+    }
+
+    (max_drawdown_pct, if equity_curve.is_empty() { None } else { max_duration })
+}
@@ -0,0 +1,269 @@
+use std::{fmt, sync::{atomic::{AtomicBool, Ordering}, Arc}};
+
+use crate::{backtest::ledger::parse_backtest_date, core::{position::PositionState, signal::Signal}};
+
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+
+/// 리스크 관리 설정. `None`인 제한은 적용하지 않음
+#[derive(Clone, Debug)]
+pub struct RiskConfig {
+    pub max_risk_per_trade_pct: Option<f64>, // 평가 자산 대비 한 번의 진입에서 스탑까지 잃을 수 있는 최대 비율
+    pub max_daily_loss_pct: Option<f64>, // UTC 하루 시작 평가 자산 대비 최대 실현 손실 비율. 넘으면 그날은 진입하지 않음
+    pub max_consecutive_losses: Option<u32>, // 연속 손실 거래 수. 도달하면 cooldown_ms 동안 진입하지 않음
+    pub cooldown_ms: i64,
+    pub max_exposure_pct: Option<f64>, // 평가 자산 대비 마켓 하나의 최대 포지션 비중
+}
+
+impl RiskConfig {
+    /// 제한 없음
+    pub fn new() -> Self {
+        Self {
+            max_risk_per_trade_pct: None,
+            max_daily_loss_pct: None,
+            max_consecutive_losses: None,
+            cooldown_ms: DAY_MS,
+            max_exposure_pct: None,
+        }
+    }
+}
+
+impl Default for RiskConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 모든 리스크 관리자가 공유하는 전체 진입 중단 스위치
+///
+/// 복제하면 같은 스위치를 가리킴. 켜져 있으면 모든 진입 신호를 거절함 (청산 신호는 그대로 처리)
+#[derive(Clone, Debug, Default)]
+pub struct KillSwitch(Arc<AtomicBool>);
+
+impl KillSwitch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn trip(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn reset(&self) {
+        self.0.store(false, Ordering::SeqCst);
+    }
+
+    pub fn is_tripped(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// 진입 신호를 거절한 이유
+#[derive(Clone, Debug, PartialEq)]
+pub enum RejectReason {
+    KillSwitch,
+    DailyLossLimit { loss_pct: f64 },
+    Cooldown { until: i64 },
+    /// 스탑이 진입가 이상이라 거래당 위험을 계산할 수 없음
+    InvalidStop { stop_price: f64 },
+    /// 거래당 위험 한도를 이미 사용함
+    RiskBudgetUsed,
+    /// 마켓 최대 비중에 도달함
+    ExposureLimit { exposure_pct: f64 },
+}
+
+impl fmt::Display for RejectReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RejectReason::KillSwitch => write!(f, "kill switch"),
+            RejectReason::DailyLossLimit { loss_pct } => write!(f, "daily loss limit ({:.2}%)", loss_pct * 100.0),
+            RejectReason::Cooldown { until } => write!(f, "consecutive losses cooldown until {}", until),
+            RejectReason::InvalidStop { stop_price } => write!(f, "stop price {:.4} is not below entry", stop_price),
+            RejectReason::RiskBudgetUsed => write!(f, "risk per trade budget used"),
+            RejectReason::ExposureLimit { exposure_pct } => write!(f, "market exposure limit ({:.2}%)", exposure_pct * 100.0),
+        }
+    }
+}
+
+/// 진입 신호 검사 결과
+#[derive(Clone, Debug, PartialEq)]
+pub enum RiskDecision {
+    Allow,
+    /// 신호의 asset_pct를 줄여서 진입
+    Resize { asset_pct: f64, reason: String },
+    Reject(RejectReason),
+}
+
+/// 전략 신호와 체결 사이에서 진입 크기와 진입 여부를 제한
+///
+/// 진입 (`Buy`, `AddToPosition`)만 검사하고 청산 신호는 그대로 통과시킴.
+/// 청산 결과는 `record_exit`로 알려줘야 일일 손실과 연속 손실이 갱신됨
+#[derive(Clone, Debug)]
+pub struct RiskManager {
+    pub config: RiskConfig,
+    pub kill_switch: KillSwitch,
+    pub consecutive_losses: u32,
+    pub cooldown_until: Option<i64>,
+    day: Option<i64>, // UTC 기준 일 번호
+    day_start_equity: f64,
+    daily_pnl: f64,
+    round_trip_pnl: f64, // 진입부터 현재까지 부분 청산을 포함한 실현 손익
+}
+
+impl RiskManager {
+    pub fn new(config: RiskConfig) -> Self {
+        Self::with_kill_switch(config, KillSwitch::new())
+    }
+
+    /// 다른 리스크 관리자와 킬 스위치를 공유
+    pub fn with_kill_switch(config: RiskConfig, kill_switch: KillSwitch) -> Self {
+        Self {
+            config,
+            kill_switch,
+            consecutive_losses: 0,
+            cooldown_until: None,
+            day: None,
+            day_start_equity: 0.0,
+            daily_pnl: 0.0,
+            round_trip_pnl: 0.0,
+        }
+    }
+
+    /// 오늘 실현 손익
+    pub fn daily_pnl(&self) -> f64 {
+        self.daily_pnl
+    }
+
+    // 날짜가 바뀌었으면 일일 손익을 초기화하고 시작 평가 자산을 기록
+    fn roll_day(&mut self, time: i64, equity: f64) {
+        let day = time.div_euclid(DAY_MS);
+        if self.day != Some(day) {
+            self.day = Some(day);
+            self.day_start_equity = equity;
+            self.daily_pnl = 0.0;
+        }
+    }
+
+    /// 진입 신호 검사
+    ///
+    /// equity: 평가 자산, cash: 진입에 쓸 수 있는 현금, position: 현재 포지션
+    pub fn check_entry(&mut self, signal: &Signal, position: &PositionState, equity: f64, cash: f64, current_price: f64, current_date: &str) -> RiskDecision {
+        let (asset_pct, stop_price) = match (signal, position) {
+            (Signal::Buy { asset_pct, initial_trailing_stop, .. }, PositionState::None) => (*asset_pct, *initial_trailing_stop),
+            (Signal::AddToPosition { asset_pct, .. }, PositionState::InPosition { trailing_stop_price, .. }) => (*asset_pct, *trailing_stop_price),
+            _ => return RiskDecision::Allow,
+        };
+
+        if self.kill_switch.is_tripped() {
+            return RiskDecision::Reject(RejectReason::KillSwitch);
+        }
+
+        if let Some(time) = parse_backtest_date(current_date) {
+            self.roll_day(time, equity);
+            match self.cooldown_until {
+                Some(until) if time < until => return RiskDecision::Reject(RejectReason::Cooldown { until }),
+                Some(_) => {
+                    self.cooldown_until = None;
+                    self.consecutive_losses = 0;
+                }
+                None => {}
+            }
+        }
+
+        if let Some(max_loss_pct) = self.config.max_daily_loss_pct {
+            let loss_pct = if self.day_start_equity > 0.0 { -self.daily_pnl / self.day_start_equity } else { 0.0 };
+            if loss_pct >= max_loss_pct {
+                return RiskDecision::Reject(RejectReason::DailyLossLimit { loss_pct });
+            }
+        }
+
+        if cash <= 0.0 || current_price <= 0.0 {
+            return RiskDecision::Allow;
+        }
+        let mut amount = cash * asset_pct;
+        let mut reasons = Vec::new();
+
+        if let Some(risk_pct) = self.config.max_risk_per_trade_pct {
+            let stop_distance = (current_price - stop_price) / current_price;
+            if stop_distance <= 0.0 {
+                return RiskDecision::Reject(RejectReason::InvalidStop { stop_price });
+            }
+            // 보유 중인 lot들이 스탑까지 잃을 수 있는 금액을 뺀 나머지 위험 한도
+            let open_risk = position.lots().iter().map(|lot| (lot.size * (lot.entry_price - stop_price)).max(0.0)).sum::<f64>();
+            let budget = equity * risk_pct - open_risk;
+            if budget <= 0.0 {
+                return RiskDecision::Reject(RejectReason::RiskBudgetUsed);
+            }
+            let max_amount = budget / stop_distance;
+            if amount > max_amount {
+                amount = max_amount;
+                reasons.push(format!("risk per trade {:.2}% (stop distance {:.2}%)", risk_pct * 100.0, stop_distance * 100.0));
+            }
+        }
+
+        if let Some(exposure_pct) = self.config.max_exposure_pct {
+            let exposure = position.size() * current_price;
+            let room = equity * exposure_pct - exposure;
+            if room <= 0.0 {
+                return RiskDecision::Reject(RejectReason::ExposureLimit { exposure_pct });
+            }
+            if amount > room {
+                amount = room;
+                reasons.push(format!("market exposure {:.2}%", exposure_pct * 100.0));
+            }
+        }
+
+        if reasons.is_empty() {
+            RiskDecision::Allow
+        } else {
+            RiskDecision::Resize { asset_pct: amount / cash, reason: reasons.join(", ") }
+        }
+    }
+
+    /// 검사 결과를 적용한 신호. 거절되면 `Signal::Hold`
+    pub fn apply(&mut self, signal: &Signal, position: &PositionState, equity: f64, cash: f64, current_price: f64, current_date: &str) -> (Signal, RiskDecision) {
+        let decision = self.check_entry(signal, position, equity, cash, current_price, current_date);
+        let signal = match (&decision, signal) {
+            (RiskDecision::Allow, signal) => signal.clone(),
+            (RiskDecision::Reject(_), _) => Signal::Hold,
+            (RiskDecision::Resize { asset_pct, .. }, Signal::Buy { reason, initial_trailing_stop, take_profit, .. }) => Signal::Buy {
+                reason: reason.clone(),
+                initial_trailing_stop: *initial_trailing_stop,
+                take_profit: *take_profit,
+                asset_pct: *asset_pct,
+            },
+            (RiskDecision::Resize { asset_pct, .. }, Signal::AddToPosition { reason, .. }) => Signal::AddToPosition { reason: reason.clone(), asset_pct: *asset_pct },
+            (RiskDecision::Resize { .. }, signal) => signal.clone(),
+        };
+        (signal, decision)
+    }
+
+    /// 청산 결과 기록
+    ///
+    /// pnl: 이번 청산의 실현 손익, equity: 청산 후 평가 자산, closed: 포지션이 모두 청산되었는지.
+    /// 포지션이 모두 청산되면 진입부터의 실현 손익으로 연속 손실을 셈
+    pub fn record_exit(&mut self, pnl: f64, equity: f64, closed: bool, current_date: &str) {
+        let time = parse_backtest_date(current_date);
+        if let Some(time) = time {
+            self.roll_day(time, equity - pnl);
+        }
+        self.daily_pnl += pnl;
+        self.round_trip_pnl += pnl;
+        if !closed {
+            return;
+        }
+
+        if self.round_trip_pnl > 0.0 {
+            self.consecutive_losses = 0;
+        } else {
+            self.consecutive_losses += 1;
+        }
+        self.round_trip_pnl = 0.0;
+
+        match (self.config.max_consecutive_losses, time) {
+            (Some(max_losses), Some(time)) if self.consecutive_losses >= max_losses && self.cooldown_until.is_none() => {
+                self.cooldown_until = Some(time + self.config.cooldown_ms);
+            }
+            _ => {}
+        }
+    }
+}
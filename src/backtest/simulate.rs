@@ -1,323 +1,460 @@
-use std::{cell::RefCell, collections::{BTreeMap, HashMap}, io::Write, rc::Rc};
+use std::{cell::{Cell, RefCell}, collections::HashMap, rc::Rc};
 
 use chrono::Utc;
 use tokio::sync::mpsc;
 
-use crate::{backtest::{fetch::fetch_n_minute_candles, lib::{BacktestParams, BacktesterState}}, core::{candle::{Candle, CandleBase, CandleTrait}, 
-orderbook::Orderbook, signal::{Signal, SignalReason}, ticker::Ticker, trade::{filter_trades_by_same_minute, is_trade_time_previous_minute, Trade}}, 
-helper::footprint::{footprint, log_footprint, FootprintTrade, FootprintValue}, strategy::{candle_pattern, lib::MarketState, of1::{calculate_of1_indicator_every_1mcandle, of1, Of1Params, Of1State}, orderbook}, 
-upbit_api::realtime::lib::{listen_realtime_data, RealtimeCallback}};
-
-// Trade를 FootprintTrade로 변환하는 함수
-fn convert_trade_to_footprint_trade(trade: &Trade) -> FootprintTrade {
-    FootprintTrade {
-        ask_bid: trade.ask_bid.clone(),
-        price: trade.trade_price,
-        volume: trade.trade_volume,
-    }
-}
+use crate::{backtest::{fetch::fetch_n_minute_candles, ledger::parse_backtest_date, lib::{BacktestParams, BacktesterState}, paper::{split_market, PaperBroker, PaperTrader}, risk::RiskManager, sizing::PositionSizing, slippage::SlippageModel, state_store::{StateStore, TradingState}}, core::{candle::{Candle, CandleBase, CandleTrait}, 
+orderbook::Orderbook, ticker::Ticker, trade::Trade}, 
+strategy::{lib::Strategy, registry::{create_candle_strategy, create_strategy, StrategyParamError}}, 
+upbit_api::{client::UpbitClient, realtime::{lib::{listen_realtime_data_with_config, RealtimeCallback, RealtimeConfig, RealtimeGap}, record::{read_warm_up_candles, replay_realtime_data, ReplaySpeed}}}};
 
 
 #[derive(Clone)]
 pub struct SimulationConfig {
     pub enable_log: bool,
+    pub strategy_name: String,
+    pub enable_webhook_log: bool,
+    /// 실시간 백테스트 중 수신한 메시지를 기록할 경로 (gzip 압축된 줄 단위 JSON)
+    pub record_path: Option<String>,
+    /// 실시간 백테스트에서는 수신한 호가로 `SlippageModel::OrderbookWalk`를 사용할 수 있음
+    pub slippage: SlippageModel,
+    /// 종목마다 복제해서 사용하므로 킬 스위치는 모든 종목이 공유함
+    pub risk: Option<RiskManager>,
+    /// 전략의 진입 신호 크기를 정하는 방식. 백테스트와 모의 거래 모두 적용
+    pub sizing: PositionSizing,
+    /// 모의 거래 상태 (`StateStore`) 파일 경로. 지정하면 시작할 때 복원하고 포지션이 바뀔 때마다 저장
+    pub state_path: Option<String>,
 }
 
 impl SimulationConfig {
     pub fn new() -> Self {
         Self {
             enable_log: true,
+            strategy_name: "of1".to_string(),
+            enable_webhook_log: true,
+            record_path: None,
+            slippage: SlippageModel::None,
+            risk: None,
+            sizing: PositionSizing::SignalPct,
+            state_path: None,
         }
     }
 }
 
 
-pub fn simulate(candles: Vec<Candle>, backtester: &mut BacktesterState) {
-    // let mut state = MarketState::new();
-    let mut state = candle_pattern::CandlePatternStrategyState::new();
-    let mut config = candle_pattern::CandlePatternStrategyConfig::new();
-    config.enable_log = false;
+/// `backtester.params.strategy_name`으로 선택된 전략을 캔들 데이터로 시뮬레이션
+///
+/// candles는 오래된 순서로 정렬되어 있어야 함. 등록되지 않은 전략 이름이면 에러
+pub fn simulate(candles: Vec<Candle>, backtester: &mut BacktesterState) -> Result<(), StrategyParamError> {
+    simulate_with_lower_timeframe(candles, &[], backtester)
+}
 
-    // state.historical_candles = history_candles.into_iter().map(|c| c as Box<dyn CandleTrait>).collect();
+/// 하위 타임프레임 캔들을 함께 사용하는 캔들 시뮬레이션
+///
+/// 한 봉에서 익절/스탑에 모두 닿았고 `IntrabarPolicy::LowerTimeframe`이면 해당 봉 구간의 lower_candles로 체결 순서를 확인함.
+/// candles와 lower_candles 모두 오래된 순서로 정렬되어 있어야 함
+pub fn simulate_with_lower_timeframe(candles: Vec<Candle>, lower_candles: &[Candle], backtester: &mut BacktesterState) -> Result<(), StrategyParamError> {
+    let strategy_name = &backtester.params.strategy_name;
+    let mut strategy = create_candle_strategy(strategy_name, &[], false)?;
 
     let first_trade_utc = candles.first().unwrap().get_candle_date_time_utc().to_string();
     println!("first_trade_utc: {}", first_trade_utc);
 
-    let last_price = candles.last().unwrap().get_trade_price();
-    let last_candle_date_time_utc = candles.last().unwrap().get_candle_date_time_utc().to_string();
+    run_candles(&candles, lower_candles, strategy.as_mut(), backtester);
+
+    backtester.print_results();
+    Ok(())
+}
+
+/// 주어진 전략으로 캔들을 순서대로 처리하고 마지막 가격으로 포지션을 청산 (결과 출력 없음)
+///
+/// 파라미터 최적화처럼 전략을 직접 만들어 여러 번 실행할 때 사용
+pub fn run_candles(candles: &[Candle], lower_candles: &[Candle], strategy: &mut dyn Strategy, backtester: &mut BacktesterState) {
+    let Some(last_candle) = candles.last() else { return };
+    let intrabar_policy = backtester.params.intrabar_policy;
 
-    for candle in candles {
+    for (index, candle) in candles.iter().enumerate() {
         let current_price = candle.get_trade_price();
-        // println!("current_price: {}", current_price);
         let candle_date_time_utc = candle.get_candle_date_time_utc().to_string();
-        backtester.check_and_close_position(current_price, &candle_date_time_utc);
-        let signal = candle_pattern::candle_pattern_strategy(&mut state, &config, &mut backtester.get_position(), Some(candle));
+        let lower = lower_candles_in_bar(candles, index, lower_candles);
+        backtester.check_and_close_position_with_candle(candle, intrabar_policy, lower);
+        // 스탑 체결의 슬리피지는 직전 캔들까지만 보고, 마감된 캔들은 진입 신호부터 사용
+        backtester.market.push_candle(candle);
+        let signal = strategy.on_candle(candle, backtester.get_position());
         backtester.handle_signal(&signal, current_price, &candle_date_time_utc);
     }
 
-    backtester.handle_signal(&Signal::Sell(SignalReason {
-        reason: "End of test".to_string(),
-    }), last_price, &last_candle_date_time_utc);
+    backtester.close_at_end(last_candle.get_trade_price(), last_candle.get_candle_date_time_utc());
+}
 
-    backtester.print_results();
+// candles[index] 구간 (다음 캔들 시작 전까지)에 속하는 하위 타임프레임 캔들
+fn lower_candles_in_bar<'a>(candles: &[Candle], index: usize, lower_candles: &'a [Candle]) -> &'a [Candle] {
+    if lower_candles.is_empty() {
+        return &[];
+    }
+    let start_of = |candle: &Candle| parse_backtest_date(candle.get_candle_date_time_utc());
+    let Some(start) = start_of(&candles[index]) else { return &[] };
+    let end = match (candles.get(index + 1), index.checked_sub(1).and_then(|i| candles.get(i))) {
+        (Some(next), _) => start_of(next),
+        // 마지막 캔들은 직전 캔들 간격만큼을 구간으로 봄
+        (None, Some(previous)) => start_of(previous).map(|previous| start + (start - previous)),
+        (None, None) => None,
+    };
+    let Some(end) = end else { return &[] };
+
+    let from = lower_candles.partition_point(|lower| start_of(lower).is_some_and(|time| time < start));
+    let to = lower_candles.partition_point(|lower| start_of(lower).is_some_and(|time| time < end));
+    &lower_candles[from..to.max(from)]
 }
 
 
 /// 실시간 백테스트
 ///
 /// params:
+/// - client: Upbit API 클라이언트
 /// - codes: 종목 코드 배열
 /// - shutdown_recv: 종료 신호 수신 채널
-pub async fn simulate_with_realtime_data(codes: &[&str], shutdown_recv: &mut mpsc::Receiver<()>, config: &SimulationConfig) -> Vec<BacktesterState> {
-    println!("realtime backtest start - codes: {:?}", codes);
+/// - config: 로그 여부, 실행할 전략 이름, 메시지 기록 경로
+///
+/// 등록되지 않은 전략 이름이면 연결하지 않고 에러를 반환
+pub async fn simulate_with_realtime_data(client: &UpbitClient, codes: &[&str], shutdown_recv: &mut mpsc::Receiver<()>, config: &SimulationConfig)
+-> Result<Vec<BacktesterState>, StrategyParamError> {
+    println!("realtime backtest start - codes: {:?}, strategy: {}", codes, config.strategy_name);
     
     let mut backtesters = Vec::new();
     let mut callback_maps = HashMap::new();
+    let mut warm_up_candles = HashMap::new();
 
     // 각 코드에 대해 백테스터와 상태 초기화
     for &code in codes {
-        let backtest_params = BacktestParams::default(code, "of1");
-        let backtester = Rc::new(RefCell::new(BacktesterState::new(backtest_params)));
-        let of1_state = Rc::new(RefCell::new(Of1State::new()));
-        let of1_params = Of1Params::new();
-        let top_n_trade_volume_avg = Rc::new(RefCell::new(0.0));
-
-        // prefetch
-        {
-            println!("prefetching for {}...", code);
-            let formatted_time = Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string();
-            let pre_fetch_candles = fetch_n_minute_candles(code, 20, &formatted_time, 1).await.unwrap();
-            for candle_trait in pre_fetch_candles {
-                let candle = Candle {
-                    base: CandleBase {
-                        market: candle_trait.get_market().to_string(),
-                        candle_date_time_utc: candle_trait.get_candle_date_time_utc().to_string(),
-                        candle_date_time_kst: candle_trait.get_candle_date_time_kst().to_string(),
-                        opening_price: candle_trait.get_opening_price(),
-                        high_price: candle_trait.get_high_price(),
-                        low_price: candle_trait.get_low_price(),
-                        trade_price: candle_trait.get_trade_price(),
-                        timestamp: candle_trait.get_timestamp(),
-                        candle_acc_trade_price: candle_trait.get_candle_acc_trade_price(),
-                        candle_acc_trade_volume: candle_trait.get_candle_acc_trade_volume(),
-                    }
-                };
-                of1_state.borrow_mut().history_candles.push_back(candle);
-            }
-            println!("prefetching done for {}", code);
-        }
-
-        // 미리 계산
-        {
-            let mut of1_state_ref = of1_state.borrow_mut();
-            calculate_of1_indicator_every_1mcandle(&mut of1_state_ref, &of1_params);
-        }
-
-        let (top_n_trade_volume_avg_fn, log_footprint_fn, log_indicator_fn) = get_simulate_log_fns();
-        
-        // 클로저 정의
-        let trade_fn = {
-            let of1_state = of1_state.clone();
-            move |trade: &Trade| {
-                let mut of1_state_ref = of1_state.borrow_mut();
-                of1_state_ref.trades.push(trade.clone());
-            }
-        };
-
-        let orderbook_fn = |_orderbook: &Orderbook| {
-            // println!("orderbook: {:?}", orderbook);
-        };
-        
-        let candle_fn = {
-            let of1_state = of1_state.clone();
-            let of1_params = of1_params.clone();
-            let top_n_trade_volume_avg = top_n_trade_volume_avg.clone();
-            let top_n_trade_volume_avg_fn = top_n_trade_volume_avg_fn.clone();
-            let log_footprint_fn = log_footprint_fn.clone();
-            let config = config.clone();
-            move |candle: &Candle| {
-                let kst = candle.get_candle_date_time_kst();
-                let mut of1_state_ref = of1_state.borrow_mut();
-                if of1_state_ref.current_mutation_candle.is_some() {
-                    let current_mutation_candle_kst = of1_state_ref.current_mutation_candle.as_ref().unwrap().get_candle_date_time_kst();
-                    // 동일 시간대 캔들이면 계속 갱신
-                    if current_mutation_candle_kst == kst {
-                        of1_state_ref.current_mutation_candle = Some(candle.clone());
-                        return;
-                    }
-                } else {
-                    of1_state_ref.current_mutation_candle = Some(candle.clone());
-                    return;
-                }
-         
-                // 다른 시간대 캔들이면 이전 캔들을 히스토리에 추가하고 현재 캔들을 갱신
-                let current_mutation_candle = of1_state_ref.current_mutation_candle.take().unwrap();
-                let previous_kst = current_mutation_candle.get_candle_date_time_kst().to_string();
-
-                of1_state_ref.history_candles.push_back(current_mutation_candle);
-                of1_state_ref.current_mutation_candle = Some(candle.clone());
-
-                // of1 지표 갱신
-                calculate_of1_indicator_every_1mcandle(&mut of1_state_ref, &of1_params);
-
-                // 이전 시간대(분 단위)의 거래 내역만 필터하여 FootprintTrade로 변환
-                let filtered_trades = filter_trades_by_same_minute(&of1_state_ref.trades, &previous_kst);
-                let footprint_trades = filtered_trades.iter().map(|trade| convert_trade_to_footprint_trade(trade)).collect::<Vec<FootprintTrade>>();
-                let footprint = footprint(&footprint_trades);
-                let recent_candle_10 = of1_state_ref.history_candles.iter().rev().take(10).cloned().collect::<Vec<Candle>>();
-                *top_n_trade_volume_avg.borrow_mut() = top_n_trade_volume_avg_fn(&recent_candle_10);
-
-                if config.enable_log {
-                    // 이전 캔들 폭 계산
-                    let previous_candle = of1_state_ref.history_candles.back().unwrap();
-                    let range = (previous_candle.get_high_price() - previous_candle.get_low_price()).abs();
-                    let avg_range = of1_state_ref.indicator.candle_20_avg_candle_range;
-                    let volume = previous_candle.get_candle_acc_trade_volume();
-                    let avg_volume = of1_state_ref.indicator.candle_10_avg_volume;
-                    let bullish = previous_candle.get_opening_price() < previous_candle.get_trade_price();
-                    println!("\nrange: {} | avg_range: {} | volume: {} | avg_volume: {} | bullish: {}", range, avg_range, volume, avg_volume, bullish);
-
-                    log_footprint_fn(&footprint);
-                }
-
-                // 새로운 footprint 추가
-                of1_state_ref.footprints.push(footprint);
-
-                // 3분전 footprint 제거
-                let current_kst = candle.get_candle_date_time_kst();
-                let parsed_kst = chrono::NaiveDateTime::parse_from_str(current_kst, "%Y-%m-%dT%H:%M:%S");
-                match parsed_kst {
-                    Ok(kst) => {
-                        let three_minutes_ago = kst - chrono::Duration::minutes(3);
-                        let three_minutes_ago_str = three_minutes_ago.format("%Y-%m-%dT%H:%M:%S").to_string();
-                        of1_state_ref.trades.retain(|trade| !is_trade_time_previous_minute(trade, &three_minutes_ago_str));
-                    }
-                    Err(_) => {
-                        println!("failed to parse kst: {}", current_kst);
-                    }
-                }
-            }
-        };
-
-        let ticker_fn = {
-            let of1_state = of1_state.clone();
-            let of1_params = of1_params.clone();
-            let backtester = backtester.clone();
-            let top_n_trade_volume_avg = top_n_trade_volume_avg.clone();
-            let log_indicator_fn = log_indicator_fn.clone();
-            let config = config.clone();
-            move |ticker: &Ticker| {
-                let current_price = ticker.trade_price;
-                let current_timestamp = ticker.trade_timestamp;
-                let mut backtester_ref = backtester.borrow_mut();
-                let mut of1_state_ref = of1_state.borrow_mut();
-                of1_state_ref.current_ticker = Some(ticker.clone());
-
-                backtester_ref.check_and_close_position(current_price, &current_timestamp.to_string());
-                let signal = of1(&mut of1_state_ref, &of1_params, &mut backtester_ref.get_position());
-                backtester_ref.handle_signal(&signal, current_price, &current_timestamp.to_string()); // 포지션 관리
-
-                if of1_state_ref.current_mutation_candle.is_none() {
-                    return;
-                }
-
-                let indicator = Indicator {
-                    top_n_trade_volume_avg: *top_n_trade_volume_avg.borrow(),
-                    previous_candle: of1_state_ref.current_mutation_candle.as_ref().unwrap().clone(),
-                    current_price: current_price,
-                    current_candle_volume: of1_state_ref.current_mutation_candle.as_ref().unwrap().get_candle_acc_trade_volume(),
-                };
-
-                if config.enable_log {
-                    log_indicator_fn(&indicator);
-                }
-            }
-        };
-
-        let exit_fn = {
-            let backtester = backtester.clone();
-            let code = code.to_string();
-            move || {
-                let backtester_ref = backtester.borrow();
-                let win_count = backtester_ref.win_count;
-                let loss_count = backtester_ref.loss_count;
-                let win_rate = win_count as f64 / (win_count + loss_count) as f64;
-                let total_pnl_pct = backtester_ref.total_pnl_pct;
-
-                println!("backtest result {} - [win: {} | loss: {} | win_rate: {:.2}% | total_pnl_pct: {:.2}%]", code, win_count, loss_count, win_rate * 100.0, total_pnl_pct * 100.0);
-            }
-        };
-
-        // 콜백 맵에 추가
-        callback_maps.insert(code, RealtimeCallback {
-            orderbook_fn: Box::new(orderbook_fn),
-            trade_fn: Box::new(trade_fn),
-            ticker_fn: Box::new(ticker_fn),
-            candle_fn: Box::new(candle_fn),
-            exit_fn: Box::new(exit_fn),
-        });
+        let mut strategy = create_strategy(&config.strategy_name, config.enable_log)
+            .ok_or_else(|| StrategyParamError::UnknownStrategy(config.strategy_name.clone()))?;
+        let candles = prefetch_and_warm_up(client, code, strategy.as_mut()).await;
+        warm_up_candles.insert(code.to_string(), candles);
 
+        let (backtester, callback) = create_realtime_backtest(code, Rc::new(RefCell::new(strategy)), config);
+        callback_maps.insert(code, callback);
         backtesters.push(backtester);
     }
 
-    listen_realtime_data(codes, shutdown_recv, &mut callback_maps).await;
+    let realtime_config = RealtimeConfig {
+        record_path: config.record_path.clone(),
+        warm_up_candles,
+        ..RealtimeConfig::new()
+    };
+    listen_realtime_data_with_config(client, codes, shutdown_recv, &mut callback_maps, &realtime_config).await;
     
     // 모든 백테스터 결과 반환
-    backtesters.into_iter().map(|backtester| backtester.borrow().clone()).collect()
+    Ok(backtesters.into_iter().map(|backtester| backtester.borrow().clone()).collect())
 }
 
-pub struct Indicator {
-    top_n_trade_volume_avg: f64,
-    // 이전 캔들
-    previous_candle: Candle,
-    current_price: f64,
-    current_candle_volume: f64,
+/// 실시간 데이터로 모의 거래
+///
+/// 전략 신호를 `PaperBroker` 주문으로 실행하며, 지정가 주문은 실제 체결과 호가 대기 순서를 기준으로 체결됨.
+/// 모든 종목이 initial_balance KRW 계좌 하나를 함께 사용하고, 종료 시 모의 거래소 상태를 반환.
+/// `state_path`를 지정하면 같은 전략으로 저장된 포지션을 복원하며, 복원한 보유 수량은 모의 계좌에 입금함
+pub async fn simulate_with_paper_trading(client: &UpbitClient, codes: &[&str], shutdown_recv: &mut mpsc::Receiver<()>, config: &SimulationConfig,
+    initial_balance: f64) -> Result<PaperBroker, StrategyParamError> {
+    println!("paper trading start - codes: {:?}, strategy: {}", codes, config.strategy_name);
+
+    let broker = Rc::new(RefCell::new(PaperBroker::new("KRW", initial_balance)));
+    let persistence = config.state_path.as_ref().map(|path| {
+        let store = StateStore::new(path);
+        let state = store.load().unwrap_or_else(|e| {
+            eprintln!("모의 거래 상태 불러오기 실패 ({}): {}", path, e);
+            TradingState::new()
+        });
+        Rc::new(PaperPersistence { store, state: RefCell::new(state) })
+    });
+    let mut callback_maps = HashMap::new();
+    let mut warm_up_candles = HashMap::new();
+    for &code in codes {
+        let mut strategy = create_strategy(&config.strategy_name, config.enable_log)
+            .ok_or_else(|| StrategyParamError::UnknownStrategy(config.strategy_name.clone()))?;
+        let candles = prefetch_and_warm_up(client, code, strategy.as_mut()).await;
+        warm_up_candles.insert(code.to_string(), candles);
+        let mut trader = PaperTrader::new(code, strategy, config.enable_log);
+        trader.sizing = config.sizing.clone();
+        trader.risk = config.risk.clone();
+        if let Some(persistence) = &persistence {
+            restore_paper_trader(&mut trader, &mut broker.borrow_mut(), &persistence.state.borrow());
+        }
+        callback_maps.insert(code, create_paper_trading_callback(trader, broker.clone(), persistence.clone()));
+    }
+
+    let realtime_config = RealtimeConfig {
+        record_path: config.record_path.clone(),
+        warm_up_candles,
+        ..RealtimeConfig::new()
+    };
+    listen_realtime_data_with_config(client, codes, shutdown_recv, &mut callback_maps, &realtime_config).await;
+
+    let broker = broker.borrow().clone();
+    println!("paper trading result - equity: {:.0} KRW", broker.equity("KRW"));
+    Ok(broker)
+}
+
+/// 최근 1분 캔들 20개로 전략 warm up. 사용한 캔들을 반환 (기록 파일에 저장)
+async fn prefetch_and_warm_up(client: &UpbitClient, code: &str, strategy: &mut dyn Strategy) -> Vec<Candle> {
+    println!("prefetching for {}...", code);
+    let formatted_time = Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string();
+    let pre_fetch_candles = fetch_n_minute_candles(client, code, 20, &formatted_time, 1).await.unwrap();
+    // upbit은 최신 캔들부터 반환하므로 오래된 순서로 뒤집음
+    let candles = pre_fetch_candles.iter().rev().map(|candle_trait| Candle {
+        base: CandleBase {
+            market: candle_trait.get_market().to_string(),
+            candle_date_time_utc: candle_trait.get_candle_date_time_utc().to_string(),
+            candle_date_time_kst: candle_trait.get_candle_date_time_kst().to_string(),
+            opening_price: candle_trait.get_opening_price(),
+            high_price: candle_trait.get_high_price(),
+            low_price: candle_trait.get_low_price(),
+            trade_price: candle_trait.get_trade_price(),
+            timestamp: candle_trait.get_timestamp(),
+            candle_acc_trade_price: candle_trait.get_candle_acc_trade_price(),
+            candle_acc_trade_volume: candle_trait.get_candle_acc_trade_volume(),
+        }
+    }).collect::<Vec<Candle>>();
+    strategy.warm_up(&candles);
+    println!("prefetching done for {}", code);
+    candles
+}
+
+// 모의 거래 상태 저장소와 저장할 전체 상태 (모든 종목)
+struct PaperPersistence {
+    store: StateStore,
+    state: RefCell<TradingState>,
+}
+
+impl PaperPersistence {
+    // 트레이더의 포지션이 바뀌었으면 상태 파일을 다시 씀
+    fn save_if_changed(&self, trader: &mut PaperTrader) {
+        if !trader.take_state_changed() {
+            return;
+        }
+        let mut state = self.state.borrow_mut();
+        state.markets.insert(trader.code.clone(), trader.market_state());
+        if let Err(e) = self.store.save(&state) {
+            eprintln!("모의 거래 상태 저장 실패: {}", e);
+        }
+    }
 }
 
-pub fn get_simulate_log_fns() -> (impl Fn(&Vec<Candle>) -> f64 + Clone, impl Fn(&BTreeMap<String, FootprintValue>) + Clone, impl Fn(&Indicator) + Clone) {
-    // 최근 10개 캔들의 거래량 평균
-    let top_n_trade_volume_avg_fn = |candles: &Vec<Candle>| {
-        let recent_candle_10 = candles.iter().rev().take(10).collect::<Vec<&Candle>>();
-        let recent_candle_10_avg = recent_candle_10.iter().map(|c| c.get_candle_acc_trade_volume()).sum::<f64>() / recent_candle_10.len() as f64;
-        recent_candle_10_avg
+// 같은 전략으로 저장된 포지션이 있으면 보유 수량을 모의 계좌에 넣고 복원
+fn restore_paper_trader(trader: &mut PaperTrader, broker: &mut PaperBroker, state: &TradingState) {
+    let Some(market_state) = state.markets.get(&trader.code) else { return };
+    if market_state.strategy_name != trader.strategy.name() {
+        println!("{} - 저장된 전략 ({})이 달라 상태를 복원하지 않습니다.", trader.code, market_state.strategy_name);
+        return;
+    }
+    broker.deposit(&split_market(&trader.code).1, market_state.position.size());
+    trader.restore(broker, market_state);
+    println!("{} - 저장된 포지션 복원 (수량: {:.8})", trader.code, trader.position.size());
+}
+
+/// 종목 하나의 모의 트레이더 실시간 콜백 생성. broker는 모든 종목이 공유함
+fn create_paper_trading_callback(trader: PaperTrader, broker: Rc<RefCell<PaperBroker>>, persistence: Option<Rc<PaperPersistence>>) -> RealtimeCallback {
+    let code = trader.code.clone();
+    let trader = Rc::new(RefCell::new(trader));
+    // 체결, 스탑/익절 변경 등으로 포지션이 바뀌면 저장
+    let save = move |trader: &mut PaperTrader| {
+        if let Some(persistence) = &persistence {
+            persistence.save_if_changed(trader);
+        }
+    };
+    let save = Rc::new(save);
+
+    let trade_fn = {
+        let trader = trader.clone();
+        let broker = broker.clone();
+        let save = save.clone();
+        move |trade: &Trade| {
+            let mut trader = trader.borrow_mut();
+            trader.on_trade(&mut broker.borrow_mut(), trade);
+            save(&mut trader);
+        }
     };
 
-    // 푸터프린트 출력
-    let log_footprint_fn = |footprint: &BTreeMap<String, FootprintValue>| {
-        let mut prices = footprint.keys().collect::<Vec<&String>>();
-        prices.sort_by(|a, b| b.partial_cmp(a).unwrap());
+    let orderbook_fn = {
+        let trader = trader.clone();
+        let broker = broker.clone();
+        let save = save.clone();
+        move |orderbook: &Orderbook| {
+            let mut trader = trader.borrow_mut();
+            trader.on_orderbook(&mut broker.borrow_mut(), orderbook);
+            save(&mut trader);
+        }
+    };
 
-        let mut total_ask_vol = 0.0;
-        let mut total_bid_vol = 0.0;
+    let candle_fn = {
+        let trader = trader.clone();
+        let broker = broker.clone();
+        let save = save.clone();
+        move |candle: &Candle| {
+            let mut trader = trader.borrow_mut();
+            trader.on_candle(&mut broker.borrow_mut(), candle);
+            save(&mut trader);
+        }
+    };
 
-        for price in prices.iter() {
-            let ask_vol = footprint[*price].ask_volume;
-            let bid_vol = footprint[*price].bid_volume;
+    let ticker_fn = {
+        let trader = trader.clone();
+        let broker = broker.clone();
+        move |ticker: &Ticker| {
+            let mut trader = trader.borrow_mut();
+            trader.on_ticker(&mut broker.borrow_mut(), ticker);
+            save(&mut trader);
+        }
+    };
 
-            total_ask_vol += ask_vol;
-            total_bid_vol += bid_vol;
+    let gap_fn = {
+        let code = code.clone();
+        move |gap: &RealtimeGap| {
+            println!("{} - {}ms 동안 실시간 데이터 누락 (재연결 시도 {}회)", code, gap.duration_ms(), gap.reconnect_attempts);
         }
+    };
+
+    let exit_fn = move || {
+        let broker_ref = broker.borrow();
+        let (_, base) = split_market(&code);
+        let open_orders = broker_ref.open_orders(Some(&code)).len();
+        println!("paper trading result {} - [{} 보유: {:.8} | 대기 주문: {}]", code, base, broker_ref.balance(&base), open_orders);
+    };
 
-        let volume_sum = total_ask_vol + total_bid_vol;
-        
-        println!();
-        println!("footprint 총 거래량: {},  매수 비율: {} ", volume_sum, total_bid_vol / volume_sum);
+    RealtimeCallback {
+        orderbook_fn: Box::new(orderbook_fn),
+        trade_fn: Box::new(trade_fn),
+        ticker_fn: Box::new(ticker_fn),
+        candle_fn: Box::new(candle_fn),
+        gap_fn: Box::new(gap_fn),
+        exit_fn: Box::new(exit_fn),
+    }
+}
 
-        let footprint_vec = prices.iter().map(|p| (p.to_string(), footprint[*p].clone())).collect::<Vec<(String, FootprintValue)>>();
-        log_footprint(footprint_vec);
+/// 기록된 실시간 메시지로 백테스트
+///
+/// 같은 기록 파일로 실행하면 항상 같은 결과가 나오므로 전략 파라미터 비교에 사용.
+/// 전략은 기록 시점의 prefetch 캔들로 warm up 하며, 재생 중에는 `enable_webhook_log`와 관계없이 webhook을 보내지 않음
+pub async fn simulate_with_recorded_data(path: &str, codes: &[&str], speed: ReplaySpeed, config: &SimulationConfig)
+-> Result<Vec<BacktesterState>, Box<dyn std::error::Error>> {
+    println!("replay backtest start - file: {}, codes: {:?}, strategy: {}", path, codes, config.strategy_name);
+
+    let config = SimulationConfig { enable_webhook_log: false, ..config.clone() };
+    let warm_up_candles = read_warm_up_candles(path)?;
+    let mut backtesters = Vec::new();
+    let mut callback_maps = HashMap::new();
+    for &code in codes {
+        let mut strategy = create_strategy(&config.strategy_name, config.enable_log)
+            .ok_or_else(|| StrategyParamError::UnknownStrategy(config.strategy_name.clone()))?;
+        if let Some(candles) = warm_up_candles.get(code) {
+            strategy.warm_up(candles);
+        }
+        let (backtester, callback) = create_realtime_backtest(code, Rc::new(RefCell::new(strategy)), &config);
+        callback_maps.insert(code, callback);
+        backtesters.push(backtester);
+    }
+
+    let count = replay_realtime_data(path, speed, &mut callback_maps).await?;
+    println!("{}개 메시지 재생 완료", count);
+
+    Ok(backtesters.into_iter().map(|backtester| backtester.borrow().clone()).collect())
+}
+
+/// 종목 하나의 백테스터와 실시간 콜백 생성
+fn create_realtime_backtest(code: &str, strategy: Rc<RefCell<Box<dyn Strategy>>>, config: &SimulationConfig)
+-> (Rc<RefCell<BacktesterState>>, RealtimeCallback) {
+    let mut backtest_params = BacktestParams::default(code, &config.strategy_name);
+    backtest_params.enable_webhook_log = config.enable_webhook_log;
+    backtest_params.slippage = config.slippage.clone();
+    backtest_params.sizing = config.sizing.clone();
+    let mut backtester = BacktesterState::new(backtest_params);
+    backtester.risk = config.risk.clone();
+    let backtester = Rc::new(RefCell::new(backtester));
+    // orderbook 이벤트로 발생한 신호를 처리할 때 사용할 마지막 체결가
+    let last_price = Rc::new(Cell::new(0.0));
+
+    // 클로저 정의
+    let trade_fn = {
+        let strategy = strategy.clone();
+        let backtester = backtester.clone();
+        let last_price = last_price.clone();
+        move |trade: &Trade| {
+            last_price.set(trade.trade_price);
+            let mut backtester_ref = backtester.borrow_mut();
+            let signal = strategy.borrow_mut().on_trade(trade, backtester_ref.get_position());
+            backtester_ref.handle_signal(&signal, trade.trade_price, &trade.trade_timestamp.to_string());
+        }
+    };
+
+    let orderbook_fn = {
+        let strategy = strategy.clone();
+        let backtester = backtester.clone();
+        let last_price = last_price.clone();
+        move |orderbook: &Orderbook| {
+            let mut backtester_ref = backtester.borrow_mut();
+            backtester_ref.market.set_orderbook(orderbook);
+            let signal = strategy.borrow_mut().on_orderbook(orderbook, backtester_ref.get_position());
+            if last_price.get() > 0.0 {
+                backtester_ref.handle_signal(&signal, last_price.get(), &orderbook.timestamp.to_string());
+            }
+        }
+    };
+    
+    let candle_fn = {
+        let strategy = strategy.clone();
+        let backtester = backtester.clone();
+        move |candle: &Candle| {
+            let mut backtester_ref = backtester.borrow_mut();
+            backtester_ref.market.push_candle(candle);
+            let signal = strategy.borrow_mut().on_candle(candle, backtester_ref.get_position());
+            backtester_ref.handle_signal(&signal, candle.get_trade_price(), candle.get_candle_date_time_utc());
+        }
+    };
+
+    let ticker_fn = {
+        let strategy = strategy.clone();
+        let backtester = backtester.clone();
+        let last_price = last_price.clone();
+        move |ticker: &Ticker| {
+            let current_price = ticker.trade_price;
+            let current_timestamp = ticker.trade_timestamp.to_string();
+            last_price.set(current_price);
+            let mut backtester_ref = backtester.borrow_mut();
+
+            backtester_ref.check_and_close_position(current_price, &current_timestamp);
+            let signal = strategy.borrow_mut().on_ticker(ticker, backtester_ref.get_position());
+            backtester_ref.handle_signal(&signal, current_price, &current_timestamp); // 포지션 관리
+        }
+    };
+
+    let gap_fn = {
+        let code = code.to_string();
+        move |gap: &RealtimeGap| {
+            println!("{} - {}ms 동안 실시간 데이터 누락 (재연결 시도 {}회)", code, gap.duration_ms(), gap.reconnect_attempts);
+        }
+    };
+
+    let exit_fn = {
+        let backtester = backtester.clone();
+        let code = code.to_string();
+        move || {
+            let backtester_ref = backtester.borrow();
+            let win_count = backtester_ref.win_count;
+            let loss_count = backtester_ref.loss_count;
+            let win_rate = win_count as f64 / (win_count + loss_count) as f64;
+            let total_pnl_pct = backtester_ref.total_pnl_pct;
+
+            println!("backtest result {} - [win: {} | loss: {} | win_rate: {:.2}% | total_pnl_pct: {:.2}%]", code, win_count, loss_count, win_rate * 100.0, total_pnl_pct * 100.0);
+        }
     };
 
-    let log_indicator_fn = |indicator: &Indicator| {
-        print!("\r최근 10개 캔들 거래량 평균: {} | 이전 저가: {} | 이전 고가: {} | 이전 시가: {} | 이전 종가: {} | 현재가: {} | 현재 캔들 거래량: {}"
-        , indicator.top_n_trade_volume_avg
-        , indicator.previous_candle.get_low_price()
-        , indicator.previous_candle.get_high_price()
-        , indicator.previous_candle.get_opening_price()
-        , indicator.previous_candle.get_trade_price()
-        , indicator.current_price
-        , indicator.current_candle_volume);
-        std::io::stdout().flush().unwrap();
+    let callback = RealtimeCallback {
+        orderbook_fn: Box::new(orderbook_fn),
+        trade_fn: Box::new(trade_fn),
+        ticker_fn: Box::new(ticker_fn),
+        candle_fn: Box::new(candle_fn),
+        gap_fn: Box::new(gap_fn),
+        exit_fn: Box::new(exit_fn),
     };
 
-    (top_n_trade_volume_avg_fn, log_footprint_fn, log_indicator_fn)
+    (backtester, callback)
 }
\ No newline at end of file
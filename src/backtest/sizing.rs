@@ -0,0 +1,101 @@
+use std::collections::VecDeque;
+
+use crate::{backtest::slippage::latest_atr, core::{candle::Candle, position::PositionState, signal::Signal}};
+
+/// 진입 신호의 크기를 정하는 방식
+///
+/// 계산한 진입 금액은 현금 대비 비율로 바꿔 신호의 asset_pct를 대신함 (0 ~ 1)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum PositionSizing {
+    /// 신호의 asset_pct를 그대로 사용
+    #[default]
+    SignalPct,
+    /// 고정 금액 (호가 통화)
+    FixedAmount { amount: f64 },
+    /// 평가 자산의 고정 비율
+    FixedFraction { pct: f64 },
+    /// 스탑에 걸렸을 때 손실이 평가 자산의 risk_pct가 되는 금액
+    FixedRisk { risk_pct: f64 },
+    /// 최근 캔들 ATR만큼 움직였을 때 손익이 평가 자산의 target_pct가 되는 금액. 캔들이 부족하면 신호의 asset_pct
+    VolatilityTarget { target_pct: f64, period: usize },
+    /// 거래 기록으로 계산한 켈리 비율에 fraction을 곱한 비율 (최대 max_pct). 거래가 min_trades보다 적으면 신호의 asset_pct
+    ///
+    /// 켈리 비율 = 승률 - (1 - 승률) / (평균 이익률 / 평균 손실률)
+    Kelly { fraction: f64, min_trades: usize, max_pct: f64 },
+}
+
+/// 진입 크기 계산에 사용하는 계좌와 시장 상태
+pub struct SizingContext<'a> {
+    pub equity: f64, // 평가 자산
+    pub cash: f64, // 진입에 쓸 수 있는 현금
+    pub price: f64,
+    pub candles: &'a VecDeque<Candle>, // 오래된 순서
+    pub returns: &'a [f64], // 청산된 거래의 손익률 (오래된 순서)
+}
+
+impl PositionSizing {
+    /// 현금 대비 진입 비율
+    ///
+    /// signal_pct: 신호의 asset_pct, stop_price: 진입 신호의 스탑 (추가 진입이면 현재 트레일링 스탑)
+    pub fn asset_pct(&self, signal_pct: f64, stop_price: f64, context: &SizingContext) -> f64 {
+        let amount = match self {
+            PositionSizing::SignalPct => return signal_pct,
+            PositionSizing::FixedAmount { amount } => *amount,
+            PositionSizing::FixedFraction { pct } => context.equity * pct,
+            PositionSizing::FixedRisk { risk_pct } => {
+                let stop_distance = (context.price - stop_price) / context.price;
+                if stop_distance > 0.0 { context.equity * risk_pct / stop_distance } else { 0.0 }
+            }
+            PositionSizing::VolatilityTarget { target_pct, period } => {
+                match latest_atr(context.candles, *period) {
+                    Some(atr) if atr > 0.0 => context.equity * target_pct * context.price / atr,
+                    _ => return signal_pct,
+                }
+            }
+            PositionSizing::Kelly { fraction, min_trades, max_pct } => {
+                if context.returns.len() < *min_trades {
+                    return signal_pct;
+                }
+                context.equity * (kelly_fraction(context.returns) * fraction).min(*max_pct)
+            }
+        };
+        if context.cash > 0.0 && context.price > 0.0 { (amount / context.cash).clamp(0.0, 1.0) } else { 0.0 }
+    }
+
+    /// 진입 신호 (`Buy`, `AddToPosition`)의 asset_pct를 바꾼 신호. 다른 신호는 그대로 반환
+    pub fn apply(&self, signal: &Signal, position: &PositionState, context: &SizingContext) -> Signal {
+        match (signal, position) {
+            (Signal::Buy { reason, initial_trailing_stop, take_profit, asset_pct }, PositionState::None) => Signal::Buy {
+                reason: reason.clone(),
+                initial_trailing_stop: *initial_trailing_stop,
+                take_profit: *take_profit,
+                asset_pct: self.asset_pct(*asset_pct, *initial_trailing_stop, context),
+            },
+            (Signal::AddToPosition { reason, asset_pct }, PositionState::InPosition { trailing_stop_price, .. }) => Signal::AddToPosition {
+                reason: reason.clone(),
+                asset_pct: self.asset_pct(*asset_pct, *trailing_stop_price, context),
+            },
+            (signal, _) => signal.clone(),
+        }
+    }
+}
+
+/// 손익률 목록으로 계산한 켈리 비율 (0 이상)
+///
+/// 손실 거래가 없으면 승률을, 이익 거래가 없으면 0을 반환
+pub fn kelly_fraction(returns: &[f64]) -> f64 {
+    if returns.is_empty() {
+        return 0.0;
+    }
+    let (wins, losses): (Vec<f64>, Vec<f64>) = returns.iter().partition(|r| **r > 0.0);
+    if wins.is_empty() {
+        return 0.0;
+    }
+    let win_rate = wins.len() as f64 / returns.len() as f64;
+    let avg_win = wins.iter().sum::<f64>() / wins.len() as f64;
+    let avg_loss = losses.iter().map(|r| r.abs()).sum::<f64>() / losses.len().max(1) as f64;
+    if avg_loss <= 0.0 {
+        return win_rate;
+    }
+    (win_rate - (1.0 - win_rate) / (avg_win / avg_loss)).max(0.0)
+}
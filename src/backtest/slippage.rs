@@ -0,0 +1,125 @@
+use std::collections::VecDeque;
+
+use crate::{core::{candle::{Candle, CandleTrait}, orderbook::Orderbook}, helper::atr::{calculate_atr, AtrCandle}, upbit_api::order::OrderSide};
+
+// 슬리피지 계산을 위해 보관하는 최근 캔들 수
+const MAX_CONTEXT_CANDLES: usize = 200;
+
+/// 체결가에 적용할 슬리피지 모델
+///
+/// 모든 모델은 주문 방향에 불리하게 적용됨 (매수는 비싸게, 매도는 싸게)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum SlippageModel {
+    /// 신호 가격에 그대로 체결
+    #[default]
+    None,
+    /// 고정 bps (1bp = 0.01%)
+    FixedBps(f64),
+    /// 최근 캔들 ATR의 배수만큼 불리하게 체결
+    Atr { period: usize, multiplier: f64 },
+    /// 주문 금액이 직전 캔들 거래대금에서 차지하는 비율의 제곱근에 비례 (시장 충격 모델)
+    ///
+    /// 슬리피지율 = impact * sqrt(주문 금액 / 캔들 거래대금)
+    VolumeParticipation { impact: f64 },
+    /// 마지막 호가를 따라 주문 수량만큼 체결한 평균가. 호가 잔량이 부족하면 남은 수량은 마지막 호가에 체결
+    OrderbookWalk,
+}
+
+/// 슬리피지 계산에 사용하는 시장 상태
+#[derive(Debug, Clone, Default)]
+pub struct MarketContext {
+    pub candles: VecDeque<Candle>, // 오래된 순서 (체결할 캔들은 포함하지 않음)
+    pub orderbook: Option<Orderbook>,
+}
+
+impl MarketContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push_candle(&mut self, candle: &Candle) {
+        // 같은 시간의 캔들은 갱신 (실시간 캔들)
+        match self.candles.back_mut() {
+            Some(last) if last.get_candle_date_time_utc() == candle.get_candle_date_time_utc() => *last = candle.clone(),
+            _ => self.candles.push_back(candle.clone()),
+        }
+        if self.candles.len() > MAX_CONTEXT_CANDLES {
+            self.candles.pop_front();
+        }
+    }
+
+    pub fn set_orderbook(&mut self, orderbook: &Orderbook) {
+        self.orderbook = Some(orderbook.clone());
+    }
+}
+
+impl SlippageModel {
+    /// price에 quantity만큼 주문했을 때의 평균 체결가
+    ///
+    /// 모델에 필요한 데이터 (캔들, 호가)가 부족하면 price를 그대로 반환.
+    /// 캔들 모델은 market에 들어 있는 이미 마감된 캔들만 사용함
+    pub fn fill_price(&self, side: OrderSide, price: f64, quantity: f64, market: &MarketContext) -> f64 {
+        let slippage = match self {
+            SlippageModel::None => 0.0,
+            SlippageModel::FixedBps(bps) => price * bps / 10000.0,
+            SlippageModel::Atr { period, multiplier } => latest_atr(&market.candles, *period).unwrap_or(0.0) * multiplier,
+            SlippageModel::VolumeParticipation { impact } => {
+                match market.candles.back().map(|candle| candle.get_candle_acc_trade_price()) {
+                    Some(traded_value) if traded_value > 0.0 => price * impact * (price * quantity / traded_value).sqrt(),
+                    _ => 0.0,
+                }
+            }
+            SlippageModel::OrderbookWalk => {
+                return market.orderbook.as_ref()
+                    .and_then(|orderbook| walk_orderbook(orderbook, side, quantity))
+                    .unwrap_or(price);
+            }
+        };
+
+        match side {
+            OrderSide::Bid => price + slippage,
+            OrderSide::Ask => (price - slippage).max(0.0),
+        }
+    }
+}
+
+pub(crate) fn latest_atr<'a>(candles: impl IntoIterator<Item = &'a Candle>, period: usize) -> Option<f64> {
+    if period == 0 {
+        return None;
+    }
+    let atr_candles = candles.into_iter().map(|candle| AtrCandle {
+        high: candle.get_high_price(),
+        low: candle.get_low_price(),
+        close: candle.get_trade_price(),
+    }).collect::<Vec<AtrCandle>>();
+    calculate_atr(&atr_candles, period).last().copied()
+}
+
+/// 호가를 따라 quantity만큼 체결한 평균가. 호가가 비어 있으면 None
+///
+/// 매수는 매도 호가를, 매도는 매수 호가를 가까운 순서로 소진함
+pub fn walk_orderbook(orderbook: &Orderbook, side: OrderSide, quantity: f64) -> Option<f64> {
+    let levels = orderbook.orderbook_units.iter().map(|unit| match side {
+        OrderSide::Bid => (unit.ask_price, unit.ask_size),
+        OrderSide::Ask => (unit.bid_price, unit.bid_size),
+    }).filter(|(price, _)| *price > 0.0).collect::<Vec<(f64, f64)>>();
+
+    let (last_price, _) = *levels.last()?;
+    if quantity <= 0.0 {
+        return Some(levels[0].0);
+    }
+
+    let mut remaining = quantity;
+    let mut cost = 0.0;
+    for (price, size) in levels {
+        let filled = remaining.min(size);
+        cost += filled * price;
+        remaining -= filled;
+        if remaining <= 0.0 {
+            break;
+        }
+    }
+    cost += remaining.max(0.0) * last_price;
+
+    Some(cost / quantity)
+}
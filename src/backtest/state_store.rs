@@ -0,0 +1,85 @@
+use std::{collections::BTreeMap, fs, io, path::{Path, PathBuf}};
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::position::PositionState;
+
+/// 종목 하나의 실거래 상태
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MarketState {
+    pub market: String,
+    pub strategy_name: String,
+    pub position: PositionState, // lot, 평균 진입가, 익절가, 트레일링 스탑
+    pub open_orders: Vec<String>, // 이 종목에서 낸 미체결 주문 uuid
+    #[serde(default)]
+    pub strategy_state: Option<serde_json::Value>, // `Strategy::snapshot`
+}
+
+impl MarketState {
+    pub fn new(market: &str, strategy_name: &str) -> Self {
+        Self {
+            market: market.to_string(),
+            strategy_name: strategy_name.to_string(),
+            position: PositionState::None,
+            open_orders: Vec::new(),
+            strategy_state: None,
+        }
+    }
+}
+
+/// 재시작 후 복원할 전체 실거래 상태 (마켓 코드별)
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct TradingState {
+    pub markets: BTreeMap<String, MarketState>,
+}
+
+impl TradingState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 마켓의 상태. 없으면 빈 상태를 추가
+    pub fn market_mut(&mut self, market: &str, strategy_name: &str) -> &mut MarketState {
+        self.markets.entry(market.to_string()).or_insert_with(|| MarketState::new(market, strategy_name))
+    }
+
+    /// 저장된 모든 미체결 주문 uuid
+    pub fn open_orders(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.markets.values().flat_map(|state| state.open_orders.iter().map(|uuid| (state.market.as_str(), uuid.as_str())))
+    }
+}
+
+/// 실거래 상태를 JSON 파일 하나에 저장
+///
+/// 상태가 바뀔 때마다 `save`로 파일 전체를 다시 쓰며, 임시 파일에 쓴 뒤 교체해서 중간에 종료되어도 이전 상태가 남음
+pub struct StateStore {
+    pub path: PathBuf,
+}
+
+impl StateStore {
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self { path: path.as_ref().to_path_buf() }
+    }
+
+    /// 저장된 상태 파일이 있는지 여부
+    pub fn exists(&self) -> bool {
+        self.path.exists()
+    }
+
+    /// 저장된 상태. 파일이 없으면 빈 상태
+    pub fn load(&self) -> io::Result<TradingState> {
+        if !self.path.exists() {
+            return Ok(TradingState::new());
+        }
+        Ok(serde_json::from_str(&fs::read_to_string(&self.path)?)?)
+    }
+
+    pub fn save(&self, state: &TradingState) -> io::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let temp_path = self.path.with_extension("tmp");
+        fs::write(&temp_path, serde_json::to_string_pretty(state)?)?;
+        fs::rename(temp_path, &self.path)
+    }
+}
@@ -0,0 +1,179 @@
+use std::ops::Range;
+
+use crate::{backtest::{lib::INITIAL_ASSET, ledger::{EquityPoint, TradeRecord}, optimize::{optimize, run_with_params, OptimizeConfig, ParamSet, ParamSpace},
+report::BacktestReport}, core::candle::{Candle, CandleTrait}, strategy::registry::StrategyParamError};
+use rand::{rngs::StdRng, SeedableRng};
+
+/// in-sample 구간에서 파라미터 조합을 고르는 방식
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Search {
+    Grid,
+    Random { count: usize, seed: u64 },
+}
+
+/// 워크 포워드 분석 설정 (구간 길이는 캔들 수)
+#[derive(Debug, Clone)]
+pub struct WalkForwardConfig {
+    pub in_sample_candles: usize,
+    pub out_of_sample_candles: usize,
+    /// true면 in-sample 시작을 처음 캔들로 고정 (구간이 점점 길어짐), false면 같은 길이로 이동
+    pub anchored: bool,
+    /// out-of-sample 실행 전 전략 상태를 채울 직전 캔들 수
+    pub warm_up_candles: usize,
+    pub search: Search,
+}
+
+impl WalkForwardConfig {
+    pub fn new(in_sample_candles: usize, out_of_sample_candles: usize) -> Self {
+        Self {
+            in_sample_candles,
+            out_of_sample_candles,
+            anchored: false,
+            warm_up_candles: 300,
+            search: Search::Grid,
+        }
+    }
+
+    /// 캔들 수 len을 나눈 (in-sample, out-of-sample) 인덱스 구간. out-of-sample 구간은 서로 겹치지 않고 이어짐
+    ///
+    /// 마지막 out-of-sample 구간은 남은 캔들이 부족하면 짧아질 수 있음
+    pub fn windows(&self, len: usize) -> Vec<(Range<usize>, Range<usize>)> {
+        let mut windows = Vec::new();
+        if self.in_sample_candles == 0 || self.out_of_sample_candles == 0 {
+            return windows;
+        }
+        let mut start = 0;
+        while start + self.in_sample_candles < len {
+            let split = start + self.in_sample_candles;
+            let end = (split + self.out_of_sample_candles).min(len);
+            let in_sample_start = if self.anchored { 0 } else { start };
+            windows.push((in_sample_start..split, split..end));
+            start += self.out_of_sample_candles;
+        }
+        windows
+    }
+}
+
+/// 워크 포워드 구간 하나의 결과
+#[derive(Debug, Clone)]
+pub struct WalkForwardWindow {
+    pub in_sample: Range<usize>,
+    pub out_of_sample: Range<usize>,
+    /// in-sample 구간에서 목표 점수가 가장 높았던 파라미터
+    pub params: ParamSet,
+    pub in_sample_report: BacktestReport,
+    pub out_of_sample_report: BacktestReport,
+    /// 캔들당 수익률 기준 out-of-sample / in-sample. in-sample 수익이 0 이하이면 None
+    pub efficiency: Option<f64>,
+}
+
+/// 워크 포워드 분석 결과
+#[derive(Debug, Clone)]
+pub struct WalkForwardReport {
+    pub windows: Vec<WalkForwardWindow>,
+    /// out-of-sample 구간을 이어 붙인 자산 곡선 (각 구간은 직전 구간의 최종 자산에서 시작)
+    pub equity_curve: Vec<EquityPoint>,
+    /// out-of-sample 거래 기록 (금액은 이어 붙인 자산 기준으로 조정)
+    pub trades: Vec<TradeRecord>,
+    /// 이어 붙인 out-of-sample 결과의 성과 지표
+    pub report: BacktestReport,
+    /// 워크 포워드 효율: 전체 out-of-sample 캔들당 수익률 / 평균 in-sample 캔들당 수익률
+    pub efficiency: Option<f64>,
+}
+
+impl WalkForwardReport {
+    pub fn print(&self) {
+        let format_pct = |value: Option<f64>| value.map(|value| format!("{:.2}%", value * 100.0)).unwrap_or("-".to_string());
+        println!("--------------------------------------------------");
+        println!(" [워크 포워드 분석 결과] - 구간: {} 개", self.windows.len());
+        for (index, window) in self.windows.iter().enumerate() {
+            let params = window.params.iter().map(|(name, value)| format!("{}={}", name, value)).collect::<Vec<String>>().join(", ");
+            println!(" > #{} IS {:?} {:.2}% / OOS {:?} {:.2}% (거래 {} 회), 효율: {} [{}]",
+                index + 1, window.in_sample, window.in_sample_report.total_return_pct * 100.0,
+                window.out_of_sample, window.out_of_sample_report.total_return_pct * 100.0, window.out_of_sample_report.total_trades,
+                format_pct(window.efficiency), params);
+        }
+        println!(" > 워크 포워드 효율: {}", format_pct(self.efficiency));
+        self.report.print();
+        println!("--------------------------------------------------");
+    }
+}
+
+// 총 수익률을 캔들당 복리 수익률로 환산
+fn per_candle_return(total_return_pct: f64, candles: usize) -> f64 {
+    if candles == 0 || total_return_pct <= -1.0 {
+        return total_return_pct;
+    }
+    (1.0 + total_return_pct).powf(1.0 / candles as f64) - 1.0
+}
+
+fn efficiency(out_of_sample_return: f64, in_sample_return: f64) -> Option<f64> {
+    (in_sample_return > 0.0).then(|| out_of_sample_return / in_sample_return)
+}
+
+/// 캔들을 구간으로 나눠 in-sample에서 파라미터를 최적화하고 바로 다음 out-of-sample 구간에서 평가
+///
+/// candles는 오래된 순서로 정렬되어 있어야 함
+pub fn walk_forward(candles: &[Candle], space: &ParamSpace, config: &WalkForwardConfig, optimize_config: &OptimizeConfig)
+    -> Result<WalkForwardReport, StrategyParamError> {
+    let mut windows = Vec::new();
+    let mut equity_curve = Vec::new();
+    let mut trades = Vec::new();
+    let mut asset = INITIAL_ASSET;
+
+    for (in_sample, out_of_sample) in config.windows(candles.len()) {
+        let param_sets = match config.search {
+            Search::Grid => space.grid(),
+            Search::Random { count, seed } => space.sample(count, &mut StdRng::seed_from_u64(seed.wrapping_add(windows.len() as u64))),
+        };
+        let results = optimize(&candles[in_sample.clone()], &param_sets, optimize_config)?;
+        let Some(best) = results.best() else { continue };
+
+        let warm_up_start = out_of_sample.start.saturating_sub(config.warm_up_candles);
+        let backtester = run_with_params(&candles[warm_up_start..out_of_sample.start], &candles[out_of_sample.clone()], &best.params, optimize_config)?;
+        let out_of_sample_report = backtester.report();
+
+        // 직전 구간의 최종 자산에 맞춰 이어 붙임
+        let scale = asset / out_of_sample_report.initial_asset;
+        let start_date = candles[out_of_sample.start].get_candle_date_time_utc();
+        if equity_curve.last().is_none_or(|point: &EquityPoint| point.date != start_date) {
+            equity_curve.push(EquityPoint { date: start_date.to_string(), equity: asset });
+        }
+        equity_curve.extend(backtester.equity_curve.iter().map(|point| EquityPoint { date: point.date.clone(), equity: point.equity * scale }));
+        trades.extend(backtester.trades.iter().map(|trade| TradeRecord {
+            entry_asset: trade.entry_asset * scale,
+            fees: trade.fees * scale,
+            pnl: trade.pnl * scale,
+            ..trade.clone()
+        }));
+        asset = out_of_sample_report.final_asset * scale;
+
+        windows.push(WalkForwardWindow {
+            efficiency: efficiency(
+                per_candle_return(out_of_sample_report.total_return_pct, out_of_sample.len()),
+                per_candle_return(best.report.total_return_pct, in_sample.len()),
+            ),
+            in_sample,
+            out_of_sample,
+            params: best.params.clone(),
+            in_sample_report: best.report.clone(),
+            out_of_sample_report,
+        });
+    }
+
+    let report = BacktestReport::new(INITIAL_ASSET, &trades, &equity_curve);
+    let out_of_sample_candles = windows.iter().map(|window| window.out_of_sample.len()).sum::<usize>();
+    let in_sample_returns = windows.iter()
+        .map(|window| per_candle_return(window.in_sample_report.total_return_pct, window.in_sample.len()))
+        .collect::<Vec<f64>>();
+    let efficiency = if in_sample_returns.is_empty() {
+        None
+    } else {
+        efficiency(
+            per_candle_return(report.total_return_pct, out_of_sample_candles),
+            in_sample_returns.iter().sum::<f64>() / in_sample_returns.len() as f64,
+        )
+    };
+
+    Ok(WalkForwardReport { windows, equity_curve, trades, report, efficiency })
+}
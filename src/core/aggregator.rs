@@ -0,0 +1,216 @@
+use std::fmt;
+
+use chrono::{DateTime, FixedOffset};
+
+use crate::core::{candle::{Candle, CandleBase}, trade::{AskBid, Trade}};
+
+// KST = UTC+9 (서머타임 없음)
+const KST_OFFSET_SECS: i32 = 9 * 3600;
+
+/// 봉을 마감하는 기준
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BarInterval {
+    /// N초 봉
+    Seconds(u32),
+    /// N분 봉
+    Minutes(u32),
+    /// 체결 N건마다
+    Ticks(u32),
+    /// 누적 거래량이 N 이상이 되면
+    Volume(f64),
+    /// 누적 거래 대금이 N 이상이 되면
+    Value(f64),
+}
+
+impl BarInterval {
+    /// 봉을 마감할 수 있는 기준인지 (길이, 건수, 거래량, 거래 대금이 0보다 큼)
+    pub fn is_valid(&self) -> bool {
+        match self {
+            BarInterval::Seconds(n) | BarInterval::Minutes(n) | BarInterval::Ticks(n) => *n > 0,
+            BarInterval::Volume(n) | BarInterval::Value(n) => n.is_finite() && *n > 0.0,
+        }
+    }
+
+    /// 시간 기준 봉이면 봉 길이 (ms)
+    pub fn duration_ms(&self) -> Option<i64> {
+        match self {
+            BarInterval::Seconds(n) => Some(*n as i64 * 1000),
+            BarInterval::Minutes(n) => Some(*n as i64 * 60_000),
+            _ => None,
+        }
+    }
+}
+
+/// 봉을 만들 수 없는 기준
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InvalidBarInterval(pub BarInterval);
+
+impl fmt::Display for InvalidBarInterval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid bar interval: {:?}", self.0)
+    }
+}
+
+impl std::error::Error for InvalidBarInterval {}
+
+/// 체결 데이터로 만든 봉
+#[derive(Debug, Clone)]
+pub struct Bar {
+    pub market: String,
+    /// 봉 시작 시각 (ms). 시간 기준 봉이면 구간 시작, 그 외에는 첫 체결 시각
+    pub open_timestamp: i64,
+    /// 마지막 체결 시각 (ms)
+    pub close_timestamp: i64,
+    pub opening_price: f64,
+    pub high_price: f64,
+    pub low_price: f64,
+    pub trade_price: f64,
+    pub volume: f64,
+    /// 누적 거래 대금
+    pub value: f64,
+    /// 매수 체결량 (ab = BID)
+    pub buy_volume: f64,
+    /// 매도 체결량 (ab = ASK)
+    pub sell_volume: f64,
+    pub trade_count: u32,
+    /// 봉에 포함된 체결. `TradeAggregator::with_trades`로 생성한 경우에만 채워짐
+    pub trades: Vec<Trade>,
+}
+
+impl Bar {
+    fn new(trade: &Trade, open_timestamp: i64) -> Self {
+        Self {
+            market: trade.code.clone(),
+            open_timestamp,
+            close_timestamp: trade.trade_timestamp,
+            opening_price: trade.trade_price,
+            high_price: trade.trade_price,
+            low_price: trade.trade_price,
+            trade_price: trade.trade_price,
+            volume: 0.0,
+            value: 0.0,
+            buy_volume: 0.0,
+            sell_volume: 0.0,
+            trade_count: 0,
+            trades: Vec::new(),
+        }
+    }
+
+    fn add(&mut self, trade: &Trade, keep_trade: bool) {
+        self.high_price = self.high_price.max(trade.trade_price);
+        self.low_price = self.low_price.min(trade.trade_price);
+        self.trade_price = trade.trade_price;
+        self.close_timestamp = self.close_timestamp.max(trade.trade_timestamp);
+        self.volume += trade.trade_volume;
+        self.value += trade.trade_price * trade.trade_volume;
+        match trade.ask_bid {
+            AskBid::Bid => self.buy_volume += trade.trade_volume,
+            AskBid::Ask => self.sell_volume += trade.trade_volume,
+            AskBid::Unknown => {}
+        }
+        self.trade_count += 1;
+        if keep_trade {
+            self.trades.push(trade.clone());
+        }
+    }
+
+    /// 매수 체결량 - 매도 체결량
+    pub fn delta(&self) -> f64 {
+        self.buy_volume - self.sell_volume
+    }
+
+    /// 봉 시작 시각을 캔들 시간으로 사용하는 `Candle`로 변환
+    pub fn to_candle(&self) -> Candle {
+        let utc = DateTime::from_timestamp_millis(self.open_timestamp).unwrap_or_default();
+        let kst = utc.with_timezone(&FixedOffset::east_opt(KST_OFFSET_SECS).unwrap());
+        Candle {
+            base: CandleBase {
+                market: self.market.clone(),
+                candle_date_time_utc: utc.format("%Y-%m-%dT%H:%M:%S").to_string(),
+                candle_date_time_kst: kst.format("%Y-%m-%dT%H:%M:%S").to_string(),
+                opening_price: self.opening_price,
+                high_price: self.high_price,
+                low_price: self.low_price,
+                trade_price: self.trade_price,
+                timestamp: self.close_timestamp as u64,
+                candle_acc_trade_price: self.value,
+                candle_acc_trade_volume: self.volume,
+            }
+        }
+    }
+}
+
+/// 체결 스트림을 `trade_timestamp` 기준으로 봉으로 묶음
+///
+/// 체결이 없는 구간의 빈 봉은 만들지 않으며, 거래량/거래 대금 봉은 체결을 나누지 않고 기준을 넘긴 체결까지 포함함.
+/// 진행 중인 봉보다 이전 구간의 체결이 늦게 도착하면 진행 중인 봉에 합침
+pub struct TradeAggregator {
+    pub interval: BarInterval,
+    keep_trades: bool,
+    current: Option<Bar>,
+}
+
+impl TradeAggregator {
+    /// 길이, 건수, 거래량, 거래 대금이 0 이하인 기준은 에러
+    pub fn new(interval: BarInterval) -> Result<Self, InvalidBarInterval> {
+        if !interval.is_valid() {
+            return Err(InvalidBarInterval(interval));
+        }
+        Ok(Self { interval, keep_trades: false, current: None })
+    }
+
+    /// 봉마다 포함된 체결을 보관 (풋프린트 계산용)
+    pub fn with_trades(mut self) -> Self {
+        self.keep_trades = true;
+        self
+    }
+
+    /// 진행 중인 봉
+    pub fn current(&self) -> Option<&Bar> {
+        self.current.as_ref()
+    }
+
+    /// 체결을 추가하고, 봉이 마감되면 마감된 봉을 반환
+    pub fn push(&mut self, trade: &Trade) -> Option<Bar> {
+        let mut closed = None;
+
+        if let Some(duration) = self.interval.duration_ms() {
+            let bucket = trade.trade_timestamp - trade.trade_timestamp.rem_euclid(duration);
+            if self.current.as_ref().is_some_and(|bar| bucket > bar.open_timestamp) {
+                closed = self.current.take();
+            }
+            let bar = self.current.get_or_insert_with(|| Bar::new(trade, bucket));
+            bar.add(trade, self.keep_trades);
+        } else {
+            let bar = self.current.get_or_insert_with(|| Bar::new(trade, trade.trade_timestamp));
+            bar.add(trade, self.keep_trades);
+            let is_full = match self.interval {
+                BarInterval::Ticks(n) => bar.trade_count >= n,
+                BarInterval::Volume(volume) => bar.volume >= volume,
+                BarInterval::Value(value) => bar.value >= value,
+                _ => false,
+            };
+            if is_full {
+                closed = self.current.take();
+            }
+        }
+
+        closed
+    }
+
+    /// 시간 기준 봉에서 now (ms)가 진행 중인 봉의 구간을 지났으면 마감하고 반환
+    ///
+    /// 다음 체결이 오기 전에 봉을 마감해야 할 때 사용
+    pub fn close_if_expired(&mut self, now: i64) -> Option<Bar> {
+        let duration = self.interval.duration_ms()?;
+        if self.current.as_ref().is_some_and(|bar| now >= bar.open_timestamp + duration) {
+            return self.current.take();
+        }
+        None
+    }
+
+    /// 진행 중인 봉을 강제로 마감
+    pub fn flush(&mut self) -> Option<Bar> {
+        self.current.take()
+    }
+}
@@ -59,6 +59,16 @@ pub struct DayCandle {
     pub change_rate: f64,   
 }
 
+/// 주봉/월봉/연봉 캔들
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct PeriodCandle {
+    #[serde(flatten)]
+    pub base: CandleBase,
+    /// 캔들 기간의 가장 첫 날 (`2024-01-01`)
+    #[serde(deserialize_with = "null_to_empty_string")]
+    pub first_day_of_period: String,
+}
+
 fn null_to_empty_string<'de, D>(deserializer: D) -> Result<String, D::Error>
 
 where
@@ -217,4 +227,37 @@ impl CandleTrait for DayCandle {
     fn get_candle_acc_trade_volume(&self) -> f64 {
         self.base.get_candle_acc_trade_volume()
     }
+}
+
+impl CandleTrait for PeriodCandle {
+    fn get_market(&self) -> &str {
+        self.base.get_market()
+    }
+    fn get_candle_date_time_utc(&self) -> &str {
+        self.base.get_candle_date_time_utc()
+    }
+    fn get_candle_date_time_kst(&self) -> &str {
+        self.base.get_candle_date_time_kst()
+    }
+    fn get_opening_price(&self) -> f64 {
+        self.base.get_opening_price()
+    }
+    fn get_high_price(&self) -> f64 {
+        self.base.get_high_price()
+    }
+    fn get_low_price(&self) -> f64 {
+        self.base.get_low_price()
+    }
+    fn get_trade_price(&self) -> f64 {
+        self.base.get_trade_price()
+    }
+    fn get_timestamp(&self) -> u64 {
+        self.base.get_timestamp()
+    }
+    fn get_candle_acc_trade_price(&self) -> f64 {
+        self.base.get_candle_acc_trade_price()
+    }
+    fn get_candle_acc_trade_volume(&self) -> f64 {
+        self.base.get_candle_acc_trade_volume()
+    }
 }
\ No newline at end of file
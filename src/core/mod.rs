@@ -2,4 +2,9 @@ pub mod candle;
 pub mod signal;
 pub mod ticker;
 pub mod trade;
-pub mod orderbook;
\ No newline at end of file
+pub mod orderbook;
+pub mod aggregator;
+pub mod my_order;
+pub mod my_asset;
+pub mod order_event;
+pub mod position;
\ No newline at end of file
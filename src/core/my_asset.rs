@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+
+use crate::core::trade::StreamType;
+
+/// 자산 하나의 잔고
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MyAssetBalance {
+    #[serde(rename = "cu")]
+    pub currency: String, // 화폐 코드
+    #[serde(rename = "b")]
+    pub balance: f64, // 주문 가능 수량
+    #[serde(rename = "l")]
+    pub locked: f64, // 주문 중 묶여 있는 수량
+}
+
+/// 내 자산 (private websocket `myAsset`)
+///
+/// 잔고가 바뀔 때마다 바뀐 자산만 수신됨
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MyAsset {
+    #[serde(rename = "ty")]
+    pub asset_type: String, // myAsset
+    #[serde(rename = "auid")]
+    pub asset_uuid: String, // 자산 고유 아이디
+    #[serde(rename = "ast")]
+    pub assets: Vec<MyAssetBalance>, // 바뀐 자산 목록
+    #[serde(rename = "asttms")]
+    pub asset_timestamp: i64, // 자산 타임스탬프 (ms)
+    #[serde(rename = "tms")]
+    pub timestamp: i64, // 타임스탬프 (ms)
+    #[serde(rename = "st")]
+    pub stream_type: StreamType, // 스트림 타입
+}
+
+impl MyAsset {
+    pub fn get(&self, currency: &str) -> Option<&MyAssetBalance> {
+        self.assets.iter().find(|asset| asset.currency == currency)
+    }
+}
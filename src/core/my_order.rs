@@ -0,0 +1,96 @@
+use serde::{Deserialize, Serialize};
+
+use crate::core::trade::{AskBid, StreamType};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum MyOrderType {
+    Limit, // 지정가
+    Price, // 시장가 매수
+    Market, // 시장가 매도
+    Best, // 최유리
+    #[serde(other)] Unknown,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum MyOrderState {
+    Wait, // 체결 대기
+    Watch, // 예약 주문 대기
+    Trade, // 체결 발생
+    Done, // 전체 체결 완료
+    Cancel, // 주문 취소
+    Prevented, // 자전거래 체결 방지로 취소
+    #[serde(other)] Unknown,
+}
+
+/// 내 주문 및 체결 (private websocket `myOrder`)
+///
+/// 주문 상태가 바뀌거나 체결될 때마다 수신됨. 체결로 수신된 경우 state는 `Trade`이고 trade_* 필드가 채워짐
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MyOrder {
+    #[serde(rename = "ty")]
+    pub message_type: String, // myOrder
+    #[serde(rename = "cd")]
+    pub code: String, // 마켓 코드
+    #[serde(rename = "uid")]
+    pub uuid: String, // 주문 고유 아이디
+    #[serde(rename = "ab")]
+    pub ask_bid: AskBid, // 매수/매도 구분
+    #[serde(rename = "ot")]
+    pub order_type: MyOrderType, // 주문 타입
+    #[serde(rename = "s")]
+    pub state: MyOrderState, // 주문 상태
+    #[serde(rename = "tid", default)]
+    pub trade_uuid: Option<String>, // 체결 고유 아이디
+    #[serde(rename = "p", default)]
+    pub price: Option<f64>, // 주문 가격 또는 체결 가격 (state가 trade일 때)
+    #[serde(rename = "ap", default)]
+    pub avg_price: Option<f64>, // 평균 체결 가격
+    #[serde(rename = "v", default)]
+    pub volume: Option<f64>, // 주문량 또는 체결량 (state가 trade일 때)
+    #[serde(rename = "rv", default)]
+    pub remaining_volume: Option<f64>, // 체결 후 남은 주문 양
+    #[serde(rename = "ev", default)]
+    pub executed_volume: f64, // 체결된 양
+    #[serde(rename = "tc", default)]
+    pub trades_count: u32, // 해당 주문의 체결 수
+    #[serde(rename = "rsf", default)]
+    pub reserved_fee: f64, // 수수료로 예약된 비용
+    #[serde(rename = "rmf", default)]
+    pub remaining_fee: f64, // 남은 수수료
+    #[serde(rename = "pf", default)]
+    pub paid_fee: f64, // 사용된 수수료
+    #[serde(rename = "l", default)]
+    pub locked: f64, // 거래에 사용 중인 비용
+    #[serde(rename = "ef", default)]
+    pub executed_funds: f64, // 체결된 금액
+    #[serde(rename = "tif", default)]
+    pub time_in_force: Option<String>, // IOC, FOK 설정 (ioc, fok)
+    #[serde(rename = "tf", default)]
+    pub trade_fee: Option<f64>, // 체결 시 발생한 수수료 (state가 trade가 아니면 None)
+    #[serde(rename = "im", default)]
+    pub is_maker: Option<bool>, // 메이커 체결 여부 (state가 trade가 아니면 None)
+    #[serde(rename = "id", default)]
+    pub identifier: Option<String>, // 클라이언트 지정 주문 식별자
+    #[serde(rename = "ttms", default)]
+    pub trade_timestamp: Option<i64>, // 체결 타임스탬프 (ms)
+    #[serde(rename = "otms")]
+    pub order_timestamp: i64, // 주문 타임스탬프 (ms)
+    #[serde(rename = "tms")]
+    pub timestamp: i64, // 타임스탬프 (ms)
+    #[serde(rename = "st")]
+    pub stream_type: StreamType, // 스트림 타입
+}
+
+impl MyOrder {
+    /// 체결 이벤트 여부
+    pub fn is_trade(&self) -> bool {
+        self.state == MyOrderState::Trade
+    }
+
+    /// 더 이상 체결되지 않는 주문 (전체 체결, 취소)
+    pub fn is_finished(&self) -> bool {
+        matches!(self.state, MyOrderState::Done | MyOrderState::Cancel | MyOrderState::Prevented)
+    }
+}
@@ -0,0 +1,25 @@
+use crate::upbit_api::order::{Order, OrderTrade};
+
+/// 주문이 호가를 제공했는지 (maker) 가져갔는지 (taker)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Liquidity {
+    Maker, // 지정가 주문이 호가에 올라가 있다가 체결
+    Taker, // 시장가 주문 또는 즉시 체결되는 지정가 주문
+}
+
+/// 모의 주문의 체결/취소 이벤트
+#[derive(Debug, Clone)]
+pub enum OrderEvent {
+    /// 주문 일부 또는 전체 체결. order는 체결을 반영한 뒤의 주문 상태
+    Fill { order: Order, trade: OrderTrade, liquidity: Liquidity },
+    /// 사용자 취소 또는 IOC/FOK 주문의 미체결 잔량 취소
+    Cancel(Order),
+}
+
+impl OrderEvent {
+    pub fn order(&self) -> &Order {
+        match self {
+            OrderEvent::Fill { order, .. } | OrderEvent::Cancel(order) => order,
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,87 @@
+use serde::{Deserialize, Serialize};
+
+use crate::core::signal::TakeProfitTarget;
+
+/// 보유 중인 진입 단위 (lot)의 진입 정보와 보유 중 가격 범위
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenTrade {
+    pub entry_date: String,
+    pub entry_reason: String,
+    pub highest_price: f64,
+    pub lowest_price: f64,
+}
+
+impl OpenTrade {
+    pub fn new(entry_date: &str, entry_reason: &str, entry_price: f64) -> Self {
+        Self {
+            entry_date: entry_date.to_string(),
+            entry_reason: entry_reason.to_string(),
+            highest_price: entry_price,
+            lowest_price: entry_price,
+        }
+    }
+
+    /// 보유 중 관찰한 가격 반영
+    pub fn update(&mut self, price: f64) {
+        self.highest_price = self.highest_price.max(price);
+        self.lowest_price = self.lowest_price.min(price);
+    }
+
+    /// 진입가 대비 보유 중 최대 역행폭 (MAE). 0 이하
+    pub fn mae_pct(&self, entry_price: f64) -> f64 {
+        if entry_price > 0.0 { (self.lowest_price / entry_price - 1.0).min(0.0) } else { 0.0 }
+    }
+
+    /// 진입가 대비 보유 중 최대 순행폭 (MFE). 0 이상
+    pub fn mfe_pct(&self, entry_price: f64) -> f64 {
+        if entry_price > 0.0 { (self.highest_price / entry_price - 1.0).max(0.0) } else { 0.0 }
+    }
+}
+
+/// 포지션을 구성하는 진입 단위
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Lot {
+    pub entry_price: f64,
+    pub entry_asset: f64, // 남은 진입 금액. 부분 청산하면 청산한 비율만큼 줄어듦
+    pub size: f64,        // 남은 수량
+    pub trade: OpenTrade, // 진입 시간, 이유와 보유 중 가격 범위
+}
+
+// 가상 포지션의 상태
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum PositionState {
+    None, // 포지션 없음
+    InPosition {
+        entry_price: f64, // lot들의 평균 진입가
+        entry_asset: f64, // lot들의 남은 진입 금액 합계
+        take_profit_price: f64, // 남은 수량을 전부 청산하는 익절가
+        trailing_stop_price: f64, // 트레일링 스탑 가격
+        lots: Vec<Lot>, // 진입 순서. 부분 청산은 오래된 lot부터 (FIFO)
+        take_profit_targets: Vec<TakeProfitTarget>, // 남은 분할 익절 목표 (가격 오름차순)
+    },
+}
+
+impl PositionState {
+    pub fn lots(&self) -> &[Lot] {
+        match self {
+            PositionState::InPosition { lots, .. } => lots,
+            PositionState::None => &[],
+        }
+    }
+
+    /// 남은 수량 합계
+    pub fn size(&self) -> f64 {
+        self.lots().iter().map(|lot| lot.size).sum()
+    }
+
+    // lot이 바뀐 뒤 평균 진입가와 진입 금액 합계를 다시 계산
+    pub(crate) fn recalculate(&mut self) {
+        if let PositionState::InPosition { entry_price, entry_asset, lots, .. } = self {
+            let size = lots.iter().map(|lot| lot.size).sum::<f64>();
+            *entry_asset = lots.iter().map(|lot| lot.entry_asset).sum();
+            if size > 0.0 {
+                *entry_price = *entry_asset / size;
+            }
+        }
+    }
+}
\ No newline at end of file
@@ -1,8 +1,17 @@
+use serde::{Deserialize, Serialize};
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct SignalReason {
     pub reason: String,
 }
 
+/// 분할 익절 목표
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TakeProfitTarget {
+    pub price: f64,
+    pub pct: f64, // 목표가에 닿았을 때 청산할 남은 포지션 비율 (0~1)
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Signal {
     Buy {
@@ -14,4 +23,16 @@ pub enum Signal {
     Sell(SignalReason),
     Hold,
     UpdateTrailingStop(f64),
-}
\ No newline at end of file
+    /// 보유 중인 포지션에 추가 진입 (asset_pct: 현재 현금 대비 비율)
+    AddToPosition {
+        reason: String,
+        asset_pct: f64,
+    },
+    /// 남은 포지션의 pct (0~1)만큼 시장가 청산
+    ReducePosition {
+        reason: String,
+        pct: f64,
+    },
+    /// 분할 익절 목표 설정 (기존 목표를 대체). 남은 수량은 take_profit에서 전부 청산됨
+    SetTakeProfitTargets(Vec<TakeProfitTarget>),
+}
@@ -1,13 +1,16 @@
 use ctb::{
     backtest::{
-        fetch::fetch_n_minute_candles, lib::{BacktestParams, BacktesterState}, simulate::{self, simulate_with_realtime_data, SimulationConfig}
-    }, core::candle::{Candle, CandleBase}, webhook::lib::send_webhook
+        candle_store::CandleStore, fetch::CandleInterval, lib::{BacktestParams, BacktesterState}, reconcile::{reconcile, ReconcileConfig},
+        simulate::{self, simulate_with_paper_trading, simulate_with_realtime_data, SimulationConfig}, state_store::StateStore
+    }, upbit_api::client::UpbitClient, webhook::lib::send_webhook
 };
 use tokio::sync::mpsc;
-use chrono::{DateTime, Utc, Duration, TimeZone};
+use chrono::{DateTime, Utc, Duration, TimeZone, NaiveDateTime};
 use rand::Rng;
 
 const CODES: [&str; 5] = ["KRW-XRP", "KRW-BLAST", "KRW-BTC", "KRW-ETH", "KRW-GLM"];
+const CANDLE_STORE_PATH: &str = "data/candles";
+const LIVE_STATE_PATH: &str = "data/state/live.json";
 
 fn generate_random_date() -> String {
     let mut rng = rand::rng();
@@ -25,36 +28,30 @@ fn generate_random_date() -> String {
 
 #[tokio::main]
 async fn main() {
+    // 저장된 실거래 상태가 중단된 동안 바뀐 계좌와 다르면 알림
+    reconcile_live_state(&UpbitClient::from_env()).await;
+
     // realtime_simulation().await;
+    // paper_trading().await;
     snapshop_simulation().await;
 }
 
 async fn snapshop_simulation() {
     let random_date = generate_random_date();
     println!("선택된 랜덤 시간: {}", random_date);
-    
-    let candles = fetch_n_minute_candles("KRW-BTC", 10000, &random_date, 5).await.unwrap();
-    let candles = candles.into_iter().map(|c| {
-        // Box<dyn CandleTrait>에서 Candle로 변환
-        // 실제로는 MinuteCandle이므로 Candle로 변환
-        let base = CandleBase {
-            market: c.get_market().to_string(),
-            candle_date_time_utc: c.get_candle_date_time_utc().to_string(),
-            candle_date_time_kst: c.get_candle_date_time_kst().to_string(),
-            opening_price: c.get_opening_price(),
-            high_price: c.get_high_price(),
-            low_price: c.get_low_price(),
-            trade_price: c.get_trade_price(),
-            timestamp: c.get_timestamp(),
-            candle_acc_trade_price: c.get_candle_acc_trade_price(),
-            candle_acc_trade_volume: c.get_candle_acc_trade_volume(),
-        };
-        Candle { base }
-    }).rev().collect();
+
+    // 5분봉 10,000개 구간. 로컬 저장소에 없는 구간만 업비트에서 받아옴
+    let to = NaiveDateTime::parse_from_str(&random_date, "%Y-%m-%d %H:%M:%S").unwrap();
+    let from = (to - Duration::minutes(5 * 10000)).format("%Y-%m-%d %H:%M:%S").to_string();
+    let client = UpbitClient::from_env();
+    let store = CandleStore::new(CANDLE_STORE_PATH);
+    let candles = store.load_synced(&client, "KRW-BTC", CandleInterval::Minutes(5), &from, &random_date).await.unwrap();
     let mut backtester_params = BacktestParams::default("KRW-XRP", "candle_pattern");
     backtester_params.enable_webhook_log = false;
     let mut backtester = BacktesterState::new(backtester_params);
-    simulate::simulate(candles, &mut backtester);
+    if let Err(e) = simulate::simulate(candles, &mut backtester) {
+        eprintln!("시뮬레이션 실패: {}", e);
+    }
 }
 
 async fn realtime_simulation() {
@@ -72,9 +69,54 @@ async fn realtime_simulation() {
     let mut config = SimulationConfig::new();
     config.enable_log = false;
     send_webhook("booting...", &format!("realtime backtest start - {}", CODES.join(", "))).await;
-    let results = simulate_with_realtime_data(&CODES, &mut shutdown_recv, &config).await;
+    let client = UpbitClient::from_env();
+    let results = match simulate_with_realtime_data(&client, &CODES, &mut shutdown_recv, &config).await {
+        Ok(results) => results,
+        Err(e) => {
+            eprintln!("실시간 백테스트 실패: {}", e);
+            return;
+        }
+    };
 
     println!("모든 백테스트 완료. 결과: {:?}", results.len());
     println!("프로그램을 종료합니다.");
     return;
+}
+
+// 저장된 실거래 상태를 계좌, 미체결 주문과 맞춤. 차이는 알림만 보냄
+//
+// 인증 키가 없거나 실거래 상태를 저장한 적이 없으면 건너뜀. 상태 파일 없이 맞추면 계좌의 모든 보유 수량이 차이로 잡힘
+async fn reconcile_live_state(client: &UpbitClient) {
+    let store = StateStore::new(LIVE_STATE_PATH);
+    if client.credentials().is_none() || !store.exists() {
+        return;
+    }
+    let mut config = ReconcileConfig::new();
+    config.markets = CODES.iter().map(|code| code.to_string()).collect();
+    config.enable_webhook_log = true;
+    let now = Utc::now().format("%Y-%m-%dT%H:%M:%S").to_string();
+    match reconcile(client, &store, &config, &now).await {
+        Ok(resolved) => println!("상태 맞추기 완료 - 차이 {}개", resolved.len()),
+        Err(e) => eprintln!("상태 맞추기 실패: {}", e),
+    }
+}
+
+async fn paper_trading() {
+    let (shutdown_send, mut shutdown_recv) = mpsc::channel(1);
+    tokio::spawn(async move {
+        tokio::signal::ctrl_c().await.expect("failed to install CTRL+C handler");
+        println!("\nCtrl+C 신호 수신. 종료를 시작합니다.");
+        shutdown_send.send(()).await.expect("failed to send shutdown signal");
+    });
+
+    // 재시작하면 저장된 포지션과 전략 상태를 복원하고, 포지션이 바뀔 때마다 저장
+    let mut config = SimulationConfig::new();
+    config.enable_log = false;
+    config.enable_webhook_log = false;
+    config.state_path = Some("data/state/paper.json".to_string());
+    let client = UpbitClient::from_env();
+    match simulate_with_paper_trading(&client, &CODES, &mut shutdown_recv, &config, 1_000_000.0).await {
+        Ok(broker) => println!("모의 거래 종료 - 평가 자산: {:.0} KRW", broker.equity("KRW")),
+        Err(e) => eprintln!("모의 거래 실패: {}", e),
+    }
 }
\ No newline at end of file
@@ -1,7 +1,7 @@
 use crate::{
-    backtest::lib::PositionState, 
-    core::{candle::{Candle, CandleTrait}, signal::{Signal, SignalReason}}, 
-    helper::{atr::{calculate_atr, AtrCandle}, bollinger_bands::calculate_bollinger_bands, candle::{identify_candle_pattern, CandlePattern}, ema::calculate_ema, rsi::calculate_rsi}
+    core::{candle::{Candle, CandleTrait}, position::PositionState, signal::{Signal, SignalReason, TakeProfitTarget}}, 
+    helper::{atr::{calculate_atr, AtrCandle}, bollinger_bands::calculate_bollinger_bands, candle::{identify_candle_pattern, CandlePattern}, ema::calculate_ema, rsi::calculate_rsi},
+    strategy::lib::Strategy
 };
 
 /// 캔들 패턴 전략의 상태
@@ -12,6 +12,8 @@ pub struct CandlePatternStrategyState {
     resistance_levels: Vec<f64>, // 저항선들
     last_trade_candle_index: Option<usize>, // 마지막 거래 캔들 인덱스
     consecutive_losses: usize, // 연속 손실 횟수
+    pending_scale_out: Option<TakeProfitTarget>, // 진입 후 설정할 분할 익절 목표
+    trail_distance: Option<f64>, // 분할 익절 후 남은 수량의 트레일링 스탑 간격 (진입 시 손절폭)
 }
 
 /// 캔들 패턴 전략의 설정
@@ -37,6 +39,10 @@ pub struct CandlePatternStrategyConfig {
 
     // --- disparity ---
     disparity_diff: f64, // 이격도 차이 임계값
+
+    // --- 분할 익절 ---
+    scale_out_r_multiple: f64, // 분할 익절 목표 (진입가 + 손절폭 * 배수)
+    scale_out_pct: f64, // 분할 익절 비율 (0이면 분할 익절하지 않음)
 }
 
 impl CandlePatternStrategyConfig {
@@ -63,8 +69,42 @@ impl CandlePatternStrategyConfig {
 
             // --- disparity ---
             disparity_diff: 0.4, // 이격도 차이 임계값  
+
+            // --- 분할 익절 ---
+            scale_out_r_multiple: 2.0, // 2R에서
+            scale_out_pct: 0.5, // 절반 익절
         }
     }
+
+    /// 이름으로 파라미터 값을 변경 (파라미터 최적화용). 정수 파라미터는 소수점을 버림
+    ///
+    /// 알 수 없는 이름이면 false
+    pub fn set_param(&mut self, name: &str, value: f64) -> bool {
+        match name {
+            "rsi_period" => self.rsi_period = value as usize,
+            "rsi_oversold" => self.rsi_oversold = value,
+            "rsi_overbought" => self.rsi_overbought = value,
+            "volume_threshold" => self.volume_threshold = value,
+            "weight_decay_rate" => self.weight_decay_rate = value,
+            "min_weight_for_buy" => self.min_weight_for_buy = value,
+            "max_weight_for_sell" => self.max_weight_for_sell = value,
+            "short_ema_period" => self.short_ema_period = value as usize,
+            "long_ema_period" => self.long_ema_period = value as usize,
+            "support_rsi_threshold" => self.support_rsi_threshold = value,
+            "resistance_rsi_threshold" => self.resistance_rsi_threshold = value,
+            "stop_loss_multiplier" => self.stop_loss_multiplier = value,
+            "take_profit_multiplier" => self.take_profit_multiplier = value,
+            "max_consecutive_losses" => self.max_consecutive_losses = value as usize,
+            "trend_strength_threshold" => self.trend_strength_threshold = value,
+            "reversal_volume_multiplier" => self.reversal_volume_multiplier = value,
+            "ema_slope_period" => self.ema_slope_period = value as usize,
+            "disparity_diff" => self.disparity_diff = value,
+            "scale_out_r_multiple" => self.scale_out_r_multiple = value,
+            "scale_out_pct" => self.scale_out_pct = value,
+            _ => return false,
+        }
+        true
+    }
 }
 
 impl CandlePatternStrategyState {
@@ -76,6 +116,8 @@ impl CandlePatternStrategyState {
             resistance_levels: Vec::new(),
             last_trade_candle_index: None,
             consecutive_losses: 0,
+            pending_scale_out: None,
+            trail_distance: None,
         }
     }
 
@@ -725,14 +767,14 @@ pub fn candle_pattern_strategy(
     let buy_threshold = config.min_weight_for_buy;
     let sell_threshold = config.max_weight_for_sell;
 
-    // 연속 손실이 너무 많으면 전략 일시 중단
-    // if state.consecutive_losses >= config.max_consecutive_losses {
-    //     return Signal::Hold;
-    // }
+    // 연속 손실이 너무 많을 때의 진입 중단은 백테스터의 `RiskManager`에서 처리 (`RiskConfig::max_consecutive_losses`)
 
     // 포지션 상태에 따른 신호 결정
     match position {
         PositionState::None => {
+            state.pending_scale_out = None;
+            state.trail_distance = None;
+
             // 포지션이 없을 때 - 매수 신호 확인
             if (state.weight >= buy_threshold * 2.0 && trend == TrendDirection::Downtrend) || (state.weight >= buy_threshold && trend != TrendDirection::Downtrend) {
                 // // 단순한 모멘텀 전략: 추세 필터 제거
@@ -756,6 +798,16 @@ pub fn candle_pattern_strategy(
                 
                 // 거래 인덱스 업데이트
                 state.last_trade_candle_index = Some(current_candle_index);
+
+                // 진입 후 2R에서 절반을 익절하고 남은 수량은 손절폭 간격으로 트레일링
+                let risk = current_price - stop_loss;
+                if config.scale_out_pct > 0.0 && risk > 0.0 {
+                    state.pending_scale_out = Some(TakeProfitTarget {
+                        price: current_price + risk * config.scale_out_r_multiple,
+                        pct: config.scale_out_pct,
+                    });
+                    state.trail_distance = Some(risk);
+                }
                 
                 Signal::Buy {
                     reason: format!("캔들 패턴 전략 - 누적 Weight: {:.3}, 현재 Weight: {:.3}, 손절: {:.0}, 익절: {:.0}", 
@@ -768,7 +820,9 @@ pub fn candle_pattern_strategy(
                 Signal::Hold
             }
         }
-        PositionState::InPosition { entry_price: _, entry_asset: _, take_profit_price: _, trailing_stop_price: _ } => {
+        PositionState::InPosition { trailing_stop_price, take_profit_targets, .. } => {
+            let current_price = state.history_candles.last().unwrap().base.trade_price;
+
             // 포지션이 있을 때 - 매도 신호 확인
             if state.weight <= sell_threshold {
                 // 거래 인덱스 업데이트
@@ -777,9 +831,68 @@ pub fn candle_pattern_strategy(
                 Signal::Sell(SignalReason {
                     reason: format!("캔들 패턴 전략 - 누적 Weight: {:.3}, 현재 Weight: {:.3}", state.weight, current_weight),
                 })
+            } else if let Some(target) = state.pending_scale_out.take() {
+                Signal::SetTakeProfitTargets(vec![target])
+            } else if let Some(distance) = state.trail_distance.filter(|_| take_profit_targets.is_empty()) {
+                // 분할 익절 이후 남은 수량 트레일링
+                let new_trailing_stop = current_price - distance;
+                if new_trailing_stop > *trailing_stop_price {
+                    Signal::UpdateTrailingStop(new_trailing_stop)
+                } else {
+                    Signal::Hold
+                }
             } else {
                 Signal::Hold
             }
         }
     }
 }
+
+/// 캔들 패턴 전략의 `Strategy` 구현체
+pub struct CandlePatternStrategy {
+    pub state: CandlePatternStrategyState,
+    pub config: CandlePatternStrategyConfig,
+}
+
+impl CandlePatternStrategy {
+    pub fn new(config: CandlePatternStrategyConfig) -> Self {
+        Self { state: CandlePatternStrategyState::new(), config }
+    }
+}
+
+impl Strategy for CandlePatternStrategy {
+    fn name(&self) -> &str {
+        "candle_pattern"
+    }
+
+    fn warm_up(&mut self, candles: &[Candle]) {
+        for candle in candles {
+            self.state.add_candle(candle.clone(), &self.config);
+        }
+    }
+
+    fn on_candle(&mut self, candle: &Candle, position: &mut PositionState) -> Signal {
+        candle_pattern_strategy(&mut self.state, &self.config, position, Some(candle.clone()))
+    }
+
+    // 캔들 기록과 지지/저항선은 warm_up으로 다시 계산하므로 포지션 관리 상태만 저장
+    fn snapshot(&self) -> Option<serde_json::Value> {
+        Some(serde_json::json!({
+            "weight": self.state.weight,
+            "consecutive_losses": self.state.consecutive_losses,
+            "pending_scale_out": self.state.pending_scale_out,
+            "trail_distance": self.state.trail_distance,
+        }))
+    }
+
+    fn restore(&mut self, snapshot: &serde_json::Value) {
+        if let Some(weight) = snapshot["weight"].as_f64() {
+            self.state.weight = weight;
+        }
+        if let Some(consecutive_losses) = snapshot["consecutive_losses"].as_u64() {
+            self.state.consecutive_losses = consecutive_losses as usize;
+        }
+        self.state.pending_scale_out = serde_json::from_value(snapshot["pending_scale_out"].clone()).unwrap_or(None);
+        self.state.trail_distance = snapshot["trail_distance"].as_f64();
+    }
+}
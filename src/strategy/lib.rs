@@ -1,6 +1,6 @@
 use std::{collections::VecDeque, time::{Duration, Instant}};
 
-use crate::{core::{candle::CandleTrait, orderbook::Orderbook, trade::Trade}};
+use crate::core::{candle::{Candle, CandleTrait}, order_event::OrderEvent, orderbook::Orderbook, position::PositionState, signal::Signal, ticker::Ticker, trade::Trade};
 
 pub struct MarketState {
     pub recent_trades: VecDeque<(Instant, Trade)>,
@@ -36,4 +36,117 @@ impl MarketState {
             self.historical_candles.pop_front();
         }
     }
-}
\ No newline at end of file
+}
+
+/// 백테스트와 실시간 실행에서 공통으로 사용하는 전략 인터페이스
+///
+/// 각 이벤트 핸들러는 현재 포지션을 참고하여 `Signal`을 반환함.
+/// 관심 없는 이벤트는 기본 구현(`Signal::Hold`)을 그대로 사용하면 됨
+pub trait Strategy {
+    /// 레지스트리에 등록된 전략 이름
+    fn name(&self) -> &str;
+
+    /// 실행 전 과거 캔들로 상태를 채움 (candles는 오래된 순서)
+    fn warm_up(&mut self, candles: &[Candle]) {
+        let mut position = PositionState::None;
+        for candle in candles {
+            self.on_candle(candle, &mut position);
+        }
+    }
+
+    fn on_candle(&mut self, _candle: &Candle, _position: &mut PositionState) -> Signal {
+        Signal::Hold
+    }
+
+    fn on_trade(&mut self, _trade: &Trade, _position: &mut PositionState) -> Signal {
+        Signal::Hold
+    }
+
+    fn on_orderbook(&mut self, _orderbook: &Orderbook, _position: &mut PositionState) -> Signal {
+        Signal::Hold
+    }
+
+    fn on_ticker(&mut self, _ticker: &Ticker, _position: &mut PositionState) -> Signal {
+        Signal::Hold
+    }
+
+    /// 모의 거래 (`PaperTrader`)에서 주문이 체결되거나 취소된 경우 호출됨. position은 이벤트를 반영한 뒤의 포지션
+    fn on_order_event(&mut self, _event: &OrderEvent, _position: &mut PositionState) -> Signal {
+        Signal::Hold
+    }
+
+    /// 재시작 후에도 유지해야 하는 전략 상태. 캔들로 다시 계산할 수 있는 상태는 제외하며, 없으면 `None`
+    fn snapshot(&self) -> Option<serde_json::Value> {
+        None
+    }
+
+    /// `snapshot`으로 저장한 상태 복원. `warm_up` 뒤에 호출됨
+    fn restore(&mut self, _snapshot: &serde_json::Value) {}
+}
+
+/// `MarketState`에 쌓인 캔들로 매 캔들마다 신호를 계산하는 전략 함수
+pub type MarketStateRunFn<P> = fn(&mut MarketState, &P, &mut PositionState) -> Signal;
+
+// 최근 거래 내역 보관 기간
+const RECENT_TRADE_WINDOW: Duration = Duration::from_secs(60);
+
+/// `run(state, params, position)` 형태의 전략을 `Strategy`로 감싸는 어댑터
+///
+/// 같은 시간대의 캔들이 다시 들어오면 (실시간 candle.1m 갱신) 마지막 캔들을 교체함.
+/// 지표 계산에 필요한 캔들 수(`min_candles`)가 쌓이기 전에는 `Signal::Hold`를 반환
+pub struct MarketStateStrategy<P> {
+    name: &'static str,
+    pub state: MarketState,
+    pub params: P,
+    run_fn: MarketStateRunFn<P>,
+    min_candles: usize,
+    max_candles: usize,
+}
+
+impl<P> MarketStateStrategy<P> {
+    pub fn new(name: &'static str, params: P, run_fn: MarketStateRunFn<P>, min_candles: usize, max_candles: usize) -> Self {
+        Self { name, state: MarketState::new(), params, run_fn, min_candles, max_candles: max_candles.max(min_candles) }
+    }
+
+    fn push_candle(&mut self, candle: &Candle) {
+        let is_same_candle = self.state.historical_candles.back()
+            .map(|last| last.get_candle_date_time_utc() == candle.get_candle_date_time_utc())
+            .unwrap_or(false);
+        if is_same_candle {
+            self.state.historical_candles.pop_back();
+        }
+        self.state.historical_candles.push_back(Box::new(candle.clone()));
+        self.state.prune_old_candles(self.max_candles);
+    }
+}
+
+impl<P> Strategy for MarketStateStrategy<P> {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn warm_up(&mut self, candles: &[Candle]) {
+        for candle in candles {
+            self.push_candle(candle);
+        }
+    }
+
+    fn on_candle(&mut self, candle: &Candle, position: &mut PositionState) -> Signal {
+        self.push_candle(candle);
+        if self.state.historical_candles.len() < self.min_candles {
+            return Signal::Hold;
+        }
+        (self.run_fn)(&mut self.state, &self.params, position)
+    }
+
+    fn on_trade(&mut self, trade: &Trade, _position: &mut PositionState) -> Signal {
+        self.state.recent_trades.push_back((Instant::now(), trade.clone()));
+        self.state.prune_old_trades(RECENT_TRADE_WINDOW);
+        Signal::Hold
+    }
+
+    fn on_orderbook(&mut self, orderbook: &Orderbook, _position: &mut PositionState) -> Signal {
+        self.state.current_orderbook = Some(orderbook.clone());
+        Signal::Hold
+    }
+}
@@ -8,4 +8,5 @@ pub mod vwma_ma_grok;
 pub mod vwap;
 pub mod orderbook;
 pub mod of1;
-pub mod candle_pattern;
\ No newline at end of file
+pub mod candle_pattern;
+pub mod registry;
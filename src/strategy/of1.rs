@@ -1,6 +1,6 @@
-use std::collections::{BTreeMap, VecDeque};
+use std::{collections::{BTreeMap, VecDeque}, io::Write};
 
-use crate::{backtest::lib::PositionState, core::{candle::{Candle, CandleTrait}, orderbook::Orderbook, signal::{Signal, SignalReason}, ticker::Ticker, trade::Trade}, helper::{footprint::{footprint, FootprintValue}, orderbook::top_n_orderbook_ratio}};
+use crate::{core::{aggregator::{Bar, BarInterval, TradeAggregator}, candle::{Candle, CandleTrait}, orderbook::Orderbook, position::PositionState, signal::{Signal, SignalReason}, ticker::Ticker, trade::Trade}, helper::{footprint::{footprint, log_footprint, FootprintTrade, FootprintValue}, orderbook::top_n_orderbook_ratio}, strategy::lib::Strategy};
 
 
 pub struct Of1State {
@@ -9,7 +9,8 @@ pub struct Of1State {
     pub current_ticker: Option<Ticker>,
     pub history_candles: VecDeque<Candle>,
     pub footprints: Vec<BTreeMap<String, FootprintValue>>,
-    pub trades: Vec<Trade>,
+    // 체결로 1분봉을 만듦. 진행 중인 봉이 current_mutation_candle
+    pub aggregator: TradeAggregator,
     pub current_mutation_candle: Option<Candle>,
 
     // --- 세션 상태 ---
@@ -41,7 +42,8 @@ impl Of1Params {
 
 impl Of1State {
     pub fn new() -> Self {
-        Self { current_ticker: None, history_candles: VecDeque::new(), footprints: Vec::new(), trades: Vec::new(), current_mutation_candle: None,
+        Self { current_ticker: None, history_candles: VecDeque::new(), footprints: Vec::new(),
+            aggregator: TradeAggregator::new(BarInterval::Minutes(1)).unwrap().with_trades(), current_mutation_candle: None,
             absorb_price: None, absorb_candle_low_price: None,
             indicator: Of1Indicator::new()
         }
@@ -212,7 +214,7 @@ pub fn of1(state: &mut Of1State, params: &Of1Params, position: &mut PositionStat
                 asset_pct: 1.0,
             };
         }
-    } else if let PositionState::InPosition { entry_price, entry_asset, take_profit_price, trailing_stop_price } = position {
+    } else if let PositionState::InPosition { entry_price, entry_asset, take_profit_price, trailing_stop_price, .. } = position {
         if current_price > *take_profit_price {
             return Signal::Sell(SignalReason { reason: "OF1 Take Profit".to_string() });
         }
@@ -223,4 +225,157 @@ pub fn of1(state: &mut Of1State, params: &Of1Params, position: &mut PositionStat
     }
 
     Signal::Hold
-}
\ No newline at end of file
+}
+
+// Trade를 FootprintTrade로 변환하는 함수
+fn convert_trade_to_footprint_trade(trade: &Trade) -> FootprintTrade {
+    FootprintTrade {
+        ask_bid: trade.ask_bid.clone(),
+        price: trade.trade_price,
+        volume: trade.trade_volume,
+    }
+}
+
+/// of1 전략의 `Strategy` 구현체
+///
+/// 체결로 직접 1분봉과 풋프린트를 만들고, ticker마다 신호를 계산함
+pub struct Of1Strategy {
+    pub state: Of1State,
+    pub params: Of1Params,
+    pub enable_log: bool,
+}
+
+impl Of1Strategy {
+    pub fn new(params: Of1Params) -> Self {
+        Self { state: Of1State::new(), params, enable_log: false }
+    }
+
+    /// 1분봉이 마감되면 히스토리, 지표, 풋프린트를 갱신
+    fn close_bar(&mut self, bar: Bar) {
+        let (top_n_trade_volume_avg_fn, log_footprint_fn, _) = get_simulate_log_fns();
+        let state = &mut self.state;
+
+        state.history_candles.push_back(bar.to_candle());
+
+        // of1 지표 갱신
+        calculate_of1_indicator_every_1mcandle(state, &self.params);
+
+        // 마감된 봉의 체결로 풋프린트 계산
+        let footprint_trades = bar.trades.iter().map(convert_trade_to_footprint_trade).collect::<Vec<FootprintTrade>>();
+        let footprint = footprint(&footprint_trades);
+        let recent_candle_10 = state.history_candles.iter().rev().take(10).cloned().collect::<Vec<Candle>>();
+        state.indicator.top_n_trade_volume_avg = top_n_trade_volume_avg_fn(&recent_candle_10);
+
+        if self.enable_log {
+            // 이전 캔들 폭 계산
+            let previous_candle = state.history_candles.back().unwrap();
+            let range = (previous_candle.get_high_price() - previous_candle.get_low_price()).abs();
+            let avg_range = state.indicator.candle_20_avg_candle_range;
+            let volume = previous_candle.get_candle_acc_trade_volume();
+            let avg_volume = state.indicator.candle_10_avg_volume;
+            let bullish = previous_candle.get_opening_price() < previous_candle.get_trade_price();
+            println!("\nrange: {} | avg_range: {} | volume: {} | avg_volume: {} | bullish: {}", range, avg_range, volume, avg_volume, bullish);
+
+            log_footprint_fn(&footprint);
+        }
+
+        // 새로운 footprint 추가
+        state.footprints.push(footprint);
+    }
+}
+
+impl Strategy for Of1Strategy {
+    fn name(&self) -> &str {
+        "of1"
+    }
+
+    // 마지막 캔들은 아직 마감되지 않은 분봉이므로 제외 (이후 체결로 다시 만들어져 마감됨)
+    fn warm_up(&mut self, candles: &[Candle]) {
+        let closed = &candles[..candles.len().saturating_sub(1)];
+        self.state.history_candles.extend(closed.iter().cloned());
+        calculate_of1_indicator_every_1mcandle(&mut self.state, &self.params);
+    }
+
+    fn on_trade(&mut self, trade: &Trade, _position: &mut PositionState) -> Signal {
+        if let Some(bar) = self.state.aggregator.push(trade) {
+            self.close_bar(bar);
+        }
+        self.state.current_mutation_candle = self.state.aggregator.current().map(|bar| bar.to_candle());
+        Signal::Hold
+    }
+
+    fn on_ticker(&mut self, ticker: &Ticker, position: &mut PositionState) -> Signal {
+        self.state.current_ticker = Some(ticker.clone());
+        let signal = of1(&mut self.state, &self.params, position);
+
+        if self.enable_log {
+            if let Some(candle) = self.state.current_mutation_candle.as_ref() {
+                let (_, _, log_indicator_fn) = get_simulate_log_fns();
+                log_indicator_fn(&Indicator {
+                    top_n_trade_volume_avg: self.state.indicator.top_n_trade_volume_avg,
+                    previous_candle: candle.clone(),
+                    current_price: ticker.trade_price,
+                    current_candle_volume: candle.get_candle_acc_trade_volume(),
+                });
+            }
+        }
+
+        signal
+    }
+}
+
+pub struct Indicator {
+    pub top_n_trade_volume_avg: f64,
+    // 이전 캔들
+    pub previous_candle: Candle,
+    pub current_price: f64,
+    pub current_candle_volume: f64,
+}
+
+pub fn get_simulate_log_fns() -> (impl Fn(&Vec<Candle>) -> f64 + Clone, impl Fn(&BTreeMap<String, FootprintValue>) + Clone, impl Fn(&Indicator) + Clone) {
+    // 최근 10개 캔들의 거래량 평균
+    let top_n_trade_volume_avg_fn = |candles: &Vec<Candle>| {
+        let recent_candle_10 = candles.iter().rev().take(10).collect::<Vec<&Candle>>();
+        let recent_candle_10_avg = recent_candle_10.iter().map(|c| c.get_candle_acc_trade_volume()).sum::<f64>() / recent_candle_10.len() as f64;
+        recent_candle_10_avg
+    };
+
+    // 푸터프린트 출력
+    let log_footprint_fn = |footprint: &BTreeMap<String, FootprintValue>| {
+        let mut prices = footprint.keys().collect::<Vec<&String>>();
+        prices.sort_by(|a, b| b.partial_cmp(a).unwrap());
+
+        let mut total_ask_vol = 0.0;
+        let mut total_bid_vol = 0.0;
+
+        for price in prices.iter() {
+            let ask_vol = footprint[*price].ask_volume;
+            let bid_vol = footprint[*price].bid_volume;
+
+            total_ask_vol += ask_vol;
+            total_bid_vol += bid_vol;
+        }
+
+        let volume_sum = total_ask_vol + total_bid_vol;
+        
+        println!();
+        println!("footprint 총 거래량: {},  매수 비율: {} ", volume_sum, total_bid_vol / volume_sum);
+
+        let footprint_vec = prices.iter().map(|p| (p.to_string(), footprint[*p].clone())).collect::<Vec<(String, FootprintValue)>>();
+        log_footprint(footprint_vec);
+    };
+
+    let log_indicator_fn = |indicator: &Indicator| {
+        print!("\r최근 10개 캔들 거래량 평균: {} | 이전 저가: {} | 이전 고가: {} | 이전 시가: {} | 이전 종가: {} | 현재가: {} | 현재 캔들 거래량: {}"
+        , indicator.top_n_trade_volume_avg
+        , indicator.previous_candle.get_low_price()
+        , indicator.previous_candle.get_high_price()
+        , indicator.previous_candle.get_opening_price()
+        , indicator.previous_candle.get_trade_price()
+        , indicator.current_price
+        , indicator.current_candle_volume);
+        std::io::stdout().flush().unwrap();
+    };
+
+    (top_n_trade_volume_avg_fn, log_footprint_fn, log_indicator_fn)
+}
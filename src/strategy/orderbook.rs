@@ -1,4 +1,4 @@
-use crate::{backtest::lib::PositionState, core::signal::{Signal, SignalReason}, helper::williams_fractal::{calculate_williams_fractals, FractalCandle, FractalType}, strategy::lib::MarketState};
+use crate::{core::{position::PositionState, signal::{Signal, SignalReason}}, helper::williams_fractal::{calculate_williams_fractals, FractalCandle, FractalType}, strategy::lib::MarketState};
 
 pub struct StrategyParams {}
 
@@ -106,7 +106,7 @@ pub fn run(state: &mut MarketState, params: &StrategyParams, position: &mut Posi
                 asset_pct: 1.0,
             };
         }
-    } else if let PositionState::InPosition { entry_price, entry_asset, take_profit_price, trailing_stop_price } = position {
+    } else if let PositionState::InPosition { entry_price, entry_asset, take_profit_price, trailing_stop_price, .. } = position {
         if current_price > *take_profit_price {
             return Signal::Sell(SignalReason {
                 reason: format!("Take profit price is reached: {}", take_profit_price),
@@ -0,0 +1,115 @@
+use std::fmt;
+
+use crate::strategy::{
+    candle_pattern::{CandlePatternStrategy, CandlePatternStrategyConfig},
+    lib::{MarketStateStrategy, Strategy},
+    of1::{Of1Params, Of1Strategy},
+    orderbook, scalp, supertrend_ema, swc, vi_rsi, vwap, vwma_ma, vwma_ma_grok,
+};
+
+/// 레지스트리에 등록된 전략 이름 목록
+pub const STRATEGY_NAMES: [&str; 10] = [
+    "swc", "vi_rsi", "supertrend_ema", "scalp", "vwma_ma", "vwma_ma_grok", "vwap", "orderbook", "of1", "candle_pattern",
+];
+
+// MarketState 기반 전략이 유지할 최대 캔들 수
+const MAX_HISTORY_CANDLES: usize = 300;
+
+/// 체결로 봉과 풋프린트를 직접 만드는 전략. 캔들만 주어지는 백테스트에서는 신호를 낼 수 없음
+pub const TRADE_ONLY_STRATEGIES: [&str; 1] = ["of1"];
+
+/// 이름으로 전략을 생성
+///
+/// 등록되지 않은 이름이면 `None`을 반환
+pub fn create_strategy(name: &str, enable_log: bool) -> Option<Box<dyn Strategy>> {
+    let strategy: Box<dyn Strategy> = match name {
+        "swc" => Box::new(MarketStateStrategy::new("swc", swc::StrategyParams::new(), swc::run, 30, MAX_HISTORY_CANDLES)),
+        "vi_rsi" => Box::new(MarketStateStrategy::new("vi_rsi", vi_rsi::StrategyParams {}, vi_rsi::run, 50, MAX_HISTORY_CANDLES)),
+        "supertrend_ema" => Box::new(MarketStateStrategy::new("supertrend_ema", supertrend_ema::StrategyParams {}, supertrend_ema::run, 50, MAX_HISTORY_CANDLES)),
+        "scalp" => Box::new(MarketStateStrategy::new("scalp", scalp::StrategyParams {}, scalp::run, 20, MAX_HISTORY_CANDLES)),
+        "vwma_ma" => Box::new(MarketStateStrategy::new("vwma_ma", vwma_ma::StrategyParams {}, vwma_ma::run, 200, MAX_HISTORY_CANDLES)),
+        "vwma_ma_grok" => Box::new(MarketStateStrategy::new("vwma_ma_grok", vwma_ma_grok::StrategyParams::default(), vwma_ma_grok::run, 200, MAX_HISTORY_CANDLES)),
+        "vwap" => Box::new(MarketStateStrategy::new("vwap", vwap::StrategyParams {}, vwap::run, 30, MAX_HISTORY_CANDLES)),
+        "orderbook" => Box::new(MarketStateStrategy::new("orderbook", orderbook::StrategyParams {}, orderbook::run, 5, MAX_HISTORY_CANDLES)),
+        "of1" => {
+            let mut strategy = Of1Strategy::new(Of1Params::new());
+            strategy.enable_log = enable_log;
+            Box::new(strategy)
+        }
+        "candle_pattern" => {
+            let mut config = CandlePatternStrategyConfig::new();
+            config.enable_log = enable_log;
+            Box::new(CandlePatternStrategy::new(config))
+        }
+        _ => return None,
+    };
+    Some(strategy)
+}
+
+/// 파라미터를 바꾼 전략 생성 실패
+#[derive(Debug, Clone, PartialEq)]
+pub enum StrategyParamError {
+    /// 등록되지 않은 전략 이름
+    UnknownStrategy(String),
+    /// 전략에 없는 파라미터 이름
+    UnknownParam { strategy: String, param: String },
+    /// 체결 데이터가 필요해 캔들 백테스트에서 실행할 수 없는 전략
+    RequiresTrades(String),
+}
+
+impl fmt::Display for StrategyParamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StrategyParamError::UnknownStrategy(name) => write!(f, "unknown strategy: {}", name),
+            StrategyParamError::UnknownParam { strategy, param } => write!(f, "unknown param {} for strategy {}", param, strategy),
+            StrategyParamError::RequiresTrades(name) => write!(f, "strategy {} needs trade data and cannot run on candles", name),
+        }
+    }
+}
+
+impl std::error::Error for StrategyParamError {}
+
+/// 기본 설정에서 params의 (이름, 값)을 바꿔 전략을 생성
+///
+/// 파라미터를 바꿀 수 있는 전략은 swc, candle_pattern. 다른 전략은 params가 비어 있을 때만 생성됨
+pub fn create_strategy_with_params(name: &str, params: &[(String, f64)], enable_log: bool) -> Result<Box<dyn Strategy>, StrategyParamError> {
+    let unknown_param = |param: &str| StrategyParamError::UnknownParam { strategy: name.to_string(), param: param.to_string() };
+    match name {
+        "swc" => {
+            let mut strategy_params = swc::StrategyParams::new();
+            for (param, value) in params {
+                if !strategy_params.set_param(param, *value) {
+                    return Err(unknown_param(param));
+                }
+            }
+            Ok(Box::new(MarketStateStrategy::new("swc", strategy_params, swc::run, 30, MAX_HISTORY_CANDLES)))
+        }
+        "candle_pattern" => {
+            let mut config = CandlePatternStrategyConfig::new();
+            config.enable_log = enable_log;
+            for (param, value) in params {
+                if !config.set_param(param, *value) {
+                    return Err(unknown_param(param));
+                }
+            }
+            Ok(Box::new(CandlePatternStrategy::new(config)))
+        }
+        _ => {
+            let strategy = create_strategy(name, enable_log).ok_or_else(|| StrategyParamError::UnknownStrategy(name.to_string()))?;
+            match params.first() {
+                Some((param, _)) => Err(unknown_param(param)),
+                None => Ok(strategy),
+            }
+        }
+    }
+}
+
+/// 캔들 백테스트 (시뮬레이션, 최적화, 포트폴리오)에서 사용할 전략 생성
+///
+/// `TRADE_ONLY_STRATEGIES`는 캔들만으로 신호를 낼 수 없으므로 에러
+pub fn create_candle_strategy(name: &str, params: &[(String, f64)], enable_log: bool) -> Result<Box<dyn Strategy>, StrategyParamError> {
+    if TRADE_ONLY_STRATEGIES.contains(&name) {
+        return Err(StrategyParamError::RequiresTrades(name.to_string()));
+    }
+    create_strategy_with_params(name, params, enable_log)
+}
@@ -1,7 +1,6 @@
 // 필요한 모듈과 타입을 가져옵니다.
 use crate::{
-    backtest::lib::PositionState, 
-    core::{candle::CandleTrait, signal::{Signal, SignalReason}}, 
+    core::{candle::CandleTrait, position::PositionState, signal::{Signal, SignalReason}}, 
     helper::{level::find_support_resistance, rsi::calculate_rsi, sma::calculate_sma}, 
     strategy::lib::MarketState
 };
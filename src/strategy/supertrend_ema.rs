@@ -1,4 +1,4 @@
-use crate::{backtest::lib::PositionState, core::signal::{Signal, SignalReason},
+use crate::{core::{position::PositionState, signal::{Signal, SignalReason}},
     helper::{ema::calculate_ema, macd::calculate_macd, supertrend::{calculate_supertrend, Ohlcv}},
     strategy::lib::MarketState
 };
@@ -44,7 +44,7 @@ pub fn run(state: &mut MarketState, params: &StrategyParams, current_position: &
                 asset_pct: 1.0,
             }
         } 
-    } else if let PositionState::InPosition { entry_price: _, entry_asset: _, take_profit_price: _, trailing_stop_price } = current_position {
+    } else if let PositionState::InPosition { trailing_stop_price, .. } = current_position {
         if current_price < *trailing_stop_price {
             return Signal::Sell(SignalReason { reason: "추적 손절매 도달".to_string() });
         }
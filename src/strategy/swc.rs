@@ -1,7 +1,8 @@
 use std::time::Duration;
 
 use crate::{
-    backtest::lib::PositionState, core::{
+    core::{
+        position::PositionState,
         signal::{Signal, SignalReason}, 
     }, helper::{
         adx::{calculate_adx, AdxCandle}, 
@@ -39,6 +40,47 @@ pub struct StrategyParams {
     pub atr_trailing_multiplier: f64, // 추적 손절매에 사용할 ATR 승수 (예: 1.5)
 }
 
+impl StrategyParams {
+    pub fn new() -> Self {
+        Self {
+            trade_delta_window: Duration::from_secs(60),
+            obi_depth: 5,
+            wall_krw_threshold: 100_000_000.0,
+            atr_period: 14,
+            atr_multiplier: 1.5,
+            base_delta_threshold: 0.1,
+            bb_period: 20,
+            bb_multiplier: 2.0,
+            adx_period: 14,
+            rsi_period: 8,
+            risk_reward_ratio: 2.0,
+            atr_trailing_multiplier: 1.5,
+        }
+    }
+
+    /// 이름으로 파라미터 값을 변경 (파라미터 최적화용). 정수 파라미터는 소수점을 버리고, trade_delta_window는 초 단위
+    ///
+    /// 알 수 없는 이름이면 false
+    pub fn set_param(&mut self, name: &str, value: f64) -> bool {
+        match name {
+            "trade_delta_window" => self.trade_delta_window = Duration::from_secs_f64(value.max(0.0)),
+            "obi_depth" => self.obi_depth = value as usize,
+            "wall_krw_threshold" => self.wall_krw_threshold = value,
+            "atr_period" => self.atr_period = value as usize,
+            "atr_multiplier" => self.atr_multiplier = value,
+            "base_delta_threshold" => self.base_delta_threshold = value,
+            "bb_period" => self.bb_period = value as usize,
+            "bb_multiplier" => self.bb_multiplier = value,
+            "adx_period" => self.adx_period = value as u32,
+            "rsi_period" => self.rsi_period = value as usize,
+            "risk_reward_ratio" => self.risk_reward_ratio = value,
+            "atr_trailing_multiplier" => self.atr_trailing_multiplier = value,
+            _ => return false,
+        }
+        true
+    }
+}
+
 pub fn run(state: &mut MarketState, params: &StrategyParams, current_position: &mut PositionState) -> Signal {
     // 1. 데이터 유효성 검사: 전략에 필요한 최소 캔들 수 확인
     let required_data_points = params.bb_period
@@ -147,7 +189,7 @@ pub fn run(state: &mut MarketState, params: &StrategyParams, current_position: &
         }
 
         // B. 포지션이 있는 경우: 종료 조건 확인
-        PositionState::InPosition { entry_price: _, entry_asset: _, take_profit_price: _, trailing_stop_price } => {
+        PositionState::InPosition { trailing_stop_price, .. } => {
             // 조건 1: 추적 손절매 가격 도달 시 매도
             if current_price < *trailing_stop_price {
                 let reason = format!("추적 손절매 도달 (Price: {:.2} < Stop: {:.2})", current_price, trailing_stop_price);
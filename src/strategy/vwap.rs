@@ -1,6 +1,5 @@
 use crate::{
-    backtest::lib::PositionState, 
-    core::signal::Signal, 
+    core::{position::PositionState, signal::Signal}, 
     helper::{adx::{calculate_adx, AdxCandle}, vwap_band::{calculate_vwap_bands, VwapCandle}}, 
     strategy::lib::MarketState
 };
@@ -1,6 +1,5 @@
 use crate::{
-    backtest::lib::PositionState, 
-    core::{candle::CandleTrait, signal::{Signal, SignalReason}}, 
+    core::{candle::CandleTrait, position::PositionState, signal::{Signal, SignalReason}}, 
     helper::{adx::{calculate_adx, AdxCandle}, level::find_support_resistance, previous::find_previous_trough_with_index, rsi::calculate_rsi, sma::calculate_sma, vwma::{calculate_vwma, VWMACandle}}, 
     strategy::lib::MarketState
 };
@@ -1,8 +1,8 @@
 use serde::Deserialize;
 
-use crate::{utils::str_to_f64, upbit_api::utils::request_upbit_api};
+use crate::{utils::str_to_f64, upbit_api::{client::UpbitClient, error::UpbitError}};
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 pub struct Account {
     pub currency: String,
     #[serde(deserialize_with = "str_to_f64")]
@@ -15,12 +15,8 @@ pub struct Account {
     pub unit_currency: String,
 }
 
-pub async fn check_my_account() -> Result<Vec<Account>, Box<dyn std::error::Error>> {
-    let body = request_upbit_api("/accounts", None).await;
-    if let Some(body) = body {
-        let accounts: Vec<Account> = serde_json::from_str(&body)?;
-        Ok(accounts)
-    } else {
-        Err("Failed to get accounts".into())
-    }
+pub async fn check_my_account(client: &UpbitClient) -> Result<Vec<Account>, UpbitError> {
+    let body = client.get("/accounts", None).await?;
+    let accounts: Vec<Account> = serde_json::from_str(&body)?;
+    Ok(accounts)
 }
\ No newline at end of file
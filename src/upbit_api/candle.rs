@@ -1,45 +1,43 @@
-use crate::{core::candle::{Candle, DayCandle, MinuteCandle}, upbit_api::utils::request_upbit_api};
+use crate::{core::candle::{Candle, DayCandle, MinuteCandle, PeriodCandle}, upbit_api::{client::UpbitClient, error::UpbitError}};
 
-pub async fn get_candle_seconds(market: &str, to: Option<&str>, count: u32) -> Result<Vec<Candle>, Box<dyn std::error::Error>> {
-    let query = if let Some(to) = to 
-    { format!("?market={}&to={}&count={}", market, to, count) } else { format!("?market={}&count={}", market, count) };
-    
-    let body = request_upbit_api("/candles/seconds", Some(query)).await;
+fn candle_query(market: &str, to: Option<&str>, count: u32) -> String {
+    if let Some(to) = to 
+    { format!("market={}&to={}&count={}", market, to, count) } else { format!("market={}&count={}", market, count) }
+}
 
-    if let Some(body) = body {
-        // println!("{}", body);
-        let candles: Vec<Candle> = serde_json::from_str(&body)?;
-        Ok(candles)
-    } else {
-        Err("Failed to get candle info".into())
-    }
+pub async fn get_candle_seconds(client: &UpbitClient, market: &str, to: Option<&str>, count: u32) -> Result<Vec<Candle>, UpbitError> {
+    let body = client.get("/candles/seconds", Some(candle_query(market, to, count))).await?;
+    let candles: Vec<Candle> = serde_json::from_str(&body)?;
+    Ok(candles)
 }
 
-pub async fn get_candle_minutes(market: &str, to: Option<&str>, count: u32, unit: u32) -> Result<Vec<MinuteCandle>, Box<dyn std::error::Error>> {
-    let query = if let Some(to) = to 
-    { format!("?market={}&to={}&count={}&unit={}", market, to, count, unit) } else { format!("?market={}&count={}&unit={}", market, count, unit) };
-    
-    let body = request_upbit_api(format!("/candles/minutes/{}", unit).as_str(), Some(query)).await;
+pub async fn get_candle_minutes(client: &UpbitClient, market: &str, to: Option<&str>, count: u32, unit: u32) -> Result<Vec<MinuteCandle>, UpbitError> {
+    let query = format!("{}&unit={}", candle_query(market, to, count), unit);
+    let body = client.get(format!("/candles/minutes/{}", unit).as_str(), Some(query)).await?;
+    let candles: Vec<MinuteCandle> = serde_json::from_str(&body)?;
+    Ok(candles)
+}
 
-    if let Some(body) = body {
-        let candles: Vec<MinuteCandle> = serde_json::from_str(&body)?;
-        Ok(candles)
-    } else {
-        Err("Failed to get candle info".into())
-    }
+pub async fn get_candle_days(client: &UpbitClient, market: &str, to: Option<&str>, count: u32) -> Result<Vec<DayCandle>, UpbitError> {
+    let body = client.get("/candles/days", Some(candle_query(market, to, count))).await?;
+    let candles: Vec<DayCandle> = serde_json::from_str(&body)?;
+    Ok(candles)
 }
 
-pub async fn get_candle_days(market: &str, to: Option<&str>, count: u32) -> Result<Vec<DayCandle>, Box<dyn std::error::Error>> {
-    let query = if let Some(to) = to 
-    { format!("?market={}&to={}&count={}", market, to, count) } else { format!("?market={}&count={}", market, count) };
-    
-    let body = request_upbit_api("/candles/days", Some(query)).await;
+pub async fn get_candle_weeks(client: &UpbitClient, market: &str, to: Option<&str>, count: u32) -> Result<Vec<PeriodCandle>, UpbitError> {
+    let body = client.get("/candles/weeks", Some(candle_query(market, to, count))).await?;
+    let candles: Vec<PeriodCandle> = serde_json::from_str(&body)?;
+    Ok(candles)
+}
+
+pub async fn get_candle_months(client: &UpbitClient, market: &str, to: Option<&str>, count: u32) -> Result<Vec<PeriodCandle>, UpbitError> {
+    let body = client.get("/candles/months", Some(candle_query(market, to, count))).await?;
+    let candles: Vec<PeriodCandle> = serde_json::from_str(&body)?;
+    Ok(candles)
+}
 
-    if let Some(body) = body {
-        // println!("{}", body);
-        let candles: Vec<DayCandle> = serde_json::from_str(&body)?;
-        Ok(candles)
-    } else {
-        Err("Failed to get candle info".into())
-    }
+pub async fn get_candle_years(client: &UpbitClient, market: &str, to: Option<&str>, count: u32) -> Result<Vec<PeriodCandle>, UpbitError> {
+    let body = client.get("/candles/years", Some(candle_query(market, to, count))).await?;
+    let candles: Vec<PeriodCandle> = serde_json::from_str(&body)?;
+    Ok(candles)
 }
\ No newline at end of file
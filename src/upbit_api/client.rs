@@ -0,0 +1,173 @@
+use std::time::Duration;
+
+use reqwest::Method;
+use serde_json::Value;
+
+use crate::upbit_api::{error::{RemainingReq, UpbitError}, rate_limit::{RateLimitGroup, RateLimiter}, utils::{create_authorization_token, UPBIT_BASE_URL, UPBIT_WEBSOCKET_URL}};
+
+/// Upbit API 인증 키
+#[derive(Clone)]
+pub struct UpbitCredentials {
+    pub access_key: String,
+    pub secret_key: String,
+}
+
+/// 모든 Upbit API 호출에 사용하는 클라이언트
+///
+/// REST/WebSocket base url과 인증 키를 보관하고, 복제하더라도 같은 커넥션 풀을 공유함.
+/// base url을 주입할 수 있어 로컬 stub 서버로 테스트할 수 있음
+#[derive(Clone)]
+pub struct UpbitClient {
+    pub base_url: String,
+    pub ws_url: String,
+    credentials: Option<UpbitCredentials>,
+    http: reqwest::Client,
+    rate_limiter: RateLimiter,
+    // 429 응답 시 재시도 횟수와 첫 대기 시간 (재시도마다 2배)
+    max_retries: u32,
+    retry_backoff: Duration,
+}
+
+const DEFAULT_MAX_RETRIES: u32 = 3;
+const DEFAULT_RETRY_BACKOFF: Duration = Duration::from_millis(250);
+
+impl UpbitClient {
+    pub fn new(base_url: &str, access_key: &str, secret_key: &str) -> Self {
+        Self::public(base_url).with_credentials(access_key, secret_key)
+    }
+
+    /// 인증 키 없이 시세 조회 API만 사용하는 클라이언트
+    ///
+    /// 요청 수 제한은 같은 base url을 사용하는 모든 클라이언트와 공유함
+    pub fn public(base_url: &str) -> Self {
+        let base_url = base_url.trim_end_matches('/').to_string();
+        Self {
+            ws_url: UPBIT_WEBSOCKET_URL.to_string(),
+            credentials: None,
+            http: reqwest::Client::new(),
+            rate_limiter: RateLimiter::shared(&base_url),
+            base_url,
+            max_retries: DEFAULT_MAX_RETRIES,
+            retry_backoff: DEFAULT_RETRY_BACKOFF,
+        }
+    }
+
+    /// 환경 변수로 클라이언트 생성
+    ///
+    /// - UPBIT_ACCESS_KEY, UPBIT_SECRET_KEY: 없으면 인증 없이 호출
+    /// - UPBIT_API_URL, UPBIT_WS_URL: 지정하면 기본 Upbit 주소 대신 사용
+    pub fn from_env() -> Self {
+        dotenv::dotenv().ok();
+        let base_url = dotenv::var("UPBIT_API_URL").unwrap_or(UPBIT_BASE_URL.to_string());
+        let ws_url = dotenv::var("UPBIT_WS_URL").unwrap_or(UPBIT_WEBSOCKET_URL.to_string());
+        let client = Self::public(&base_url).with_ws_url(&ws_url);
+        match (dotenv::var("UPBIT_ACCESS_KEY"), dotenv::var("UPBIT_SECRET_KEY")) {
+            (Ok(access_key), Ok(secret_key)) => client.with_credentials(&access_key, &secret_key),
+            _ => client,
+        }
+    }
+
+    pub fn with_credentials(mut self, access_key: &str, secret_key: &str) -> Self {
+        self.credentials = Some(UpbitCredentials {
+            access_key: access_key.to_string(),
+            secret_key: secret_key.to_string(),
+        });
+        self
+    }
+
+    pub fn with_ws_url(mut self, ws_url: &str) -> Self {
+        self.ws_url = ws_url.to_string();
+        self
+    }
+
+    /// 기본 공유 제한기 대신 별도의 제한기를 사용할 때 사용
+    pub fn with_rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = rate_limiter;
+        self
+    }
+
+    /// 429 응답 재시도 설정
+    pub fn with_retry(mut self, max_retries: u32, retry_backoff: Duration) -> Self {
+        self.max_retries = max_retries;
+        self.retry_backoff = retry_backoff;
+        self
+    }
+
+    pub fn rate_limiter(&self) -> &RateLimiter {
+        &self.rate_limiter
+    }
+
+    pub fn credentials(&self) -> Option<&UpbitCredentials> {
+        self.credentials.as_ref()
+    }
+
+    /// `Bearer {jwt}` 형식의 인증 헤더 값 생성
+    ///
+    /// query는 `?` 없이 `key=value&...` 형식이어야 함
+    pub fn authorization_token(&self, query: &Option<String>) -> Option<String> {
+        let credentials = self.credentials.as_ref()?;
+        create_authorization_token(&credentials.access_key, &credentials.secret_key, query)
+    }
+
+    /// 요청을 보내고 응답 본문을 반환. 인증 키가 있으면 인증 헤더를 붙임
+    ///
+    /// 엔드포인트 그룹별 요청 수 제한을 지키며, 429 응답을 받으면 대기 후 재시도함
+    ///
+    /// - query: URL 쿼리 스트링 (`?` 제외). query_hash 계산에 사용됨
+    /// - body: JSON 본문. 이 경우 query는 본문을 쿼리 스트링으로 변환한 값이어야 함
+    pub async fn send(&self, method: Method, path: &str, query: Option<String>, body: Option<Value>)
+    -> Result<String, UpbitError> {
+        let group = RateLimitGroup::for_request(&method, path);
+        let mut backoff = self.retry_backoff;
+        let mut attempt = 0;
+        loop {
+            self.rate_limiter.acquire(group).await;
+            match self.send_once(method.clone(), path, &query, &body, group).await {
+                Err(UpbitError::RateLimited { .. }) if attempt < self.max_retries => {
+                    self.rate_limiter.block(group, backoff);
+                    backoff *= 2;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn send_once(&self, method: Method, path: &str, query: &Option<String>, body: &Option<Value>, group: RateLimitGroup)
+    -> Result<String, UpbitError> {
+        let url = match (query, body) {
+            (Some(query), None) => format!("{}{}?{}", self.base_url, path, query),
+            _ => format!("{}{}", self.base_url, path),
+        };
+
+        let mut request = self.http.request(method, url);
+        if self.credentials.is_some() {
+            // 재시도마다 nonce가 달라야 하므로 토큰을 새로 만듦
+            let authorization_token = self.authorization_token(query)
+                .ok_or(UpbitError::Jwt("invalid secret key".to_string()))?;
+            request = request.header("Authorization", authorization_token);
+        }
+        if let Some(body) = body {
+            request = request.json(body);
+        }
+
+        let response = request.send().await?;
+        let status = response.status();
+        let remaining_req = response.headers().get("Remaining-Req")
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.to_string());
+        if let Some(remaining_req) = remaining_req.as_deref().and_then(RemainingReq::parse) {
+            self.rate_limiter.update(group, &remaining_req);
+        }
+        let text = response.text().await?;
+        if !status.is_success() {
+            return Err(UpbitError::from_response(status.as_u16(), remaining_req.as_deref(), &text));
+        }
+        Ok(text)
+    }
+
+    /// GET 요청
+    pub async fn get(&self, path: &str, query: Option<String>) -> Result<String, UpbitError> {
+        self.send(Method::GET, path, query, None).await
+    }
+}
@@ -0,0 +1,162 @@
+use std::fmt;
+
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+
+use crate::upbit_api::market_rules::MarketRuleError;
+
+/// Upbit 에러 응답 본문 (`{"error": {"name": ..., "message": ...}}`)
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct UpbitErrorBody {
+    #[serde(deserialize_with = "value_to_string")]
+    pub name: String,
+    #[serde(default)]
+    pub message: String,
+}
+
+#[derive(Deserialize)]
+struct UpbitErrorResponse {
+    error: UpbitErrorBody,
+}
+
+impl UpbitErrorBody {
+    /// 응답 본문에서 에러 정보를 추출. 형식이 다르면 `None`
+    pub fn parse(body: &str) -> Option<Self> {
+        serde_json::from_str::<UpbitErrorResponse>(body).ok().map(|response| response.error)
+    }
+}
+
+// name이 문자열이 아닌 숫자로 오는 경우가 있음
+fn value_to_string<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = Value::deserialize(deserializer)?;
+    Ok(match value {
+        Value::String(s) => s,
+        other => other.to_string(),
+    })
+}
+
+/// `Remaining-Req` 응답 헤더 (예: `group=candles; min=1800; sec=29`)
+///
+/// min 값은 더 이상 내려오지 않을 수 있음
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemainingReq {
+    pub group: String,
+    pub min: Option<u32>,
+    pub sec: u32,
+}
+
+impl RemainingReq {
+    pub fn parse(header: &str) -> Option<Self> {
+        let mut group = None;
+        let mut min = None;
+        let mut sec = None;
+        for part in header.split(';') {
+            let (key, value) = part.trim().split_once('=')?;
+            match key.trim() {
+                "group" => group = Some(value.trim().to_string()),
+                "min" => min = value.trim().parse::<u32>().ok(),
+                "sec" => sec = value.trim().parse::<u32>().ok(),
+                _ => {}
+            }
+        }
+        Some(Self { group: group?, min, sec: sec? })
+    }
+}
+
+/// Upbit API 호출 에러
+#[derive(Debug)]
+pub enum UpbitError {
+    /// 연결 실패, 타임아웃 등 네트워크 에러
+    Transport(reqwest::Error),
+    /// 2xx가 아닌 응답
+    Http { status: u16, error: Option<UpbitErrorBody>, body: String },
+    /// 요청 수 제한 초과 (429)
+    RateLimited { remaining_req: Option<RemainingReq>, error: Option<UpbitErrorBody> },
+    /// 인증 실패 응답 (401)
+    Auth { error: Option<UpbitErrorBody> },
+    /// JWT 토큰 생성 실패 (인증 키 누락 등)
+    Jwt(String),
+    /// 응답 JSON 디코딩 실패
+    Decode(serde_json::Error),
+    /// 호가 단위, 최소 주문 금액 등 주문 규칙 위반 (요청을 보내지 않음)
+    InvalidOrder(MarketRuleError),
+}
+
+impl UpbitError {
+    /// 응답 상태, `Remaining-Req` 헤더, 본문으로 에러 생성
+    pub fn from_response(status: u16, remaining_req: Option<&str>, body: &str) -> Self {
+        let error = UpbitErrorBody::parse(body);
+        match status {
+            401 => UpbitError::Auth { error },
+            429 => UpbitError::RateLimited { remaining_req: remaining_req.and_then(RemainingReq::parse), error },
+            _ => UpbitError::Http { status, error, body: body.to_string() },
+        }
+    }
+
+    /// Upbit이 내려준 에러 이름 (예: `insufficient_funds_bid`)
+    pub fn error_name(&self) -> Option<&str> {
+        match self {
+            UpbitError::Http { error, .. } | UpbitError::RateLimited { error, .. } | UpbitError::Auth { error } => {
+                error.as_ref().map(|e| e.name.as_str())
+            }
+            _ => None,
+        }
+    }
+
+    /// 같은 요청을 다시 보내면 성공할 수 있는 에러인지 여부
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            UpbitError::Transport(_) | UpbitError::RateLimited { .. } => true,
+            UpbitError::Http { status, .. } => *status >= 500,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for UpbitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UpbitError::Transport(e) => write!(f, "Upbit request failed: {}", e),
+            UpbitError::Http { status, error: Some(error), .. } => write!(f, "Upbit API error ({}): {} - {}", status, error.name, error.message),
+            UpbitError::Http { status, error: None, body } => write!(f, "Upbit API error ({}): {}", status, body),
+            UpbitError::RateLimited { remaining_req, .. } => write!(f, "Upbit rate limit exceeded: {:?}", remaining_req),
+            UpbitError::Auth { error: Some(error) } => write!(f, "Upbit authentication failed: {} - {}", error.name, error.message),
+            UpbitError::Auth { error: None } => write!(f, "Upbit authentication failed"),
+            UpbitError::Jwt(message) => write!(f, "Failed to create jwt token: {}", message),
+            UpbitError::Decode(e) => write!(f, "Failed to decode Upbit response: {}", e),
+            UpbitError::InvalidOrder(e) => write!(f, "Invalid order: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for UpbitError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            UpbitError::Transport(e) => Some(e),
+            UpbitError::Decode(e) => Some(e),
+            UpbitError::InvalidOrder(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for UpbitError {
+    fn from(e: reqwest::Error) -> Self {
+        UpbitError::Transport(e)
+    }
+}
+
+impl From<MarketRuleError> for UpbitError {
+    fn from(e: MarketRuleError) -> Self {
+        UpbitError::InvalidOrder(e)
+    }
+}
+
+impl From<serde_json::Error> for UpbitError {
+    fn from(e: serde_json::Error) -> Self {
+        UpbitError::Decode(e)
+    }
+}
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use crate::upbit_api::utils::request_upbit_api;
+use crate::upbit_api::{client::UpbitClient, error::UpbitError};
 
 #[derive(Deserialize, Serialize, Debug)]
 pub struct Market {
@@ -27,13 +27,8 @@ pub struct MarketCaution {
 }
 
 /// 시장 정보 조회
-pub async fn get_market_info() -> Result<Vec<Market>, Box<dyn std::error::Error>> {
-    let body = request_upbit_api("/market/all", None).await;
-    if let Some(body) = body {
-        // eprintln!("{}", body);
-        let markets: Vec<Market> = serde_json::from_str(&body)?;
-        Ok(markets)
-    } else {
-        Err("Failed to get market info".into())
-    }
+pub async fn get_market_info(client: &UpbitClient) -> Result<Vec<Market>, UpbitError> {
+    let body = client.get("/market/all", None).await?;
+    let markets: Vec<Market> = serde_json::from_str(&body)?;
+    Ok(markets)
 }
\ No newline at end of file
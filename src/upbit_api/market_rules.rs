@@ -0,0 +1,223 @@
+use std::fmt;
+
+use crate::{core::signal::{Signal, TakeProfitTarget}, upbit_api::order::{OrderRequest, OrderType}};
+
+// 주문 수량 소수점 자리수
+const VOLUME_DECIMALS: i32 = 8;
+
+/// 마켓의 호가 통화
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum QuoteCurrency {
+    Krw,
+    Btc,
+    Usdt,
+}
+
+impl QuoteCurrency {
+    /// 마켓 코드 (`KRW-BTC`)의 앞부분으로 판단
+    pub fn from_market(code: &str) -> Option<Self> {
+        match code.split('-').next()? {
+            "KRW" => Some(QuoteCurrency::Krw),
+            "BTC" => Some(QuoteCurrency::Btc),
+            "USDT" => Some(QuoteCurrency::Usdt),
+            _ => None,
+        }
+    }
+}
+
+/// 주문 규칙 위반
+#[derive(Debug, Clone, PartialEq)]
+pub enum MarketRuleError {
+    /// 호가 단위에 맞지 않는 가격
+    InvalidTickSize { price: f64, tick_size: f64 },
+    /// 최소 주문 금액 미만
+    BelowMinimumOrder { amount: f64, minimum: f64 },
+    /// 0 이하이거나 소수점 8자리를 넘는 수량
+    InvalidVolume(f64),
+    /// 주문 타입에 필요한 값 (price, volume) 누락
+    MissingField(&'static str),
+}
+
+impl fmt::Display for MarketRuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MarketRuleError::InvalidTickSize { price, tick_size } => write!(f, "price {} is not a multiple of tick size {}", price, tick_size),
+            MarketRuleError::BelowMinimumOrder { amount, minimum } => write!(f, "order amount {} is below minimum {}", amount, minimum),
+            MarketRuleError::InvalidVolume(volume) => write!(f, "invalid volume {}", volume),
+            MarketRuleError::MissingField(field) => write!(f, "missing {}", field),
+        }
+    }
+}
+
+impl std::error::Error for MarketRuleError {}
+
+/// 업비트 마켓별 호가 단위와 최소 주문 금액
+///
+/// - KRW: 가격대별 호가 단위 (2,000,000 이상 1,000원 ~ 10 미만 0.01원), 최소 주문 5,000 KRW
+/// - BTC: 호가 단위 0.00000001 BTC, 최소 주문 0.00005 BTC
+/// - USDT: 가격대별 호가 단위 (10 이상 0.01 ~ 0.0001 미만 0.00000001), 최소 주문 0.5 USDT
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MarketRules {
+    pub quote: QuoteCurrency,
+    pub min_order_amount: f64,
+}
+
+impl MarketRules {
+    pub fn new(quote: QuoteCurrency) -> Self {
+        let min_order_amount = match quote {
+            QuoteCurrency::Krw => 5000.0,
+            QuoteCurrency::Btc => 0.00005,
+            QuoteCurrency::Usdt => 0.5,
+        };
+        Self { quote, min_order_amount }
+    }
+
+    /// 마켓 코드로 규칙 선택. 알 수 없는 마켓은 KRW 규칙 사용
+    pub fn for_market(code: &str) -> Self {
+        Self::new(QuoteCurrency::from_market(code).unwrap_or(QuoteCurrency::Krw))
+    }
+
+    /// 가격대의 호가 단위
+    pub fn tick_size(&self, price: f64) -> f64 {
+        match self.quote {
+            QuoteCurrency::Krw => match price {
+                p if p >= 2_000_000.0 => 1000.0,
+                p if p >= 1_000_000.0 => 500.0,
+                p if p >= 500_000.0 => 100.0,
+                p if p >= 100_000.0 => 50.0,
+                p if p >= 10_000.0 => 10.0,
+                p if p >= 1_000.0 => 5.0,
+                p if p >= 100.0 => 1.0,
+                p if p >= 10.0 => 0.1,
+                p if p >= 1.0 => 0.01,
+                p if p >= 0.1 => 0.001,
+                p if p >= 0.01 => 0.0001,
+                p if p >= 0.001 => 0.00001,
+                p if p >= 0.0001 => 0.000001,
+                p if p >= 0.00001 => 0.0000001,
+                _ => 0.00000001,
+            },
+            QuoteCurrency::Btc => 0.00000001,
+            QuoteCurrency::Usdt => match price {
+                p if p >= 10.0 => 0.01,
+                p if p >= 1.0 => 0.001,
+                p if p >= 0.1 => 0.0001,
+                p if p >= 0.01 => 0.00001,
+                p if p >= 0.001 => 0.000001,
+                p if p >= 0.0001 => 0.0000001,
+                _ => 0.00000001,
+            },
+        }
+    }
+
+    /// 가장 가까운 호가로 반올림
+    pub fn round_price(&self, price: f64) -> f64 {
+        self.align_price(price, f64::round)
+    }
+
+    /// 가격 이하의 가장 가까운 호가
+    pub fn round_price_down(&self, price: f64) -> f64 {
+        self.align_price(price, f64::floor)
+    }
+
+    /// 가격 이상의 가장 가까운 호가
+    pub fn round_price_up(&self, price: f64) -> f64 {
+        self.align_price(price, f64::ceil)
+    }
+
+    // 호가 단위 경계에서는 내림한 가격의 호가 단위가 달라질 수 있으므로 결과 가격 기준으로 한 번 더 맞춤
+    fn align_price(&self, price: f64, round: fn(f64) -> f64) -> f64 {
+        if !price.is_finite() || price <= 0.0 {
+            return price;
+        }
+        let aligned = snap(round(snap(price / self.tick_size(price), 9)) * self.tick_size(price), 8);
+        let tick_size = self.tick_size(aligned);
+        snap(round(snap(aligned / tick_size, 9)) * tick_size, 8)
+    }
+
+    /// 주문 가능한 수량 (소수점 8자리 내림)
+    pub fn round_volume(&self, volume: f64) -> f64 {
+        let scale = 10f64.powi(VOLUME_DECIMALS);
+        (snap(volume * scale, 4)).floor() / scale
+    }
+
+    /// 호가 통화 금액의 소수점 자리수 (KRW는 원 단위)
+    pub fn amount_decimals(&self) -> i32 {
+        match self.quote {
+            QuoteCurrency::Krw => 0,
+            QuoteCurrency::Btc | QuoteCurrency::Usdt => 8,
+        }
+    }
+
+    /// 주문 가능한 호가 통화 금액 (시장가 매수 총액). 호가 통화 자리수 아래는 내림
+    pub fn round_amount(&self, amount: f64) -> f64 {
+        let scale = 10f64.powi(self.amount_decimals());
+        (snap(amount * scale, 4)).floor() / scale
+    }
+
+    pub fn is_valid_price(&self, price: f64) -> bool {
+        price > 0.0 && (self.round_price(price) - price).abs() <= self.tick_size(price) * 1e-6
+    }
+
+    /// 주문 요청이 호가 단위, 최소 주문 금액 규칙을 만족하는지 확인
+    ///
+    /// 시장가/최유리 매도는 가격을 알 수 없으므로 수량만 확인함
+    pub fn validate_order(&self, request: &OrderRequest) -> Result<(), MarketRuleError> {
+        if let Some(volume) = request.volume
+            && (volume <= 0.0 || (self.round_volume(volume) - volume).abs() > 1e-12) {
+            return Err(MarketRuleError::InvalidVolume(volume));
+        }
+
+        match request.ord_type {
+            OrderType::Limit => {
+                let price = request.price.ok_or(MarketRuleError::MissingField("price"))?;
+                let volume = request.volume.ok_or(MarketRuleError::MissingField("volume"))?;
+                if !self.is_valid_price(price) {
+                    return Err(MarketRuleError::InvalidTickSize { price, tick_size: self.tick_size(price) });
+                }
+                self.check_amount(price * volume)
+            }
+            // 시장가/최유리 매수의 price는 주문 총액
+            OrderType::Price => self.check_amount(request.price.ok_or(MarketRuleError::MissingField("price"))?),
+            OrderType::Best => match request.price {
+                Some(amount) => self.check_amount(amount),
+                None => request.volume.map(|_| ()).ok_or(MarketRuleError::MissingField("volume")),
+            },
+            OrderType::Market => request.volume.map(|_| ()).ok_or(MarketRuleError::MissingField("volume")),
+        }
+    }
+
+    /// 주문 금액이 최소 주문 금액 이상인지 확인
+    pub fn check_amount(&self, amount: f64) -> Result<(), MarketRuleError> {
+        // 부동소수점 오차로 최소 금액과 같은 주문이 거절되지 않도록 약간의 여유를 둠
+        if amount < self.min_order_amount * (1.0 - 1e-9) {
+            return Err(MarketRuleError::BelowMinimumOrder { amount, minimum: self.min_order_amount });
+        }
+        Ok(())
+    }
+
+    /// 전략 신호의 가격을 호가 단위에 맞춤
+    ///
+    /// 롱 포지션 기준으로 익절가는 내림 (먼저 체결되는 방향), 스탑은 올림 (먼저 청산되는 방향)
+    pub fn align_signal(&self, signal: &Signal) -> Signal {
+        match signal {
+            Signal::Buy { reason, initial_trailing_stop, take_profit, asset_pct } => Signal::Buy {
+                reason: reason.clone(),
+                initial_trailing_stop: self.round_price_up(*initial_trailing_stop),
+                take_profit: self.round_price_down(*take_profit),
+                asset_pct: *asset_pct,
+            },
+            Signal::UpdateTrailingStop(trailing_stop) => Signal::UpdateTrailingStop(self.round_price_up(*trailing_stop)),
+            Signal::SetTakeProfitTargets(targets) => Signal::SetTakeProfitTargets(targets.iter()
+                .map(|target| TakeProfitTarget { price: self.round_price_down(target.price), pct: target.pct })
+                .collect()),
+            _ => signal.clone(),
+        }
+    }
+}
+
+// 부동소수점 오차 제거 (소수점 decimals 자리 반올림)
+fn snap(value: f64, decimals: i32) -> f64 {
+    let scale = 10f64.powi(decimals);
+    (value * scale).round() / scale
+}
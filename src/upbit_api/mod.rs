@@ -2,4 +2,9 @@ pub mod account;
 pub mod market;
 pub mod candle;
 mod utils;
-pub mod realtime;
\ No newline at end of file
+pub mod realtime;
+pub mod client;
+pub mod order;
+pub mod error;
+pub mod rate_limit;
+pub mod market_rules;
\ No newline at end of file
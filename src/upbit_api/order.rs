@@ -0,0 +1,270 @@
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use url::form_urlencoded;
+
+use crate::{upbit_api::{client::UpbitClient, error::UpbitError, market_rules::MarketRules}, utils::{str_to_f64, str_to_option_f64}};
+
+/// 주문 종류 (bid: 매수, ask: 매도)
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum OrderSide {
+    Bid,
+    Ask,
+}
+
+/// 주문 타입
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum OrderType {
+    Limit,  // 지정가 주문
+    Price,  // 시장가 주문 (매수)
+    Market, // 시장가 주문 (매도)
+    Best,   // 최유리 주문 (time_in_force 필수)
+}
+
+/// 주문 체결 조건
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeInForce {
+    Ioc, // Immediate or Cancel
+    Fok, // Fill or Kill
+}
+
+/// 주문 상태
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum OrderState {
+    Wait,   // 체결 대기
+    Watch,  // 예약 주문 대기
+    Done,   // 전체 체결 완료
+    Cancel, // 주문 취소
+}
+
+/// POST /orders 요청 파라미터
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderRequest {
+    pub market: String,
+    pub side: OrderSide,
+    pub volume: Option<f64>,
+    pub price: Option<f64>,
+    pub ord_type: OrderType,
+    pub identifier: Option<String>,
+    pub time_in_force: Option<TimeInForce>,
+}
+
+impl OrderRequest {
+    /// 지정가 주문
+    pub fn limit(market: &str, side: OrderSide, volume: f64, price: f64) -> Self {
+        Self { market: market.to_string(), side, volume: Some(volume), price: Some(price),
+            ord_type: OrderType::Limit, identifier: None, time_in_force: None }
+    }
+
+    /// 시장가 매수 (price: 매수할 총 금액)
+    pub fn market_buy(market: &str, price: f64) -> Self {
+        Self { market: market.to_string(), side: OrderSide::Bid, volume: None, price: Some(price),
+            ord_type: OrderType::Price, identifier: None, time_in_force: None }
+    }
+
+    /// 시장가 매도 (volume: 매도할 수량)
+    pub fn market_sell(market: &str, volume: f64) -> Self {
+        Self { market: market.to_string(), side: OrderSide::Ask, volume: Some(volume), price: None,
+            ord_type: OrderType::Market, identifier: None, time_in_force: None }
+    }
+
+    /// 최유리 매수 (price: 매수할 총 금액)
+    pub fn best_buy(market: &str, price: f64, time_in_force: TimeInForce) -> Self {
+        Self { market: market.to_string(), side: OrderSide::Bid, volume: None, price: Some(price),
+            ord_type: OrderType::Best, identifier: None, time_in_force: Some(time_in_force) }
+    }
+
+    /// 최유리 매도 (volume: 매도할 수량)
+    pub fn best_sell(market: &str, volume: f64, time_in_force: TimeInForce) -> Self {
+        Self { market: market.to_string(), side: OrderSide::Ask, volume: Some(volume), price: None,
+            ord_type: OrderType::Best, identifier: None, time_in_force: Some(time_in_force) }
+    }
+
+    /// 지정가 IOC/FOK 주문 등 체결 조건 지정
+    pub fn with_time_in_force(mut self, time_in_force: TimeInForce) -> Self {
+        self.time_in_force = Some(time_in_force);
+        self
+    }
+
+    /// 사용자 지정 주문 식별자
+    pub fn with_identifier(mut self, identifier: &str) -> Self {
+        self.identifier = Some(identifier.to_string());
+        self
+    }
+
+    /// 요청 파라미터 (순서 유지). query_hash와 본문 모두 이 순서를 사용
+    pub fn params(&self) -> Vec<(&'static str, String)> {
+        let mut params = vec![("market", self.market.clone()), ("side", param_str(&self.side))];
+        if let Some(volume) = self.volume {
+            params.push(("volume", volume.to_string()));
+        }
+        if let Some(price) = self.price {
+            params.push(("price", price.to_string()));
+        }
+        params.push(("ord_type", param_str(&self.ord_type)));
+        if let Some(identifier) = &self.identifier {
+            params.push(("identifier", identifier.clone()));
+        }
+        if let Some(time_in_force) = self.time_in_force {
+            params.push(("time_in_force", param_str(&time_in_force)));
+        }
+        params
+    }
+
+    /// query_hash 계산에 사용할 쿼리 스트링
+    pub fn to_query_string(&self) -> String {
+        to_query_string(&self.params())
+    }
+
+    /// 요청 본문 JSON
+    pub fn to_body(&self) -> Value {
+        let mut body = Map::new();
+        for (key, value) in self.params() {
+            body.insert(key.to_string(), Value::String(value));
+        }
+        Value::Object(body)
+    }
+}
+
+/// 주문 조회/취소 시 사용하는 주문 키
+#[derive(Debug, Clone, PartialEq)]
+pub enum OrderKey {
+    Uuid(String),
+    Identifier(String),
+}
+
+impl OrderKey {
+    fn to_query_string(&self) -> String {
+        match self {
+            OrderKey::Uuid(uuid) => to_query_string(&[("uuid", uuid.clone())]),
+            OrderKey::Identifier(identifier) => to_query_string(&[("identifier", identifier.clone())]),
+        }
+    }
+}
+
+/// 주문 체결 내역
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct OrderTrade {
+    pub market: String,
+    pub uuid: String,
+    #[serde(deserialize_with = "str_to_f64")]
+    pub price: f64,
+    #[serde(deserialize_with = "str_to_f64")]
+    pub volume: f64,
+    #[serde(deserialize_with = "str_to_f64")]
+    pub funds: f64,
+    pub side: OrderSide,
+    pub created_at: String,
+}
+
+/// 주문 정보
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Order {
+    pub uuid: String,
+    pub side: OrderSide,
+    pub ord_type: OrderType,
+    #[serde(default, deserialize_with = "str_to_option_f64")]
+    pub price: Option<f64>,
+    pub state: OrderState,
+    pub market: String,
+    pub created_at: String,
+    #[serde(default, deserialize_with = "str_to_option_f64")]
+    pub volume: Option<f64>,
+    #[serde(default, deserialize_with = "str_to_option_f64")]
+    pub remaining_volume: Option<f64>,
+    #[serde(deserialize_with = "str_to_f64")]
+    pub reserved_fee: f64,
+    #[serde(deserialize_with = "str_to_f64")]
+    pub remaining_fee: f64,
+    #[serde(deserialize_with = "str_to_f64")]
+    pub paid_fee: f64,
+    #[serde(deserialize_with = "str_to_f64")]
+    pub locked: f64,
+    #[serde(deserialize_with = "str_to_f64")]
+    pub executed_volume: f64,
+    pub trades_count: u32,
+    #[serde(default)]
+    pub time_in_force: Option<TimeInForce>,
+    #[serde(default)]
+    pub identifier: Option<String>,
+    /// GET /order 에서만 포함됨
+    #[serde(default)]
+    pub trades: Vec<OrderTrade>,
+}
+
+// 요청 파라미터 값. serde rename과 같은 문자열을 쓰도록 직렬화 결과를 그대로 사용
+// 문자열이 아닌 값은 JSON 표현을, 직렬화에 실패하면 빈 문자열을 사용
+fn param_str<T: Serialize>(value: &T) -> String {
+    match serde_json::to_value(value) {
+        Ok(Value::String(value)) => value,
+        Ok(value) => value.to_string(),
+        Err(_) => String::new(),
+    }
+}
+
+// 쿼리 스트링. 값은 URL 인코딩하고, 요청 URL과 query_hash 모두 이 문자열을 사용
+fn to_query_string(params: &[(&str, String)]) -> String {
+    params.iter()
+        .map(|(key, value)| format!("{}={}", key, form_urlencoded::byte_serialize(value.as_bytes()).collect::<String>()))
+        .collect::<Vec<String>>()
+        .join("&")
+}
+
+/// 주문하기 (POST /orders)
+///
+/// 마켓의 호가 단위, 최소 주문 금액을 만족하지 않으면 요청을 보내지 않고 `UpbitError::InvalidOrder` 반환
+pub async fn place_order(client: &UpbitClient, request: &OrderRequest) -> Result<Order, UpbitError> {
+    MarketRules::for_market(&request.market).validate_order(request)?;
+    let body = client.send(Method::POST, "/orders", Some(request.to_query_string()), Some(request.to_body())).await?;
+    let order: Order = serde_json::from_str(&body)?;
+    Ok(order)
+}
+
+/// 주문 취소 (DELETE /order)
+pub async fn cancel_order(client: &UpbitClient, key: &OrderKey) -> Result<Order, UpbitError> {
+    let body = client.send(Method::DELETE, "/order", Some(key.to_query_string()), None).await?;
+    let order: Order = serde_json::from_str(&body)?;
+    Ok(order)
+}
+
+/// 개별 주문 조회 (GET /order)
+pub async fn get_order(client: &UpbitClient, key: &OrderKey) -> Result<Order, UpbitError> {
+    let body = client.send(Method::GET, "/order", Some(key.to_query_string()), None).await?;
+    let order: Order = serde_json::from_str(&body)?;
+    Ok(order)
+}
+
+/// 체결 대기 주문 조회 (GET /orders/open)
+pub async fn get_open_orders(client: &UpbitClient, market: Option<&str>, limit: u32) -> Result<Vec<Order>, UpbitError> {
+    let mut params = Vec::new();
+    if let Some(market) = market {
+        params.push(("market", market.to_string()));
+    }
+    params.push(("limit", limit.to_string()));
+
+    let body = client.send(Method::GET, "/orders/open", Some(to_query_string(&params)), None).await?;
+    let orders: Vec<Order> = serde_json::from_str(&body)?;
+    Ok(orders)
+}
+
+/// 종료된 주문 조회 (GET /orders/closed)
+///
+/// states가 비어있으면 done, cancel 모두 조회
+pub async fn get_closed_orders(client: &UpbitClient, market: Option<&str>, states: &[OrderState], limit: u32) -> Result<Vec<Order>, UpbitError> {
+    let mut params = Vec::new();
+    if let Some(market) = market {
+        params.push(("market", market.to_string()));
+    }
+    for state in states {
+        params.push(("states[]", param_str(state)));
+    }
+    params.push(("limit", limit.to_string()));
+
+    let body = client.send(Method::GET, "/orders/closed", Some(to_query_string(&params)), None).await?;
+    let orders: Vec<Order> = serde_json::from_str(&body)?;
+    Ok(orders)
+}
@@ -0,0 +1,162 @@
+use std::{collections::HashMap, sync::{Arc, Mutex, OnceLock}, time::Duration};
+
+use reqwest::Method;
+use tokio::time::Instant;
+
+use crate::upbit_api::error::RemainingReq;
+
+/// Upbit 요청 수 제한이 적용되는 엔드포인트 그룹
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RateLimitGroup {
+    /// 시세 조회 (캔들, 시장 정보 등)
+    Quotation,
+    /// 주문 외 거래소 API (계좌, 주문 조회 등)
+    Exchange,
+    /// 주문 생성/취소
+    Order,
+}
+
+impl RateLimitGroup {
+    /// 요청 메서드와 경로로 그룹 결정
+    pub fn for_request(method: &Method, path: &str) -> Self {
+        let is_exchange_path = path.starts_with("/accounts") || path.starts_with("/order")
+            || path.starts_with("/withdraw") || path.starts_with("/deposit");
+        if !is_exchange_path {
+            return RateLimitGroup::Quotation;
+        }
+        if path.starts_with("/order") && (*method == Method::POST || *method == Method::DELETE) {
+            return RateLimitGroup::Order;
+        }
+        RateLimitGroup::Exchange
+    }
+
+    /// Upbit 문서 기준 초당 요청 수
+    pub fn default_per_sec(&self) -> f64 {
+        match self {
+            RateLimitGroup::Quotation => 10.0,
+            RateLimitGroup::Exchange => 30.0,
+            RateLimitGroup::Order => 8.0,
+        }
+    }
+}
+
+// 그룹별 토큰 버킷
+#[derive(Debug)]
+struct TokenBucket {
+    per_sec: f64,
+    tokens: f64,
+    last_refill: Instant,
+    // 서버가 남은 요청이 없다고 알려준 경우 이 시각까지 대기
+    blocked_until: Option<Instant>,
+}
+
+impl TokenBucket {
+    fn new(per_sec: f64) -> Self {
+        Self { per_sec, tokens: per_sec, last_refill: Instant::now(), blocked_until: None }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.per_sec).min(self.per_sec);
+        self.last_refill = now;
+    }
+
+    /// 토큰을 하나 사용. 사용할 수 없으면 기다려야 하는 시간을 반환
+    fn try_take(&mut self, now: Instant) -> Option<Duration> {
+        if let Some(blocked_until) = self.blocked_until {
+            if now < blocked_until {
+                return Some(blocked_until - now);
+            }
+            self.blocked_until = None;
+        }
+
+        self.refill(now);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            None
+        } else {
+            Some(Duration::from_secs_f64((1.0 - self.tokens) / self.per_sec))
+        }
+    }
+
+    fn block_for(&mut self, duration: Duration) {
+        let until = Instant::now() + duration;
+        self.tokens = 0.0;
+        self.blocked_until = Some(self.blocked_until.map_or(until, |current| current.max(until)));
+    }
+}
+
+/// 그룹별 토큰 버킷으로 Upbit REST 요청 속도를 제한
+///
+/// `Remaining-Req` 응답 헤더로 남은 요청 수를 보정하므로 여러 작업이 동시에 호출해도 429를 줄일 수 있음.
+/// 복제하면 같은 버킷을 공유함
+#[derive(Clone, Debug)]
+pub struct RateLimiter {
+    buckets: Arc<Mutex<HashMap<RateLimitGroup, TokenBucket>>>,
+}
+
+impl RateLimiter {
+    pub fn new() -> Self {
+        let buckets = [RateLimitGroup::Quotation, RateLimitGroup::Exchange, RateLimitGroup::Order]
+            .into_iter()
+            .map(|group| (group, TokenBucket::new(group.default_per_sec())))
+            .collect();
+        Self { buckets: Arc::new(Mutex::new(buckets)) }
+    }
+
+    /// base url별로 프로세스 전체에서 공유하는 제한기
+    ///
+    /// Upbit 요청 수 제한은 IP/계정 단위이므로 같은 서버를 호출하는 클라이언트는 따로 만들어도 같은 버킷을 사용해야 함
+    pub fn shared(base_url: &str) -> Self {
+        static SHARED: OnceLock<Mutex<HashMap<String, RateLimiter>>> = OnceLock::new();
+        SHARED.get_or_init(|| Mutex::new(HashMap::new()))
+            .lock().unwrap()
+            .entry(base_url.to_string())
+            .or_default()
+            .clone()
+    }
+
+    /// 그룹의 초당 요청 수 변경
+    pub fn with_limit(self, group: RateLimitGroup, per_sec: f64) -> Self {
+        self.buckets.lock().unwrap().insert(group, TokenBucket::new(per_sec));
+        self
+    }
+
+    /// 요청을 보낼 수 있을 때까지 대기
+    pub async fn acquire(&self, group: RateLimitGroup) {
+        loop {
+            let wait = self.buckets.lock().unwrap().get_mut(&group).and_then(|bucket| bucket.try_take(Instant::now()));
+            match wait {
+                Some(wait) => tokio::time::sleep(wait).await,
+                None => return,
+            }
+        }
+    }
+
+    /// 응답의 `Remaining-Req` 헤더로 남은 요청 수 보정
+    ///
+    /// 초당 남은 요청이 없으면 다음 1초 구간까지 대기
+    pub fn update(&self, group: RateLimitGroup, remaining_req: &RemainingReq) {
+        let mut buckets = self.buckets.lock().unwrap();
+        let Some(bucket) = buckets.get_mut(&group) else { return };
+        if remaining_req.sec == 0 {
+            bucket.block_for(Duration::from_secs(1));
+        } else {
+            bucket.refill(Instant::now());
+            bucket.tokens = bucket.tokens.min(remaining_req.sec as f64);
+        }
+    }
+
+    /// 429 응답을 받은 경우 해당 그룹의 요청을 duration 동안 막음
+    pub fn block(&self, group: RateLimitGroup, duration: Duration) {
+        if let Some(bucket) = self.buckets.lock().unwrap().get_mut(&group) {
+            bucket.block_for(duration);
+        }
+    }
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
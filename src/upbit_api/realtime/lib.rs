@@ -1,97 +1,456 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, time::Duration};
 
+use chrono::Utc;
 use futures_util::{SinkExt, StreamExt};
 use serde_json::{json, Value};
-use tokio::sync::mpsc;
+use tokio::{sync::mpsc, time::{interval_at, timeout, Instant, MissedTickBehavior}};
 use tokio_tungstenite::connect_async;
-use tungstenite::{client::IntoClientRequest, Message};
+use tungstenite::{client::IntoClientRequest, handshake::client::Request, http::HeaderValue, Message};
 
-use crate::core::{candle::{Candle, CandleBase}, orderbook::Orderbook, ticker::Ticker, trade::Trade};
+use crate::{core::{candle::{Candle, CandleBase}, my_asset::MyAsset, my_order::MyOrder, orderbook::Orderbook, ticker::Ticker, trade::Trade},
+upbit_api::{client::UpbitClient, realtime::record::RealtimeRecorder}};
 
 pub struct RealtimeCallback {
     pub orderbook_fn: Box<dyn FnMut(&Orderbook)>,
     pub trade_fn: Box<dyn FnMut(&Trade)>,
     pub ticker_fn: Box<dyn FnMut(&Ticker)>,
     pub candle_fn: Box<dyn FnMut(&Candle)>,
+    /// 연결이 끊겼다가 다시 연결된 경우 호출됨. 끊긴 구간의 캔들은 REST API로 다시 받아야 함
+    pub gap_fn: Box<dyn FnMut(&RealtimeGap)>,
     pub exit_fn: Box<dyn FnMut()>,
 }
 
+/// private websocket (`myOrder`, `myAsset`) 콜백
+///
+/// 종목과 관계없이 계정의 모든 주문/자산 이벤트를 하나의 콜백으로 받음
+pub struct PrivateRealtimeCallback {
+    pub my_order_fn: Box<dyn FnMut(&MyOrder)>,
+    pub my_asset_fn: Box<dyn FnMut(&MyAsset)>,
+    /// 연결이 끊겼다가 다시 연결된 경우 호출됨. 끊긴 동안의 체결/잔고는 REST API로 다시 확인해야 함
+    pub gap_fn: Box<dyn FnMut(&RealtimeGap)>,
+    pub exit_fn: Box<dyn FnMut()>,
+}
+
+// 수신한 메시지와 연결 이벤트를 콜백으로 전달
+trait RealtimeHandler {
+    fn dispatch(&mut self, text: &str);
+    fn gap(&mut self, gap: &RealtimeGap);
+    fn exit(&mut self);
+}
+
+impl RealtimeHandler for HashMap<&str, RealtimeCallback> {
+    fn dispatch(&mut self, text: &str) {
+        dispatch_message(text, self);
+    }
+
+    fn gap(&mut self, gap: &RealtimeGap) {
+        for callback in self.values_mut() {
+            (callback.gap_fn)(gap);
+        }
+    }
+
+    fn exit(&mut self) {
+        // 모든 코드에 대해 exit_fn 호출
+        for callback in self.values_mut() {
+            (callback.exit_fn)();
+        }
+    }
+}
+
+impl RealtimeHandler for PrivateRealtimeCallback {
+    fn dispatch(&mut self, text: &str) {
+        dispatch_private_message(text, self);
+    }
+
+    fn gap(&mut self, gap: &RealtimeGap) {
+        (self.gap_fn)(gap);
+    }
+
+    fn exit(&mut self) {
+        (self.exit_fn)();
+    }
+}
+
+// 연결할 websocket 엔드포인트
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Endpoint {
+    Public,
+    /// `{ws_url}/private`. 연결할 때마다 새 JWT로 인증함
+    Private,
+}
+
+/// 연결이 끊겨 데이터를 받지 못한 구간 (ms 단위 타임스탬프)
+#[derive(Debug, Clone, PartialEq)]
+pub struct RealtimeGap {
+    pub disconnected_at: i64,
+    pub reconnected_at: i64,
+    // 재연결에 성공하기까지 시도한 횟수
+    pub reconnect_attempts: u32,
+}
+
+impl RealtimeGap {
+    pub fn duration_ms(&self) -> i64 {
+        self.reconnected_at - self.disconnected_at
+    }
+}
+
+/// 실시간 연결 유지 설정
+#[derive(Debug, Clone)]
+pub struct RealtimeConfig {
+    /// ping 전송 주기. Upbit은 120초 동안 메시지가 없으면 연결을 끊음
+    pub ping_interval: Duration,
+    /// 이 시간 동안 아무 메시지(pong 포함)도 받지 못하면 끊긴 것으로 보고 재연결
+    pub idle_timeout: Duration,
+    pub connect_timeout: Duration,
+    /// 재연결 대기 시간. 실패할 때마다 2배씩 늘어나 max_backoff까지 증가
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// 연속 재연결 실패 허용 횟수. None이면 무제한
+    ///
+    /// 연결 직후 끊긴 경우 (인증 거절, 점검, 요청 수 초과 등)도 실패로 셈
+    pub max_reconnect_attempts: Option<u32>,
+    /// 데이터를 받았거나 이 시간 이상 유지된 연결이 끊기면 백오프와 실패 횟수를 초기화함
+    pub stable_session: Duration,
+    /// 지정하면 수신한 메시지를 이 경로에 기록함 (`replay_realtime_data`로 재생)
+    pub record_path: Option<String>,
+    /// 기록할 때 파일 앞부분에 함께 저장할 종목별 warm up 캔들 (`read_warm_up_candles`로 읽음)
+    pub warm_up_candles: HashMap<String, Vec<Candle>>,
+}
+
+impl RealtimeConfig {
+    pub fn new() -> Self {
+        Self {
+            ping_interval: Duration::from_secs(30),
+            idle_timeout: Duration::from_secs(90),
+            connect_timeout: Duration::from_secs(10),
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+            max_reconnect_attempts: None,
+            stable_session: Duration::from_secs(10),
+            record_path: None,
+            warm_up_candles: HashMap::new(),
+        }
+    }
+}
+
+impl Default for RealtimeConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// 재연결 사이에 유지되는 상태
+struct ListenState {
+    recorder: Option<RealtimeRecorder>,
+    // 연결이 끊긴 시각. 재연결에 성공하면 gap 이벤트로 전달하고 비움
+    disconnected_at: Option<i64>,
+    // 연속 연결 실패 횟수
+    attempts: u32,
+}
+
+// 연결 한 번이 끝난 이유
+enum SessionEnd {
+    Shutdown,
+    /// stable: 데이터를 받았거나 `stable_session` 이상 유지된 연결
+    Disconnected { stable: bool },
+}
+
 pub async fn listen_realtime_data(
+    client: &UpbitClient,
     codes: &[&str],
     shutdown_recv: &mut mpsc::Receiver<()>,
     callback_maps: &mut HashMap<&str, RealtimeCallback>,
 ) {
-    let url = "wss://api.upbit.com/websocket/v1".into_client_request().unwrap();
-    let (ws_stream, _) = connect_async(url).await.expect("Failed to connect");
-    let (mut write, mut read) = ws_stream.split();
+    listen_realtime_data_with_config(client, codes, shutdown_recv, callback_maps, &RealtimeConfig::new()).await;
+}
+
+/// 실시간 데이터 수신
+///
+/// 연결이 끊기면 지수 백오프로 재연결하고 같은 구독 요청을 다시 보냄.
+/// 재연결에 성공하면 각 종목의 gap_fn을 호출하고, 종료 신호를 받거나 재연결 허용 횟수를 넘기면 exit_fn을 호출하고 끝냄
+pub async fn listen_realtime_data_with_config(
+    client: &UpbitClient,
+    codes: &[&str],
+    shutdown_recv: &mut mpsc::Receiver<()>,
+    callback_maps: &mut HashMap<&str, RealtimeCallback>,
+    config: &RealtimeConfig,
+) {
+    listen(client, Endpoint::Public, &subscription_request(codes), shutdown_recv, callback_maps, config).await;
+}
+
+/// 내 주문/체결 (`myOrder`)과 자산 (`myAsset`) 실시간 수신
+///
+/// private websocket 엔드포인트에 Bearer JWT로 인증해서 연결함. codes가 비어 있으면 모든 마켓의 주문을 받음.
+/// 재연결과 종료 처리는 `listen_realtime_data_with_config`와 같으며, 인증 키가 없으면 바로 exit_fn을 호출하고 끝냄
+pub async fn listen_private_data(
+    client: &UpbitClient,
+    codes: &[&str],
+    shutdown_recv: &mut mpsc::Receiver<()>,
+    callback: &mut PrivateRealtimeCallback,
+    config: &RealtimeConfig,
+) {
+    if client.credentials().is_none() {
+        eprintln!("인증 키가 없어 private websocket에 연결할 수 없습니다.");
+        (callback.exit_fn)();
+        return;
+    }
+    listen(client, Endpoint::Private, &private_subscription_request(codes), shutdown_recv, callback, config).await;
+}
+
+async fn listen<H: RealtimeHandler>(
+    client: &UpbitClient,
+    endpoint: Endpoint,
+    request: &Value,
+    shutdown_recv: &mut mpsc::Receiver<()>,
+    handler: &mut H,
+    config: &RealtimeConfig,
+) {
+    let mut backoff = config.initial_backoff;
+    let mut state = ListenState {
+        recorder: config.record_path.as_ref().and_then(|path| {
+            RealtimeRecorder::create(path)
+                .inspect_err(|e| eprintln!("기록 파일 생성 실패 ({}): {}", path, e))
+                .ok()
+        }),
+        disconnected_at: None,
+        attempts: 0,
+    };
+    if let Some(recorder) = state.recorder.as_mut() {
+        let received_at = Utc::now().timestamp_millis();
+        for (code, candles) in &config.warm_up_candles {
+            if let Err(e) = recorder.record_warm_up(received_at, code, candles) {
+                eprintln!("warm up 캔들 기록 실패 ({}): {}", code, e);
+            }
+        }
+    }
 
-    let request = json!([
-        {"ticket": "unique_ticket"},
+    loop {
+        match run_session(client, endpoint, request, shutdown_recv, handler, config, &mut state).await {
+            Ok(SessionEnd::Shutdown) => break,
+            Ok(SessionEnd::Disconnected { stable: true }) => {
+                // 정상적으로 유지되던 연결이 끊긴 경우 백오프 초기화
+                backoff = config.initial_backoff;
+                state.attempts = 0;
+                state.disconnected_at.get_or_insert(Utc::now().timestamp_millis());
+                eprintln!("WebSocket 연결이 끊어져 재연결합니다.");
+            }
+            Ok(SessionEnd::Disconnected { stable: false }) => {
+                state.disconnected_at.get_or_insert(Utc::now().timestamp_millis());
+                state.attempts += 1;
+                eprintln!("WebSocket 연결이 바로 끊어졌습니다 ({}회).", state.attempts);
+            }
+            Err(e) => {
+                state.disconnected_at.get_or_insert(Utc::now().timestamp_millis());
+                state.attempts += 1;
+                eprintln!("WebSocket 연결 실패 ({}회): {}", state.attempts, e);
+            }
+        }
+        if config.max_reconnect_attempts.is_some_and(|max| state.attempts > max) {
+            eprintln!("재연결 허용 횟수를 초과하여 종료합니다.");
+            break;
+        }
+
+        tokio::select! {
+            _ = tokio::time::sleep(backoff) => {}
+            _ = shutdown_recv.recv() => {
+                println!("종료 신호를 수신하여 메인 루프를 중단합니다.");
+                break;
+            }
+        }
+        backoff = (backoff * 2).min(config.max_backoff);
+    }
+
+    if let Some(recorder) = state.recorder {
+        let count = recorder.count;
+        match recorder.finish() {
+            Ok(()) => println!("실시간 메시지 {}개 기록 완료", count),
+            Err(e) => eprintln!("기록 파일 저장 실패: {}", e),
+        }
+    }
+
+    handler.exit();
+}
+
+fn subscription_request(codes: &[&str]) -> Value {
+    json!([
+        {"ticket": uuid::Uuid::new_v4().to_string()},
         {"type": "trade", "codes": codes, "is_only_realtime": true},
         {"type": "orderbook", "codes": codes, "is_only_realtime": true},
         {"type": "ticker", "codes": codes, "is_only_realtime": true},
         {"type": "candle.1m", "codes": codes, "is_only_realtime": true},
         {"format": "SIMPLE"}
-    ]);
+    ])
+}
+
+/// `myOrder`, `myAsset` 구독 요청. codes가 비어 있으면 모든 마켓의 주문
+pub fn private_subscription_request(codes: &[&str]) -> Value {
+    let my_order = if codes.is_empty() { json!({"type": "myOrder"}) } else { json!({"type": "myOrder", "codes": codes}) };
+    json!([
+        {"ticket": uuid::Uuid::new_v4().to_string()},
+        my_order,
+        {"type": "myAsset"},
+        {"format": "SIMPLE"}
+    ])
+}
+
+/// private websocket 주소
+pub fn private_ws_url(client: &UpbitClient) -> String {
+    format!("{}/private", client.ws_url.trim_end_matches('/'))
+}
+
+// 엔드포인트별 연결 요청. private은 인증 헤더를 붙임
+fn connect_request(client: &UpbitClient, endpoint: Endpoint) -> Result<Request, Box<dyn std::error::Error>> {
+    match endpoint {
+        Endpoint::Public => Ok(client.ws_url.as_str().into_client_request()?),
+        Endpoint::Private => {
+            let mut request = private_ws_url(client).into_client_request()?;
+            let token = client.authorization_token(&None).ok_or("missing credentials")?;
+            request.headers_mut().insert("Authorization", HeaderValue::from_str(&token)?);
+            Ok(request)
+        }
+    }
+}
+
+/// 연결 후 구독 요청을 보내고 연결이 끊기거나 종료 신호를 받을 때까지 메시지를 처리
+async fn run_session<H: RealtimeHandler>(
+    client: &UpbitClient,
+    endpoint: Endpoint,
+    request: &Value,
+    shutdown_recv: &mut mpsc::Receiver<()>,
+    handler: &mut H,
+    config: &RealtimeConfig,
+    state: &mut ListenState,
+) -> Result<SessionEnd, Box<dyn std::error::Error>> {
+    let url = connect_request(client, endpoint)?;
+    let (ws_stream, _) = timeout(config.connect_timeout, connect_async(url)).await
+        .map_err(|_| "connect timeout")??;
+    let (mut write, mut read) = ws_stream.split();
+    write.send(Message::Text(request.to_string().into())).await?;
+
+    if let Some(disconnected_at) = state.disconnected_at.take() {
+        let gap = RealtimeGap {
+            disconnected_at,
+            reconnected_at: Utc::now().timestamp_millis(),
+            reconnect_attempts: state.attempts + 1,
+        };
+        println!("WebSocket 재연결 완료 - {}ms 동안 데이터 누락", gap.duration_ms());
+        handler.gap(&gap);
+    }
+
+    let mut ping_interval = interval_at(Instant::now() + config.ping_interval, config.ping_interval);
+    ping_interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let mut last_received = Instant::now();
+    let connected_at = Instant::now();
+    let mut received_data = false;
+    let disconnected = |received_data: bool| SessionEnd::Disconnected { stable: received_data || connected_at.elapsed() >= config.stable_session };
 
-    write.send(Message::Text(request.to_string().into())).await.unwrap();
-    
     loop {
         tokio::select! {
-            Some(msg) = read.next() => {
-                if let Ok(msg) = msg {
-                    if let Ok(text) = msg.to_text() {
-                        let value: Value = serde_json::from_str(&text).unwrap();
-                        let code = value["cd"].as_str().unwrap();
-                        
-                        if let Some(callback) = callback_maps.get_mut(code) {
-                            if value["ty"] == "orderbook" {
-                                let orderbook: Orderbook = serde_json::from_str(&text).unwrap();
-                                (callback.orderbook_fn)(&orderbook);
-                            } else if value["ty"] == "trade" {
-                                let trade: Trade = serde_json::from_str(&text).unwrap();
-                                (callback.trade_fn)(&trade);
-                            } else if value["ty"] == "ticker" {
-                                let ticker: Ticker = serde_json::from_str(&text).unwrap();
-                                (callback.ticker_fn)(&ticker);
-                            } else if value["ty"] == "candle.1m" {
-                                let candle = Candle {
-                                    base: CandleBase {
-                                        market: value["cd"].as_str().unwrap().to_string(),
-                                        candle_date_time_utc: value["cdttmu"].as_str().unwrap().to_string(),
-                                        candle_date_time_kst: value["cdttmk"].as_str().unwrap().to_string(),
-                                        opening_price: value["op"].as_f64().unwrap(),
-                                        high_price: value["hp"].as_f64().unwrap(),
-                                        low_price: value["lp"].as_f64().unwrap(),
-                                        trade_price: value["tp"].as_f64().unwrap(),
-                                        timestamp: value["tms"].as_u64().unwrap(),
-                                        candle_acc_trade_price: value["catp"].as_f64().unwrap(),
-                                        candle_acc_trade_volume: value["catv"].as_f64().unwrap(),
-                                    }
-                                };
-
-                                (callback.candle_fn)(&candle);
+            msg = read.next() => {
+                let msg = match msg {
+                    Some(Ok(msg)) => msg,
+                    Some(Err(e)) => {
+                        eprintln!("WebSocket 수신 오류: {}", e);
+                        return Ok(disconnected(received_data));
+                    }
+                    None => return Ok(disconnected(received_data)),
+                };
+                last_received = Instant::now();
+                match msg {
+                    Message::Text(_) | Message::Binary(_) => {
+                        if let Ok(text) = msg.to_text() {
+                            if let Some(recorder) = state.recorder.as_mut()
+                                && let Err(e) = recorder.record(Utc::now().timestamp_millis(), text) {
+                                eprintln!("실시간 메시지 기록 실패: {}", e);
                             }
+                            // 연결 직후의 에러 응답 (인증 실패 등)은 데이터로 보지 않음
+                            received_data = received_data || !serde_json::from_str::<Value>(text).is_ok_and(|value| value.get("error").is_some());
+                            handler.dispatch(text);
                         }
                     }
+                    Message::Close(_) => return Ok(disconnected(received_data)),
+                    _ => {}
+                }
+            }
+
+            _ = ping_interval.tick() => {
+                if last_received.elapsed() >= config.idle_timeout {
+                    eprintln!("{:?} 동안 메시지를 받지 못했습니다.", config.idle_timeout);
+                    return Ok(disconnected(received_data));
+                }
+                if write.send(Message::Ping(Vec::new().into())).await.is_err() {
+                    return Ok(disconnected(received_data));
                 }
             }
 
             _ = shutdown_recv.recv() => {
                 println!("종료 신호를 수신하여 메인 루프를 중단합니다.");
-                break; // select! 와 loop 를 모두 빠져나감
+                // WebSocket 연결 종료
+                if let Err(e) = write.close().await {
+                    eprintln!("WebSocket 연결 종료 중 오류 발생: {}", e);
+                }
+                println!("WebSocket 연결이 정상적으로 종료되었습니다.");
+                return Ok(SessionEnd::Shutdown);
             }
         }
     }
+}
 
-    // 모든 코드에 대해 exit_fn 호출
-    for callback in callback_maps.values_mut() {
-        (callback.exit_fn)();
+/// SIMPLE 포맷 메시지 하나를 종목별 콜백으로 전달
+///
+/// 상태 응답 등 처리할 수 없는 메시지는 무시함
+pub fn dispatch_message(text: &str, callback_maps: &mut HashMap<&str, RealtimeCallback>) {
+    let Ok(value) = serde_json::from_str::<Value>(text) else { return };
+    let Some(code) = value["cd"].as_str() else { return };
+    let Some(callback) = callback_maps.get_mut(code) else { return };
+
+    let result = match value["ty"].as_str() {
+        Some("orderbook") => serde_json::from_value::<Orderbook>(value.clone()).map(|orderbook| (callback.orderbook_fn)(&orderbook)),
+        Some("trade") => serde_json::from_value::<Trade>(value.clone()).map(|trade| (callback.trade_fn)(&trade)),
+        Some("ticker") => serde_json::from_value::<Ticker>(value.clone()).map(|ticker| (callback.ticker_fn)(&ticker)),
+        Some("candle.1m") => {
+            if let Some(candle) = parse_candle(&value) {
+                (callback.candle_fn)(&candle);
+            }
+            Ok(())
+        }
+        _ => Ok(()),
+    };
+
+    if let Err(e) = result {
+        eprintln!("실시간 메시지 파싱 실패: {}", e);
     }
-    
-    // WebSocket 연결 종료
-    if let Err(e) = write.close().await {
-        eprintln!("WebSocket 연결 종료 중 오류 발생: {}", e);
+}
+
+/// private websocket SIMPLE 포맷 메시지 하나를 콜백으로 전달
+///
+/// 상태 응답 등 처리할 수 없는 메시지는 무시함
+pub fn dispatch_private_message(text: &str, callback: &mut PrivateRealtimeCallback) {
+    let Ok(value) = serde_json::from_str::<Value>(text) else { return };
+    let result = match value["ty"].as_str() {
+        Some("myOrder") => serde_json::from_value::<MyOrder>(value).map(|order| (callback.my_order_fn)(&order)),
+        Some("myAsset") => serde_json::from_value::<MyAsset>(value).map(|asset| (callback.my_asset_fn)(&asset)),
+        _ => Ok(()),
+    };
+
+    if let Err(e) = result {
+        eprintln!("실시간 메시지 파싱 실패: {}", e);
     }
-    println!("WebSocket 연결이 정상적으로 종료되었습니다.");
-}
\ No newline at end of file
+}
+
+fn parse_candle(value: &Value) -> Option<Candle> {
+    Some(Candle {
+        base: CandleBase {
+            market: value["cd"].as_str()?.to_string(),
+            candle_date_time_utc: value["cdttmu"].as_str()?.to_string(),
+            candle_date_time_kst: value["cdttmk"].as_str()?.to_string(),
+            opening_price: value["op"].as_f64()?,
+            high_price: value["hp"].as_f64()?,
+            low_price: value["lp"].as_f64()?,
+            trade_price: value["tp"].as_f64()?,
+            timestamp: value["tms"].as_u64()?,
+            candle_acc_trade_price: value["catp"].as_f64()?,
+            candle_acc_trade_volume: value["catv"].as_f64()?,
+        }
+    })
+}
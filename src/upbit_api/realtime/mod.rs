@@ -1,4 +1,5 @@
 pub mod orderbook;
 pub mod ticker;
 pub mod trade;
-pub mod lib;
\ No newline at end of file
+pub mod lib;
+pub mod record;
\ No newline at end of file
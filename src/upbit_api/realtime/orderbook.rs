@@ -1,11 +1,11 @@
-use crate::core::orderbook::Orderbook;
+use crate::{core::orderbook::Orderbook, upbit_api::client::UpbitClient};
 use futures_util::{SinkExt, StreamExt};
 use serde_json::json;
 use tokio_tungstenite::{connect_async, tungstenite::Message};
 use tungstenite::client::IntoClientRequest;
 
-pub async fn get_orderbook(code: &str) {
-    let url = "wss://api.upbit.com/websocket/v1".into_client_request().unwrap();
+pub async fn get_orderbook(client: &UpbitClient, code: &str) {
+    let url = client.ws_url.as_str().into_client_request().unwrap();
     let (ws_stream, _) = connect_async(url).await.expect("Failed to connect");
     let (mut write, mut read) = ws_stream.split();
 
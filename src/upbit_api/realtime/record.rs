@@ -0,0 +1,138 @@
+use std::{collections::HashMap, fs::File, io::{BufRead, BufReader, BufWriter, Write}, path::Path, time::Duration};
+
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::{core::candle::Candle, upbit_api::realtime::lib::{dispatch_message, RealtimeCallback}};
+
+// 기록 대상 메시지 타입
+const RECORDED_TYPES: [&str; 4] = ["trade", "orderbook", "ticker", "candle.1m"];
+// 기록 시작 전 전략 warm up에 사용한 캔들. upbit 메시지 타입과 겹치지 않는 이름을 사용
+const WARM_UP_TYPE: &str = "warmUp";
+
+/// 기록된 실시간 메시지 한 줄
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RecordedMessage {
+    /// 수신 시각 (ms)
+    pub received_at: i64,
+    /// 수신한 원본 메시지
+    pub message: String,
+}
+
+/// 실시간 메시지를 gzip으로 압축한 줄 단위 JSON 파일로 기록
+pub struct RealtimeRecorder {
+    writer: GzEncoder<BufWriter<File>>,
+    pub count: usize,
+}
+
+impl RealtimeRecorder {
+    pub fn create<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        let file = File::create(path)?;
+        Ok(Self {
+            writer: GzEncoder::new(BufWriter::new(file), Compression::default()),
+            count: 0,
+        })
+    }
+
+    /// 체결/호가/현재가/1분 캔들 메시지만 기록하고 나머지는 무시. 기록했으면 true
+    pub fn record(&mut self, received_at: i64, message: &str) -> std::io::Result<bool> {
+        let is_recorded_type = serde_json::from_str::<Value>(message).ok()
+            .and_then(|value| value["ty"].as_str().map(|ty| RECORDED_TYPES.contains(&ty)))
+            .unwrap_or(false);
+        if !is_recorded_type {
+            return Ok(false);
+        }
+
+        let line = serde_json::to_string(&RecordedMessage { received_at, message: message.to_string() })?;
+        self.writer.write_all(line.as_bytes())?;
+        self.writer.write_all(b"\n")?;
+        self.count += 1;
+        Ok(true)
+    }
+
+    /// 전략 warm up에 사용한 종목의 과거 캔들을 기록 (candles는 오래된 순서)
+    ///
+    /// 재생할 때는 메시지로 전달하지 않고 `read_warm_up_candles`로 읽음. count에 포함하지 않음
+    pub fn record_warm_up(&mut self, received_at: i64, code: &str, candles: &[Candle]) -> std::io::Result<()> {
+        let message = json!({"ty": WARM_UP_TYPE, "cd": code, "candles": candles}).to_string();
+        let line = serde_json::to_string(&RecordedMessage { received_at, message })?;
+        self.writer.write_all(line.as_bytes())?;
+        self.writer.write_all(b"\n")
+    }
+
+    /// 압축 스트림을 마무리하고 파일을 닫음. 호출하지 않으면 파일 끝이 잘릴 수 있음
+    pub fn finish(self) -> std::io::Result<()> {
+        self.writer.finish()?.flush()
+    }
+}
+
+/// 기록 파일을 한 줄씩 읽음
+pub fn read_recording<P: AsRef<Path>>(path: P) -> std::io::Result<impl Iterator<Item = std::io::Result<RecordedMessage>>> {
+    let file = File::open(path)?;
+    let reader = BufReader::new(GzDecoder::new(file));
+    Ok(reader.lines()
+        .filter(|line| !matches!(line, Ok(line) if line.trim().is_empty()))
+        .map(|line| line.and_then(|line| serde_json::from_str::<RecordedMessage>(&line).map_err(std::io::Error::from))))
+}
+
+/// 기록 파일에 저장된 종목별 warm up 캔들
+pub fn read_warm_up_candles<P: AsRef<Path>>(path: P) -> std::io::Result<HashMap<String, Vec<Candle>>> {
+    let mut warm_up_candles = HashMap::new();
+    for recorded in read_recording(path)? {
+        let Some(value) = warm_up_message(&recorded?.message) else { continue };
+        let Some(code) = value["cd"].as_str() else { continue };
+        let candles = serde_json::from_value::<Vec<Candle>>(value["candles"].clone())?;
+        warm_up_candles.insert(code.to_string(), candles);
+    }
+    Ok(warm_up_candles)
+}
+
+// warm up 캔들 기록이면 파싱한 메시지를 반환
+fn warm_up_message(message: &str) -> Option<Value> {
+    serde_json::from_str::<Value>(message).ok()
+        .filter(|value| value["ty"].as_str() == Some(WARM_UP_TYPE))
+}
+
+/// 재생 속도
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReplaySpeed {
+    /// 대기 없이 최대한 빠르게
+    MaxSpeed,
+    /// 기록된 수신 간격대로
+    RealTime,
+}
+
+/// 기록 파일의 메시지를 `listen_realtime_data`와 같은 콜백으로 재생
+///
+/// warm up 캔들 기록은 건너뛰고, 모든 메시지를 재생하면 exit_fn을 호출하고 재생한 메시지 수를 반환
+pub async fn replay_realtime_data<P: AsRef<Path>>(
+    path: P,
+    speed: ReplaySpeed,
+    callback_maps: &mut HashMap<&str, RealtimeCallback>,
+) -> std::io::Result<usize> {
+    let mut count = 0;
+    let mut previous_received_at: Option<i64> = None;
+
+    for recorded in read_recording(path)? {
+        let recorded = recorded?;
+        if warm_up_message(&recorded.message).is_some() {
+            continue;
+        }
+        if speed == ReplaySpeed::RealTime {
+            if let Some(previous) = previous_received_at {
+                let wait = (recorded.received_at - previous).max(0) as u64;
+                tokio::time::sleep(Duration::from_millis(wait)).await;
+            }
+            previous_received_at = Some(recorded.received_at);
+        }
+
+        dispatch_message(&recorded.message, callback_maps);
+        count += 1;
+    }
+
+    for callback in callback_maps.values_mut() {
+        (callback.exit_fn)();
+    }
+    Ok(count)
+}
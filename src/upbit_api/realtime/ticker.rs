@@ -1,4 +1,4 @@
-use crate::{core::ticker::Ticker};
+use crate::{core::ticker::Ticker, upbit_api::client::UpbitClient};
 use futures_util::{SinkExt, StreamExt};
 use serde_json::json;
 use tokio_tungstenite::{connect_async, tungstenite::Message};
@@ -19,8 +19,8 @@ pub struct VirtualTrader {
     pub initial_cash: f64,
 }
 
-pub async fn get_ticker(code: &str) {
-    let url = "wss://api.upbit.com/websocket/v1".into_client_request().unwrap();
+pub async fn get_ticker(client: &UpbitClient, code: &str) {
+    let url = client.ws_url.as_str().into_client_request().unwrap();
     let (ws_stream, _) = connect_async(url).await.expect("Failed to connect");
     let (mut write, mut read) = ws_stream.split();
 
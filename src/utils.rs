@@ -14,4 +14,12 @@ where
 {
     let s: String = Deserialize::deserialize(deserializer)?;
     s.parse::<u32>().map_err(serde::de::Error::custom)
-}
\ No newline at end of file
+}
+
+pub fn str_to_option_f64<'de, D>(deserializer: D) -> Result<Option<f64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s: Option<String> = Deserialize::deserialize(deserializer)?;
+    s.map(|s| s.parse::<f64>().map_err(serde::de::Error::custom)).transpose()
+}
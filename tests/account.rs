@@ -1,7 +1,7 @@
-use ctb::upbit_api::account::check_my_account;
+use ctb::upbit_api::{account::check_my_account, client::UpbitClient};
 
 #[tokio::test]
 async fn test_check_my_account() {
-    let result = check_my_account().await;
+    let result = check_my_account(&UpbitClient::from_env()).await;
     eprintln!("{:?}", result);
 }
\ No newline at end of file
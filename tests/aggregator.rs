@@ -0,0 +1,129 @@
+use ctb::core::{aggregator::{BarInterval, InvalidBarInterval, TradeAggregator}, candle::CandleTrait, trade::{AskBid, Change, StreamType, Trade}};
+
+// 2024-01-01T00:00:00Z
+const BASE_TIMESTAMP: i64 = 1704067200000;
+
+fn get_trade(offset_ms: i64, price: f64, volume: f64, ask_bid: AskBid) -> Trade {
+    Trade {
+        trade_type: "trade".to_string(),
+        code: "KRW-BTC".to_string(),
+        trade_price: price,
+        trade_volume: volume,
+        ask_bid,
+        prev_closing_price: 0.0,
+        change: Change::Even,
+        change_price: 0.0,
+        trade_date: "".to_string(),
+        trade_time: "".to_string(),
+        trade_timestamp: BASE_TIMESTAMP + offset_ms,
+        timestamp: BASE_TIMESTAMP + offset_ms,
+        sequential_id: offset_ms,
+        best_ask_price: 0.0,
+        best_ask_size: 0.0,
+        best_bid_price: 0.0,
+        best_bid_size: 0.0,
+        stream_type: StreamType::Realtime,
+    }
+}
+
+#[test]
+fn test_minute_bars() {
+    let mut aggregator = TradeAggregator::new(BarInterval::Minutes(1)).unwrap();
+    assert!(aggregator.push(&get_trade(1_000, 100.0, 1.0, AskBid::Bid)).is_none());
+    assert!(aggregator.push(&get_trade(20_000, 105.0, 2.0, AskBid::Ask)).is_none());
+    assert!(aggregator.push(&get_trade(59_999, 98.0, 0.5, AskBid::Bid)).is_none());
+    assert_eq!(aggregator.current().unwrap().trade_count, 3);
+
+    let bar = aggregator.push(&get_trade(60_000, 101.0, 1.0, AskBid::Bid)).unwrap();
+    assert_eq!(bar.open_timestamp, BASE_TIMESTAMP);
+    assert_eq!(bar.close_timestamp, BASE_TIMESTAMP + 59_999);
+    assert_eq!((bar.opening_price, bar.high_price, bar.low_price, bar.trade_price), (100.0, 105.0, 98.0, 98.0));
+    assert_eq!(bar.volume, 3.5);
+    assert_eq!(bar.value, 100.0 + 210.0 + 49.0);
+    assert_eq!(bar.buy_volume, 1.5);
+    assert_eq!(bar.sell_volume, 2.0);
+    assert_eq!(bar.delta(), -0.5);
+    assert!(bar.trades.is_empty());
+
+    // 진행 중인 봉은 새 구간에서 시작
+    let current = aggregator.current().unwrap();
+    assert_eq!(current.open_timestamp, BASE_TIMESTAMP + 60_000);
+    assert_eq!(current.opening_price, 101.0);
+}
+
+#[test]
+fn test_bar_to_candle_uses_utc_and_kst() {
+    let mut aggregator = TradeAggregator::new(BarInterval::Minutes(5)).unwrap();
+    aggregator.push(&get_trade(7 * 60_000 + 30_000, 100.0, 1.0, AskBid::Bid));
+    let candle = aggregator.flush().unwrap().to_candle();
+
+    assert_eq!(candle.get_market(), "KRW-BTC");
+    assert_eq!(candle.get_candle_date_time_utc(), "2024-01-01T00:05:00");
+    assert_eq!(candle.get_candle_date_time_kst(), "2024-01-01T09:05:00");
+    assert_eq!(candle.get_candle_acc_trade_volume(), 1.0);
+    assert!(aggregator.current().is_none());
+}
+
+#[test]
+fn test_second_bars_skip_empty_intervals() {
+    let mut aggregator = TradeAggregator::new(BarInterval::Seconds(1)).unwrap().with_trades();
+    aggregator.push(&get_trade(100, 100.0, 1.0, AskBid::Bid));
+    aggregator.push(&get_trade(900, 101.0, 1.0, AskBid::Bid));
+
+    let bar = aggregator.push(&get_trade(5_200, 102.0, 1.0, AskBid::Ask)).unwrap();
+    assert_eq!(bar.trades.len(), 2);
+    assert_eq!(aggregator.current().unwrap().open_timestamp, BASE_TIMESTAMP + 5_000);
+}
+
+#[test]
+fn test_late_trade_is_merged_into_current_bar() {
+    let mut aggregator = TradeAggregator::new(BarInterval::Minutes(1)).unwrap();
+    aggregator.push(&get_trade(61_000, 100.0, 1.0, AskBid::Bid));
+    assert!(aggregator.push(&get_trade(59_000, 99.0, 1.0, AskBid::Ask)).is_none());
+
+    let current = aggregator.current().unwrap();
+    assert_eq!(current.trade_count, 2);
+    assert_eq!(current.open_timestamp, BASE_TIMESTAMP + 60_000);
+}
+
+#[test]
+fn test_close_if_expired() {
+    let mut aggregator = TradeAggregator::new(BarInterval::Minutes(1)).unwrap();
+    aggregator.push(&get_trade(1_000, 100.0, 1.0, AskBid::Bid));
+    assert!(aggregator.close_if_expired(BASE_TIMESTAMP + 59_000).is_none());
+    assert!(aggregator.close_if_expired(BASE_TIMESTAMP + 60_000).is_some());
+    assert!(aggregator.current().is_none());
+
+    // 체결 수 기준 봉은 시간으로 마감하지 않음
+    let mut aggregator = TradeAggregator::new(BarInterval::Ticks(10)).unwrap();
+    aggregator.push(&get_trade(1_000, 100.0, 1.0, AskBid::Bid));
+    assert!(aggregator.close_if_expired(BASE_TIMESTAMP + 3_600_000).is_none());
+}
+
+#[test]
+fn test_tick_volume_and_value_bars() {
+    let mut aggregator = TradeAggregator::new(BarInterval::Ticks(2)).unwrap();
+    assert!(aggregator.push(&get_trade(0, 100.0, 1.0, AskBid::Bid)).is_none());
+    let bar = aggregator.push(&get_trade(10, 101.0, 1.0, AskBid::Bid)).unwrap();
+    assert_eq!(bar.trade_count, 2);
+    assert!(aggregator.current().is_none());
+
+    let mut aggregator = TradeAggregator::new(BarInterval::Volume(3.0)).unwrap();
+    assert!(aggregator.push(&get_trade(0, 100.0, 2.0, AskBid::Bid)).is_none());
+    // 기준을 넘긴 체결도 나누지 않고 포함
+    let bar = aggregator.push(&get_trade(10, 100.0, 2.0, AskBid::Ask)).unwrap();
+    assert_eq!(bar.volume, 4.0);
+    assert_eq!(bar.open_timestamp, BASE_TIMESTAMP);
+
+    let mut aggregator = TradeAggregator::new(BarInterval::Value(1000.0)).unwrap();
+    assert!(aggregator.push(&get_trade(0, 100.0, 5.0, AskBid::Bid)).is_none());
+    let bar = aggregator.push(&get_trade(10, 100.0, 5.0, AskBid::Bid)).unwrap();
+    assert_eq!(bar.value, 1000.0);
+}
+
+#[test]
+fn test_invalid_intervals() {
+    for interval in [BarInterval::Seconds(0), BarInterval::Minutes(0), BarInterval::Ticks(0), BarInterval::Volume(0.0), BarInterval::Value(-1.0), BarInterval::Volume(f64::NAN)] {
+        assert_eq!(TradeAggregator::new(interval).err().map(|e| e.to_string()), Some(InvalidBarInterval(interval).to_string()));
+    }
+}
@@ -1,10 +1,10 @@
 use std::collections::HashSet;
 
-use ctb::{backtest::{fetch::{fetch_n_day_candles, fetch_n_minute_candles}, lib::{BacktestParams, BacktesterState}}, core::signal::Signal};
+use ctb::{backtest::{fetch::{fetch_n_day_candles, fetch_n_minute_candles}, lib::{BacktestParams, BacktesterState}}, core::signal::Signal, upbit_api::client::UpbitClient};
 
 #[tokio::test]
 async fn test_fetch_n_minute_candles() {
-    let candles = fetch_n_minute_candles("KRW-BTC", 400, "2024-01-01T00:00:00Z", 5).await.unwrap();
+    let candles = fetch_n_minute_candles(&UpbitClient::from_env(), "KRW-BTC", 400, "2024-01-01T00:00:00Z", 5).await.unwrap();
     let mut set = HashSet::new();
     for c in &candles {
         set.insert(c.get_candle_date_time_utc());
@@ -17,7 +17,7 @@ async fn test_fetch_n_minute_candles() {
 
 #[tokio::test]
 async fn test_fetch_n_day_candles() {
-    let candles = fetch_n_day_candles("KRW-BTC", 400, "2024-01-01T00:00:00Z").await.unwrap();
+    let candles = fetch_n_day_candles(&UpbitClient::from_env(), "KRW-BTC", 400, "2024-01-01T00:00:00Z").await.unwrap();
     let mut set = HashSet::new();
     for c in &candles {
         set.insert(c.get_candle_date_time_utc());
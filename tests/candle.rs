@@ -1,22 +1,53 @@
-use ctb::upbit_api::candle::{get_candle_days, get_candle_minutes, get_candle_seconds};
+mod mock_server;
+
+use ctb::{core::candle::CandleTrait, upbit_api::{candle::{get_candle_days, get_candle_minutes, get_candle_seconds}, client::UpbitClient}};
+use mock_server::{spawn_mock_server, MockResponse};
 
 #[tokio::test]
 async fn test_get_candle_seconds() {
-    let result = get_candle_seconds("KRW-BTC", None, 5).await;
+    let result = get_candle_seconds(&UpbitClient::from_env(), "KRW-BTC", None, 5).await;
     // eprintln!("{:?}", result);
     assert!(result.is_ok());
 }
 
 #[tokio::test]
 async fn test_get_candle_minutes() {
-    let result = get_candle_minutes("KRW-BTC", None, 5, 1).await;
+    let result = get_candle_minutes(&UpbitClient::from_env(), "KRW-BTC", None, 5, 1).await;
     // eprintln!("{:?}", result);
     assert!(result.is_ok());
 }
 
 #[tokio::test]
 async fn test_get_candle_days() {
-    let result = get_candle_days("KRW-BTC", None, 5).await;
+    let result = get_candle_days(&UpbitClient::from_env(), "KRW-BTC", None, 5).await;
     // eprintln!("{:?}", result);
     assert!(result.is_ok());
-}
\ No newline at end of file
+}
+
+#[tokio::test]
+async fn test_get_candle_minutes_from_stub_server() {
+    let body = r#"[{
+        "market": "KRW-BTC",
+        "candle_date_time_utc": "2024-01-01T00:05:00",
+        "candle_date_time_kst": "2024-01-01T09:05:00",
+        "opening_price": 100.0,
+        "high_price": 110.0,
+        "low_price": 90.0,
+        "trade_price": 105.0,
+        "timestamp": 1704067500000,
+        "candle_acc_trade_price": 1000.0,
+        "candle_acc_trade_volume": 10.0,
+        "unit": 5
+    }]"#;
+    let (base_url, captured) = spawn_mock_server(vec![MockResponse::json(200, body)]).await;
+    let client = UpbitClient::public(&base_url);
+
+    let candles = get_candle_minutes(&client, "KRW-BTC", Some("2024-01-01T00:10:00"), 1, 5).await.unwrap();
+    assert_eq!(candles.len(), 1);
+    assert_eq!(candles[0].unit, 5);
+    assert_eq!(candles[0].get_trade_price(), 105.0);
+
+    let captured = captured.lock().unwrap();
+    assert_eq!(captured[0].path, "/candles/minutes/5?market=KRW-BTC&to=2024-01-01T00:10:00&count=1&unit=5");
+    assert!(captured[0].header("Authorization").is_none());
+}
@@ -0,0 +1,143 @@
+mod mock_server;
+
+use std::path::PathBuf;
+
+use ctb::{backtest::{candle_store::CandleStore, fetch::{CandleGap, CandleInterval}}, core::candle::{Candle, CandleBase}, upbit_api::client::UpbitClient};
+use mock_server::{spawn_mock_server, MockResponse};
+
+fn create_candle(date: &str, price: f64) -> Candle {
+    Candle {
+        base: CandleBase {
+            market: "KRW-BTC".to_string(),
+            candle_date_time_utc: date.to_string(),
+            candle_date_time_kst: date.to_string(),
+            opening_price: price,
+            high_price: price,
+            low_price: price,
+            trade_price: price,
+            timestamp: 0,
+            candle_acc_trade_price: 1000.0,
+            candle_acc_trade_volume: 10.0,
+        }
+    }
+}
+
+// 5분봉 응답 (최신 순서)
+fn minute_candles_body(dates: &[&str]) -> String {
+    let candles = dates.iter().rev().map(|date| {
+        let mut value = serde_json::to_value(create_candle(date, 100.0)).unwrap();
+        value["unit"] = 5.into();
+        value
+    }).collect::<Vec<serde_json::Value>>();
+    serde_json::to_string(&candles).unwrap()
+}
+
+fn temp_store(name: &str) -> (CandleStore, PathBuf) {
+    let root = std::env::temp_dir().join(format!("ctb_candle_store_{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    (CandleStore::new(&root), root)
+}
+
+fn dates(candles: &[Candle]) -> Vec<&str> {
+    candles.iter().map(|candle| candle.base.candle_date_time_utc.as_str()).collect()
+}
+
+#[test]
+fn test_save_and_load() {
+    let (store, root) = temp_store("save");
+    let interval = CandleInterval::Minutes(5);
+    let added = store.save("KRW-BTC", interval, &[
+        create_candle("2024-01-02T00:00:00", 3.0),
+        create_candle("2024-01-01T23:55:00", 2.0),
+        create_candle("2024-01-01T23:50:00", 1.0),
+    ]).unwrap();
+    assert_eq!(added, 3);
+
+    // 날짜별 파티션
+    assert!(root.join("KRW-BTC/minutes_5/2024-01-01.jsonl.gz").exists());
+    assert!(root.join("KRW-BTC/minutes_5/2024-01-02.jsonl.gz").exists());
+
+    // 같은 시간은 덮어쓰고 새 캔들만 셈
+    let added = store.save("KRW-BTC", interval, &[create_candle("2024-01-01T23:55:00", 20.0), create_candle("2024-01-02T00:05:00", 4.0)]).unwrap();
+    assert_eq!(added, 1);
+
+    let candles = store.load("KRW-BTC", interval, "2024-01-01T00:00:00", "2024-01-03T00:00:00").unwrap();
+    assert_eq!(dates(&candles), vec!["2024-01-01T23:50:00", "2024-01-01T23:55:00", "2024-01-02T00:00:00", "2024-01-02T00:05:00"]);
+    assert_eq!(candles[1].base.trade_price, 20.0);
+
+    // to는 포함하지 않음
+    let candles = store.load("KRW-BTC", interval, "2024-01-01T23:55:00", "2024-01-02T00:05:00").unwrap();
+    assert_eq!(dates(&candles), vec!["2024-01-01T23:55:00", "2024-01-02T00:00:00"]);
+
+    // 다른 간격, 마켓은 비어 있음
+    assert!(store.load("KRW-BTC", CandleInterval::Days, "2024-01-01T00:00:00", "2024-02-01T00:00:00").unwrap().is_empty());
+    assert!(store.load("KRW-ETH", interval, "2024-01-01T00:00:00", "2024-02-01T00:00:00").unwrap().is_empty());
+    assert!(store.load("KRW-BTC", interval, "invalid", "2024-02-01T00:00:00").is_err());
+
+    let _ = std::fs::remove_dir_all(root);
+}
+
+#[test]
+fn test_find_gaps() {
+    let (store, root) = temp_store("gaps");
+    let interval = CandleInterval::Minutes(5);
+    store.save("KRW-BTC", interval, &[
+        create_candle("2024-01-01T00:00:00", 1.0),
+        create_candle("2024-01-01T00:05:00", 1.0),
+        create_candle("2024-01-01T00:20:00", 1.0),
+        create_candle("2024-01-01T00:25:00", 1.0),
+    ]).unwrap();
+
+    let gaps = store.find_gaps("KRW-BTC", interval, "2024-01-01T00:00:00", "2024-01-02T00:00:00").unwrap();
+    assert_eq!(gaps, vec![CandleGap { from: "2024-01-01T00:10:00".to_string(), to: "2024-01-01T00:20:00".to_string(), missing: 2 }]);
+
+    let _ = std::fs::remove_dir_all(root);
+}
+
+#[test]
+fn test_coverage() {
+    let (store, root) = temp_store("coverage");
+    let interval = CandleInterval::Seconds;
+    store.mark_covered("KRW-BTC", interval, 100, 200).unwrap();
+    store.mark_covered("KRW-BTC", interval, 300, 400).unwrap();
+    store.mark_covered("KRW-BTC", interval, 200, 250).unwrap();
+    assert_eq!(store.coverage("KRW-BTC", interval).unwrap(), vec![(100, 250), (300, 400)]);
+    assert_eq!(store.missing_ranges("KRW-BTC", interval, 0, 500).unwrap(), vec![(0, 100), (250, 300), (400, 500)]);
+    assert_eq!(store.missing_ranges("KRW-BTC", interval, 120, 350).unwrap(), vec![(250, 300)]);
+    assert!(store.missing_ranges("KRW-BTC", interval, 120, 240).unwrap().is_empty());
+
+    let _ = std::fs::remove_dir_all(root);
+}
+
+#[tokio::test]
+async fn test_sync_downloads_only_missing_ranges() {
+    let (store, root) = temp_store("sync");
+    let interval = CandleInterval::Minutes(5);
+
+    let (base_url, captured) = spawn_mock_server(vec![MockResponse::json(200, &minute_candles_body(&[
+        "2024-01-01T00:00:00", "2024-01-01T00:05:00", "2024-01-01T00:15:00", "2024-01-01T00:20:00",
+    ]))]).await;
+    let client = UpbitClient::public(&base_url);
+    let added = store.sync(&client, "KRW-BTC", interval, "2024-01-01T00:00:00", "2024-01-01T00:25:00").await.unwrap();
+    assert_eq!(added, 4);
+    assert_eq!(captured.lock().unwrap()[0].path, "/candles/minutes/5?market=KRW-BTC&to=2024-01-01T00:25:00Z&count=200&unit=5");
+
+    // 이미 받은 구간은 요청하지 않음 (거래가 없어 빠진 00:10도 다시 받지 않음)
+    let added = store.sync(&client, "KRW-BTC", interval, "2024-01-01T00:00:00", "2024-01-01T00:25:00").await.unwrap();
+    assert_eq!(added, 0);
+    assert_eq!(captured.lock().unwrap().len(), 1);
+
+    // 늘어난 구간만 요청
+    let (base_url, captured) = spawn_mock_server(vec![MockResponse::json(200, &minute_candles_body(&[
+        "2024-01-01T00:20:00", "2024-01-01T00:25:00", "2024-01-01T00:30:00",
+    ]))]).await;
+    let client = UpbitClient::public(&base_url);
+    let candles = store.load_synced(&client, "KRW-BTC", interval, "2024-01-01T00:00:00", "2024-01-01T00:35:00").await.unwrap();
+    assert_eq!(captured.lock().unwrap()[0].path, "/candles/minutes/5?market=KRW-BTC&to=2024-01-01T00:35:00Z&count=200&unit=5");
+    assert_eq!(dates(&candles), vec![
+        "2024-01-01T00:00:00", "2024-01-01T00:05:00", "2024-01-01T00:15:00", "2024-01-01T00:20:00", "2024-01-01T00:25:00", "2024-01-01T00:30:00",
+    ]);
+    assert_eq!(store.coverage("KRW-BTC", interval).unwrap().len(), 1);
+
+    let _ = std::fs::remove_dir_all(root);
+}
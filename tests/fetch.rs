@@ -0,0 +1,124 @@
+mod mock_server;
+
+use ctb::{backtest::{fetch::{fetch_candles_range, find_gaps, format_candle_date, CandleGap, CandleInterval, FetchError}, ledger::parse_backtest_date},
+core::candle::{Candle, CandleBase}, upbit_api::client::UpbitClient};
+use mock_server::{spawn_mock_server, MockResponse};
+
+fn create_candle(date: &str) -> Candle {
+    Candle {
+        base: CandleBase {
+            market: "KRW-BTC".to_string(),
+            candle_date_time_utc: date.to_string(),
+            candle_date_time_kst: date.to_string(),
+            opening_price: 100.0,
+            high_price: 100.0,
+            low_price: 100.0,
+            trade_price: 100.0,
+            timestamp: 0,
+            candle_acc_trade_price: 1000.0,
+            candle_acc_trade_volume: 10.0,
+        }
+    }
+}
+
+// 업비트 응답 (최신 순서). extra는 간격별 추가 필드
+fn candles_body(dates: &[String], extra: (&str, serde_json::Value)) -> String {
+    let candles = dates.iter().rev().map(|date| {
+        let mut value = serde_json::to_value(create_candle(date)).unwrap();
+        value[extra.0] = extra.1.clone();
+        value
+    }).collect::<Vec<serde_json::Value>>();
+    serde_json::to_string(&candles).unwrap()
+}
+
+// 2024-01-01 00:00부터 index분 뒤의 1분봉 시간
+fn minute(index: i64) -> String {
+    format_candle_date(parse_backtest_date("2024-01-01T00:00:00").unwrap() + index * 60_000)
+}
+
+fn dates(candles: &[Candle]) -> Vec<&str> {
+    candles.iter().map(|candle| candle.base.candle_date_time_utc.as_str()).collect()
+}
+
+#[test]
+fn test_interval_boundaries() {
+    let time = |date: &str| parse_backtest_date(date).unwrap();
+
+    assert_eq!(CandleInterval::Minutes(15).floor(time("2024-01-01T10:44:59")), time("2024-01-01T10:30:00"));
+    // 2024-01-03은 수요일
+    assert_eq!(CandleInterval::Weeks.floor(time("2024-01-03T12:00:00")), time("2024-01-01T00:00:00"));
+    assert_eq!(CandleInterval::Weeks.next(time("2024-01-01T00:00:00")), time("2024-01-08T00:00:00"));
+    assert_eq!(CandleInterval::Months.floor(time("2024-02-29T23:00:00")), time("2024-02-01T00:00:00"));
+    assert_eq!(CandleInterval::Months.next(time("2024-01-01T00:00:00")), time("2024-02-01T00:00:00"));
+    assert_eq!(CandleInterval::Years.next(time("2024-01-01T00:00:00")), time("2025-01-01T00:00:00"));
+    assert_eq!(CandleInterval::Months.count_between(time("2024-01-01T00:00:00"), time("2024-04-01T00:00:00")), 3);
+    assert_eq!(CandleInterval::Minutes(5).count_between(time("2024-01-01T00:00:00"), time("2024-01-01T00:12:00")), 3);
+
+    for unit in [1, 3, 5, 10, 15, 30, 60, 240] {
+        assert!(CandleInterval::Minutes(unit).is_supported());
+    }
+    assert!(!CandleInterval::Minutes(7).is_supported());
+    assert_eq!(CandleInterval::Weeks.name(), "weeks");
+}
+
+#[tokio::test]
+async fn test_fetch_range_pages_backwards_and_dedups() {
+    // 첫 페이지: 02:40 ~ 05:59 (200개), 두 번째 페이지: 00:00 ~ 02:40 (02:40 중복, 01:40 ~ 01:42 없음)
+    let first_page = (160..360).map(minute).collect::<Vec<String>>();
+    let second_page = (0..=160).filter(|i| !(100..103).contains(i)).map(minute).collect::<Vec<String>>();
+    let (base_url, captured) = spawn_mock_server(vec![
+        MockResponse::json(200, &candles_body(&first_page, ("unit", 1.into()))),
+        MockResponse::json(200, &candles_body(&second_page, ("unit", 1.into()))),
+    ]).await;
+    let client = UpbitClient::public(&base_url);
+
+    let range = fetch_candles_range(&client, "KRW-BTC", CandleInterval::Minutes(1), "2024-01-01T00:00:00", "2024-01-01T06:00:00").await.unwrap();
+    assert_eq!(range.candles.len(), 357);
+    assert_eq!(range.candles.first().unwrap().base.candle_date_time_utc, "2024-01-01T00:00:00");
+    assert_eq!(range.candles.last().unwrap().base.candle_date_time_utc, "2024-01-01T05:59:00");
+    assert!(range.candles.windows(2).all(|w| w[0].base.candle_date_time_utc < w[1].base.candle_date_time_utc));
+    assert_eq!(range.gaps, vec![CandleGap { from: "2024-01-01T01:40:00".to_string(), to: "2024-01-01T01:43:00".to_string(), missing: 3 }]);
+
+    let captured = captured.lock().unwrap();
+    assert_eq!(captured.len(), 2);
+    assert_eq!(captured[0].path, "/candles/minutes/1?market=KRW-BTC&to=2024-01-01T06:00:00Z&count=200&unit=1");
+    assert_eq!(captured[1].path, "/candles/minutes/1?market=KRW-BTC&to=2024-01-01T02:40:00Z&count=200&unit=1");
+}
+
+#[tokio::test]
+async fn test_fetch_range_months_and_weeks() {
+    let months = ["2024-01-01T00:00:00", "2024-02-01T00:00:00", "2024-04-01T00:00:00", "2024-05-01T00:00:00"].map(str::to_string);
+    let (base_url, captured) = spawn_mock_server(vec![MockResponse::json(200, &candles_body(&months, ("first_day_of_period", "2024-01-01".into())))]).await;
+    let client = UpbitClient::public(&base_url);
+    let range = fetch_candles_range(&client, "KRW-BTC", CandleInterval::Months, "2024-01-01T00:00:00", "2024-06-01T00:00:00").await.unwrap();
+    assert_eq!(dates(&range.candles), months.iter().map(String::as_str).collect::<Vec<&str>>());
+    assert_eq!(range.gaps, vec![CandleGap { from: "2024-03-01T00:00:00".to_string(), to: "2024-04-01T00:00:00".to_string(), missing: 1 }]);
+    assert_eq!(captured.lock().unwrap()[0].path, "/candles/months?market=KRW-BTC&to=2024-06-01T00:00:00Z&count=200");
+
+    // from 이전 캔들은 제외
+    let weeks = ["2023-12-25T00:00:00", "2024-01-01T00:00:00", "2024-01-08T00:00:00"].map(str::to_string);
+    let (base_url, captured) = spawn_mock_server(vec![MockResponse::json(200, &candles_body(&weeks, ("first_day_of_period", "2024-01-01".into())))]).await;
+    let client = UpbitClient::public(&base_url);
+    let range = fetch_candles_range(&client, "KRW-BTC", CandleInterval::Weeks, "2024-01-01T00:00:00", "2024-01-15T00:00:00").await.unwrap();
+    assert_eq!(dates(&range.candles), vec!["2024-01-01T00:00:00", "2024-01-08T00:00:00"]);
+    assert!(range.gaps.is_empty());
+    assert_eq!(captured.lock().unwrap()[0].path, "/candles/weeks?market=KRW-BTC&to=2024-01-15T00:00:00Z&count=200");
+}
+
+#[tokio::test]
+async fn test_fetch_range_rejects_invalid_input() {
+    let client = UpbitClient::public("http://127.0.0.1:1");
+    let result = fetch_candles_range(&client, "KRW-BTC", CandleInterval::Minutes(7), "2024-01-01T00:00:00", "2024-01-02T00:00:00").await;
+    assert!(matches!(result, Err(FetchError::UnsupportedInterval(CandleInterval::Minutes(7)))));
+
+    let result = fetch_candles_range(&client, "KRW-BTC", CandleInterval::Days, "yesterday", "2024-01-02T00:00:00").await;
+    assert!(matches!(result, Err(FetchError::InvalidDate(date)) if date == "yesterday"));
+}
+
+#[test]
+fn test_find_gaps_for_days() {
+    let candles = ["2024-01-01T00:00:00", "2024-01-02T00:00:00", "2024-01-05T00:00:00"].map(create_candle);
+    assert_eq!(find_gaps(&candles, CandleInterval::Days), vec![
+        CandleGap { from: "2024-01-03T00:00:00".to_string(), to: "2024-01-05T00:00:00".to_string(), missing: 2 },
+    ]);
+}
@@ -0,0 +1,108 @@
+use ctb::{backtest::{fee::FeeSchedule, fill::{resolve_intrabar_exit, IntrabarFill, IntrabarPolicy}, ledger::ExitReason, lib::{BacktestParams, BacktesterState}}, core::{candle::{Candle, CandleBase}, position::PositionState, signal::Signal}};
+
+fn create_candle(date: &str, opening: f64, high: f64, low: f64, trade: f64) -> Candle {
+    Candle {
+        base: CandleBase {
+            market: "KRW-BTC".to_string(),
+            candle_date_time_utc: date.to_string(),
+            candle_date_time_kst: date.to_string(),
+            opening_price: opening,
+            high_price: high,
+            low_price: low,
+            trade_price: trade,
+            timestamp: 0,
+            candle_acc_trade_price: 1000000.0,
+            candle_acc_trade_volume: 1000.0,
+        }
+    }
+}
+
+fn take_profit(price: f64) -> Option<IntrabarFill> {
+    Some(IntrabarFill { price, exit_reason: ExitReason::TakeProfit })
+}
+
+fn stop(price: f64) -> Option<IntrabarFill> {
+    Some(IntrabarFill { price, exit_reason: ExitReason::TrailingStop })
+}
+
+#[test]
+fn test_single_level_hit() {
+    // 고가만 익절가에 닿음
+    let candle = create_candle("2024-01-01T00:00:00", 100.0, 125.0, 98.0, 110.0);
+    assert_eq!(resolve_intrabar_exit(&candle, 120.0, 90.0, IntrabarPolicy::Pessimistic, &[]), take_profit(120.0));
+
+    // 저가만 스탑에 닿음
+    let candle = create_candle("2024-01-01T00:00:00", 100.0, 105.0, 85.0, 95.0);
+    assert_eq!(resolve_intrabar_exit(&candle, 120.0, 90.0, IntrabarPolicy::Optimistic, &[]), stop(90.0));
+
+    // 둘 다 닿지 않음
+    let candle = create_candle("2024-01-01T00:00:00", 100.0, 105.0, 95.0, 102.0);
+    assert_eq!(resolve_intrabar_exit(&candle, 120.0, 90.0, IntrabarPolicy::Pessimistic, &[]), None);
+}
+
+#[test]
+fn test_both_levels_hit() {
+    let candle = create_candle("2024-01-01T00:00:00", 100.0, 125.0, 85.0, 100.0);
+    assert_eq!(resolve_intrabar_exit(&candle, 120.0, 90.0, IntrabarPolicy::Pessimistic, &[]), stop(90.0));
+    assert_eq!(resolve_intrabar_exit(&candle, 120.0, 90.0, IntrabarPolicy::Optimistic, &[]), take_profit(120.0));
+    // 하위 캔들이 없으면 Pessimistic과 같음
+    assert_eq!(resolve_intrabar_exit(&candle, 120.0, 90.0, IntrabarPolicy::LowerTimeframe, &[]), stop(90.0));
+}
+
+#[test]
+fn test_lower_timeframe() {
+    let candle = create_candle("2024-01-01T00:00:00", 100.0, 125.0, 85.0, 100.0);
+
+    // 익절가에 먼저 닿음
+    let lower = vec![
+        create_candle("2024-01-01T00:00:00", 100.0, 110.0, 95.0, 108.0),
+        create_candle("2024-01-01T00:01:00", 108.0, 125.0, 105.0, 110.0),
+        create_candle("2024-01-01T00:02:00", 110.0, 110.0, 85.0, 100.0),
+    ];
+    assert_eq!(resolve_intrabar_exit(&candle, 120.0, 90.0, IntrabarPolicy::LowerTimeframe, &lower), take_profit(120.0));
+
+    // 스탑에 먼저 닿음
+    let lower = vec![
+        create_candle("2024-01-01T00:00:00", 100.0, 100.0, 85.0, 95.0),
+        create_candle("2024-01-01T00:01:00", 95.0, 125.0, 95.0, 100.0),
+    ];
+    assert_eq!(resolve_intrabar_exit(&candle, 120.0, 90.0, IntrabarPolicy::LowerTimeframe, &lower), stop(90.0));
+}
+
+#[test]
+fn test_gap_at_open() {
+    // 시가가 스탑 아래에서 시작하면 시가에 체결
+    let candle = create_candle("2024-01-01T00:00:00", 80.0, 95.0, 78.0, 90.0);
+    assert_eq!(resolve_intrabar_exit(&candle, 120.0, 90.0, IntrabarPolicy::Optimistic, &[]), stop(80.0));
+
+    // 시가가 익절가 위에서 시작하면 시가에 체결
+    let candle = create_candle("2024-01-01T00:00:00", 130.0, 135.0, 85.0, 100.0);
+    assert_eq!(resolve_intrabar_exit(&candle, 120.0, 90.0, IntrabarPolicy::Pessimistic, &[]), take_profit(130.0));
+}
+
+#[test]
+fn test_backtester_candle_exit() {
+    let mut params = BacktestParams::default("KRW-BTC", "TEST");
+    params.enable_webhook_log = false;
+    params.fees = FeeSchedule::flat(0.0);
+    let mut backtester = BacktesterState::new(params);
+
+    backtester.handle_signal(&Signal::Buy {
+        reason: "breakout".to_string(),
+        initial_trailing_stop: 90.0,
+        take_profit: 120.0,
+        asset_pct: 1.0,
+    }, 100.0, "2024-01-01T00:00:00");
+
+    // 종가는 범위 안이지만 저가가 스탑에 닿음
+    let candle = create_candle("2024-01-01T00:01:00", 100.0, 105.0, 85.0, 101.0);
+    backtester.check_and_close_position_with_candle(&candle, IntrabarPolicy::Pessimistic, &[]);
+
+    assert!(matches!(backtester.position, PositionState::None));
+    let trade = &backtester.trades[0];
+    assert_eq!(trade.exit_reason, ExitReason::TrailingStop);
+    assert!((trade.exit_price - 90.0).abs() < 1e-9);
+    assert!((trade.mae_pct + 0.15).abs() < 1e-9);
+    assert!((trade.mfe_pct - 0.05).abs() < 1e-9);
+    assert_eq!(backtester.equity_curve.last().unwrap().date, "2024-01-01T00:01:00");
+}
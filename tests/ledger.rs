@@ -0,0 +1,120 @@
+use ctb::{backtest::{fee::FeeSchedule, ledger::{save_equity_curve_csv, save_trades_csv, save_trades_json, write_trades_csv, ExitReason, TradeRecord}, lib::{BacktestParams, BacktesterState}}, core::{position::{OpenTrade, PositionState}, signal::{Signal, SignalReason}}};
+
+fn buy(backtester: &mut BacktesterState, price: f64, date: &str, reason: &str) {
+    backtester.handle_signal(&Signal::Buy {
+        reason: reason.to_string(),
+        initial_trailing_stop: price * 0.9,
+        take_profit: price * 1.2,
+        asset_pct: 0.5,
+    }, price, date);
+}
+
+fn run_backtest() -> BacktesterState {
+    let mut params = BacktestParams::default("KRW-BTC", "TEST");
+    params.enable_webhook_log = false;
+    params.fees = FeeSchedule::flat(0.001);
+    let mut backtester = BacktesterState::new(params);
+
+    // 익절
+    buy(&mut backtester, 100.0, "2024-01-01T00:00:00", "breakout");
+    backtester.check_and_close_position(95.0, "2024-01-01T00:01:00");
+    backtester.check_and_close_position(125.0, "2024-01-01T00:02:00");
+
+    // 트레일링 스탑
+    buy(&mut backtester, 100.0, "2024-01-01T00:03:00", "pullback, \"retest\"");
+    backtester.check_and_close_position(105.0, "2024-01-01T00:04:00");
+    backtester.check_and_close_position(89.0, "2024-01-01T00:05:00");
+
+    // 전략 매도
+    buy(&mut backtester, 100.0, "2024-01-01T00:06:00", "breakout");
+    backtester.handle_signal(&Signal::Sell(SignalReason { reason: "trend broken".to_string() }), 102.0, "2024-01-01T00:07:00");
+
+    // 테스트 종료
+    buy(&mut backtester, 100.0, "2024-01-01T00:08:00", "breakout");
+    backtester.close_at_end(101.0, "2024-01-01T00:09:00");
+
+    backtester
+}
+
+#[test]
+fn test_trade_records() {
+    let backtester = run_backtest();
+    let trades = &backtester.trades;
+    let exit_reasons = trades.iter().map(|trade| trade.exit_reason).collect::<Vec<ExitReason>>();
+    assert_eq!(exit_reasons, vec![ExitReason::TakeProfit, ExitReason::TrailingStop, ExitReason::StrategySell, ExitReason::EndOfTest]);
+
+    let take_profit = &trades[0];
+    assert_eq!(take_profit.entry_date, "2024-01-01T00:00:00");
+    assert_eq!(take_profit.exit_date, "2024-01-01T00:02:00");
+    assert!((take_profit.exit_price - 120.0).abs() < 1e-9);
+    assert!((take_profit.mae_pct + 0.05).abs() < 1e-9);
+    assert!((take_profit.mfe_pct - 0.25).abs() < 1e-9);
+    assert!((take_profit.size - take_profit.entry_asset / 100.0).abs() < 1e-9);
+    assert!((take_profit.fees - take_profit.entry_asset * 0.002).abs() < 1e-9);
+    assert_eq!(take_profit.entry_reason, "breakout");
+    assert_eq!(take_profit.sell_reason, None);
+
+    let trailing_stop = &trades[1];
+    assert!((trailing_stop.exit_price - 89.0).abs() < 1e-9); // 스탑을 뚫고 내려간 현재가에 체결
+    assert!((trailing_stop.mae_pct + 0.11).abs() < 1e-9);
+    assert!((trailing_stop.mfe_pct - 0.05).abs() < 1e-9);
+
+    assert_eq!(trades[2].sell_reason.as_deref(), Some("trend broken"));
+    assert_eq!(trades[3].sell_reason, None);
+    assert!(matches!(backtester.position, PositionState::None));
+}
+
+#[test]
+fn test_open_trade_excursions() {
+    let mut trade = OpenTrade::new("2024-01-01T00:00:00", "breakout", 100.0);
+    trade.update(90.0);
+    trade.update(120.0);
+    assert!((trade.mae_pct(100.0) + 0.1).abs() < 1e-9);
+    assert!((trade.mfe_pct(100.0) - 0.2).abs() < 1e-9);
+    // 진입가가 보유 중 가격 범위 밖이면 MAE는 0 이하, MFE는 0 이상으로 제한
+    assert_eq!(trade.mae_pct(80.0), 0.0);
+    assert_eq!(trade.mfe_pct(130.0), 0.0);
+    assert_eq!(trade.mae_pct(0.0), 0.0);
+}
+
+#[test]
+fn test_export_trades_csv() {
+    let backtester = run_backtest();
+    let mut buffer = Vec::new();
+    write_trades_csv(&mut buffer, &backtester.trades).unwrap();
+    let csv = String::from_utf8(buffer).unwrap();
+    let lines = csv.lines().collect::<Vec<&str>>();
+
+    assert_eq!(lines.len(), 5);
+    assert!(lines[0].starts_with("code,entry_date,exit_date,entry_price"));
+    assert!(lines[1].contains(",take_profit,"));
+    // 쉼표와 따옴표가 있는 필드는 따옴표로 감쌈
+    assert!(lines[2].ends_with(",\"pullback, \"\"retest\"\"\","));
+    assert!(lines[3].ends_with(",breakout,trend broken"));
+}
+
+#[test]
+fn test_save_ledger_files() {
+    let backtester = run_backtest();
+    let dir = std::env::temp_dir().join(format!("ctb-ledger-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    save_trades_json(dir.join("trades.json"), &backtester.trades).unwrap();
+    let json = std::fs::read_to_string(dir.join("trades.json")).unwrap();
+    let trades: Vec<TradeRecord> = serde_json::from_str(&json).unwrap();
+    assert_eq!(trades.len(), 4);
+    assert_eq!(trades[1].exit_reason, ExitReason::TrailingStop);
+    assert!(json.contains("\"exit_reason\": \"end_of_test\""));
+
+    save_trades_csv(dir.join("trades.csv"), &backtester.trades).unwrap();
+    assert_eq!(std::fs::read_to_string(dir.join("trades.csv")).unwrap().lines().count(), 5);
+
+    save_equity_curve_csv(dir.join("equity.csv"), &backtester.equity_curve).unwrap();
+    let equity_csv = std::fs::read_to_string(dir.join("equity.csv")).unwrap();
+    let lines = equity_csv.lines().collect::<Vec<&str>>();
+    assert_eq!(lines[0], "date,equity");
+    assert_eq!(lines.len(), backtester.equity_curve.len() + 1);
+    assert!(lines[1].starts_with("2024-01-01T00:00:00,"));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -1,7 +1,7 @@
-use ctb::upbit_api::market::get_market_info;
+use ctb::upbit_api::{client::UpbitClient, market::get_market_info};
 
 #[tokio::test]
 pub async fn test_get_market_info() {
-    let result = get_market_info().await;
+    let result = get_market_info(&UpbitClient::from_env()).await;
     eprintln!("{:?}", result);
 }
\ No newline at end of file
@@ -0,0 +1,152 @@
+use ctb::{backtest::lib::{BacktestParams, BacktesterState}, core::{position::PositionState, signal::Signal},
+upbit_api::{market_rules::{MarketRuleError, MarketRules, QuoteCurrency}, order::{OrderRequest, OrderSide, TimeInForce}}};
+
+#[test]
+fn test_quote_currency() {
+    assert_eq!(QuoteCurrency::from_market("KRW-BTC"), Some(QuoteCurrency::Krw));
+    assert_eq!(QuoteCurrency::from_market("BTC-ETH"), Some(QuoteCurrency::Btc));
+    assert_eq!(QuoteCurrency::from_market("USDT-BTC"), Some(QuoteCurrency::Usdt));
+    assert_eq!(QuoteCurrency::from_market("ETH"), None);
+    assert_eq!(MarketRules::for_market("KRW-BTC").min_order_amount, 5000.0);
+    assert_eq!(MarketRules::for_market("BTC-ETH").min_order_amount, 0.00005);
+}
+
+#[test]
+fn test_krw_tick_size() {
+    let rules = MarketRules::for_market("KRW-BTC");
+    assert_eq!(rules.tick_size(2_500_000.0), 1000.0);
+    assert_eq!(rules.tick_size(2_000_000.0), 1000.0);
+    assert_eq!(rules.tick_size(1_999_999.0), 500.0);
+    assert_eq!(rules.tick_size(700_000.0), 100.0);
+    assert_eq!(rules.tick_size(150_000.0), 50.0);
+    assert_eq!(rules.tick_size(50_000.0), 10.0);
+    assert_eq!(rules.tick_size(5_000.0), 5.0);
+    assert_eq!(rules.tick_size(500.0), 1.0);
+    // 1,000원 미만은 가격대가 10배 낮아질 때마다 호가 단위도 10배 작아짐
+    assert_eq!(rules.tick_size(100.0), 1.0);
+    assert_eq!(rules.tick_size(99.9), 0.1);
+    assert_eq!(rules.tick_size(10.0), 0.1);
+    assert_eq!(rules.tick_size(9.99), 0.01);
+    assert_eq!(rules.tick_size(1.0), 0.01);
+    assert_eq!(rules.tick_size(0.999), 0.001);
+    assert_eq!(rules.tick_size(0.1), 0.001);
+    assert_eq!(rules.tick_size(0.0999), 0.0001);
+    assert_eq!(rules.tick_size(0.01), 0.0001);
+    assert_eq!(rules.tick_size(0.00999), 0.00001);
+    assert_eq!(rules.tick_size(0.001), 0.00001);
+    assert_eq!(rules.tick_size(0.000999), 0.000001);
+    assert_eq!(rules.tick_size(0.0001), 0.000001);
+    assert_eq!(rules.tick_size(0.0000999), 0.0000001);
+    assert_eq!(rules.tick_size(0.00001), 0.0000001);
+    assert_eq!(rules.tick_size(0.00000999), 0.00000001);
+}
+
+#[test]
+fn test_round_price() {
+    let rules = MarketRules::for_market("KRW-BTC");
+    assert_eq!(rules.round_price(2_000_300.0), 2_000_000.0);
+    assert_eq!(rules.round_price_up(2_000_300.0), 2_001_000.0);
+    assert_eq!(rules.round_price_down(2_000_900.0), 2_000_000.0);
+    // 호가 단위 경계
+    assert_eq!(rules.round_price_up(1_999_800.0), 2_000_000.0);
+    assert_eq!(rules.round_price_down(1_999_800.0), 1_999_500.0);
+    assert_eq!(rules.round_price(1_234.0), 1_235.0);
+    assert_eq!(rules.round_price_down(56.78), 56.7);
+    assert_eq!(rules.round_price_up(56.71), 56.8);
+    assert_eq!(rules.round_price_down(5.6789), 5.67);
+    assert_eq!(rules.round_price_up(9.991), 10.0);
+    // 1원 미만 코인의 스탑/익절도 진입가 근처 호가로 맞춤
+    assert_eq!(rules.round_price_up(0.0042), 0.0042);
+    assert_eq!(rules.round_price_up(0.00421234), 0.00422);
+    assert_eq!(rules.round_price_down(0.0046789), 0.00467);
+    assert_eq!(rules.round_price_down(0.56789), 0.567);
+    assert_eq!(rules.round_price_up(0.99999), 1.0);
+    assert!(rules.is_valid_price(0.00421));
+    assert!(!rules.is_valid_price(0.004213));
+    // 이미 맞는 가격은 그대로
+    assert_eq!(rules.round_price_down(0.1 + 0.2), 0.3);
+    assert_eq!(rules.round_price_up(100.0 * 0.9), 90.0);
+
+    let rules = MarketRules::for_market("BTC-ETH");
+    assert_eq!(rules.round_price_down(0.0512345678), 0.05123456);
+}
+
+#[test]
+fn test_round_volume() {
+    let rules = MarketRules::for_market("KRW-BTC");
+    assert_eq!(rules.round_volume(0.123456789), 0.12345678);
+    assert_eq!(rules.round_volume(0.1 + 0.2), 0.3);
+}
+
+#[test]
+fn test_round_amount() {
+    assert_eq!(MarketRules::for_market("KRW-BTC").round_amount(12345.67), 12345.0);
+    assert_eq!(MarketRules::for_market("BTC-ETH").round_amount(0.498753117), 0.49875311);
+    assert_eq!(MarketRules::for_market("USDT-BTC").round_amount(10.5), 10.5);
+}
+
+#[test]
+fn test_validate_order() {
+    let rules = MarketRules::for_market("KRW-BTC");
+    assert_eq!(rules.validate_order(&OrderRequest::limit("KRW-BTC", OrderSide::Bid, 0.01, 2_001_000.0)), Ok(()));
+    assert_eq!(rules.validate_order(&OrderRequest::limit("KRW-BTC", OrderSide::Bid, 0.01, 2_000_300.0)),
+        Err(MarketRuleError::InvalidTickSize { price: 2_000_300.0, tick_size: 1000.0 }));
+    assert_eq!(rules.validate_order(&OrderRequest::limit("KRW-BTC", OrderSide::Bid, 0.001, 2_000_000.0)),
+        Err(MarketRuleError::BelowMinimumOrder { amount: 2000.0, minimum: 5000.0 }));
+    assert_eq!(rules.validate_order(&OrderRequest::limit("KRW-BTC", OrderSide::Bid, 0.123456789, 2_000_000.0)),
+        Err(MarketRuleError::InvalidVolume(0.123456789)));
+
+    assert_eq!(rules.validate_order(&OrderRequest::market_buy("KRW-BTC", 5000.0)), Ok(()));
+    assert!(matches!(rules.validate_order(&OrderRequest::market_buy("KRW-BTC", 4999.0)), Err(MarketRuleError::BelowMinimumOrder { .. })));
+    assert_eq!(rules.validate_order(&OrderRequest::market_sell("KRW-BTC", 0.5)), Ok(()));
+    assert!(matches!(rules.validate_order(&OrderRequest::best_buy("KRW-BTC", 1000.0, TimeInForce::Ioc)), Err(MarketRuleError::BelowMinimumOrder { .. })));
+}
+
+#[test]
+fn test_align_signal() {
+    let rules = MarketRules::for_market("KRW-BTC");
+    let signal = Signal::Buy {
+        reason: "breakout".to_string(),
+        initial_trailing_stop: 1_999_800.0,
+        take_profit: 2_345_678.9,
+        asset_pct: 0.5,
+    };
+    assert_eq!(rules.align_signal(&signal), Signal::Buy {
+        reason: "breakout".to_string(),
+        initial_trailing_stop: 2_000_000.0,
+        take_profit: 2_345_000.0,
+        asset_pct: 0.5,
+    });
+    assert_eq!(rules.align_signal(&Signal::UpdateTrailingStop(1234.1)), Signal::UpdateTrailingStop(1235.0));
+    assert_eq!(rules.align_signal(&Signal::Hold), Signal::Hold);
+}
+
+#[test]
+fn test_backtester_applies_market_rules() {
+    let mut params = BacktestParams::default("KRW-BTC", "TEST");
+    params.enable_webhook_log = false;
+    let mut backtester = BacktesterState::new(params);
+
+    // 최소 주문 금액 미만 진입은 무시
+    backtester.handle_signal(&Signal::Buy {
+        reason: "small".to_string(),
+        initial_trailing_stop: 90.0,
+        take_profit: 120.0,
+        asset_pct: 0.001,
+    }, 100.0, "2024-01-01T00:00:00");
+    assert!(matches!(backtester.position, PositionState::None));
+
+    backtester.handle_signal(&Signal::Buy {
+        reason: "breakout".to_string(),
+        initial_trailing_stop: 1_950_123.0,
+        take_profit: 2_100_456.0,
+        asset_pct: 0.5,
+    }, 2_000_000.0, "2024-01-01T00:01:00");
+    match backtester.position {
+        PositionState::InPosition { take_profit_price, trailing_stop_price, .. } => {
+            assert_eq!(take_profit_price, 2_100_000.0);
+            assert_eq!(trailing_stop_price, 1_950_500.0);
+        }
+        PositionState::None => panic!("position not opened"),
+    }
+}
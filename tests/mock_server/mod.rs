@@ -0,0 +1,158 @@
+// 테스트 바이너리마다 사용하는 헬퍼가 달라 사용하지 않는 항목이 생김
+#![allow(dead_code)]
+
+use std::sync::{Arc, Mutex};
+
+use tokio::{io::{AsyncReadExt, AsyncWriteExt}, net::TcpListener};
+
+/// mock 서버가 받은 요청
+#[derive(Debug, Clone)]
+pub struct CapturedRequest {
+    pub method: String,
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl CapturedRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|(key, _)| key.eq_ignore_ascii_case(name)).map(|(_, value)| value.as_str())
+    }
+}
+
+/// mock 서버가 돌려줄 응답
+pub struct MockResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl MockResponse {
+    pub fn json(status: u16, body: &str) -> Self {
+        Self { status, headers: Vec::new(), body: body.to_string() }
+    }
+
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+}
+
+/// 주어진 응답을 순서대로 한 번씩 돌려주는 로컬 HTTP 서버를 띄움
+///
+/// base url (`http://127.0.0.1:{port}`)과 수신한 요청 목록을 반환
+pub async fn spawn_mock_server(responses: Vec<MockResponse>) -> (String, Arc<Mutex<Vec<CapturedRequest>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let captured = Arc::new(Mutex::new(Vec::new()));
+
+    let captured_ref = captured.clone();
+    tokio::spawn(async move {
+        for response in responses {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let request = read_request(&mut socket).await;
+            captured_ref.lock().unwrap().push(request);
+
+            let mut raw = format!("HTTP/1.1 {} MOCK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n",
+                response.status, response.body.len());
+            for (name, value) in &response.headers {
+                raw.push_str(&format!("{}: {}\r\n", name, value));
+            }
+            raw.push_str("\r\n");
+            raw.push_str(&response.body);
+            socket.write_all(raw.as_bytes()).await.unwrap();
+            socket.shutdown().await.ok();
+        }
+    });
+
+    (base_url, captured)
+}
+
+async fn read_request(socket: &mut tokio::net::TcpStream) -> CapturedRequest {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 4096];
+    let header_end = loop {
+        let n = socket.read(&mut chunk).await.unwrap();
+        buffer.extend_from_slice(&chunk[..n]);
+        if let Some(pos) = buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos + 4;
+        }
+        if n == 0 {
+            break buffer.len();
+        }
+    };
+
+    let head = String::from_utf8_lossy(&buffer[..header_end]).to_string();
+    let mut lines = head.lines();
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let method = request_line.next().unwrap_or_default().to_string();
+    let path = request_line.next().unwrap_or_default().to_string();
+    let headers = lines.filter_map(|line| line.split_once(": "))
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect::<Vec<(String, String)>>();
+
+    let content_length = headers.iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.parse::<usize>().ok())
+        .unwrap_or(0);
+    while buffer.len() < header_end + content_length {
+        let n = socket.read(&mut chunk).await.unwrap();
+        if n == 0 { break; }
+        buffer.extend_from_slice(&chunk[..n]);
+    }
+    let body = String::from_utf8_lossy(&buffer[header_end..]).to_string();
+
+    CapturedRequest { method, path, headers, body }
+}
+
+/// 연결마다 주어진 메시지를 보내는 로컬 WebSocket 서버를 띄움
+///
+/// 각 연결은 구독 요청을 받은 뒤 메시지를 보내고 끊음. 마지막 연결은 클라이언트가 닫을 때까지 유지함.
+/// ws url과 연결마다 받은 구독 요청 목록을 반환
+pub async fn spawn_mock_ws_server(sessions: Vec<Vec<String>>) -> (String, Arc<Mutex<Vec<String>>>) {
+    let (ws_url, subscriptions, _) = spawn_mock_ws_server_with_handshakes(sessions).await;
+    (ws_url, subscriptions)
+}
+
+/// `spawn_mock_ws_server`와 같으며, 연결마다 받은 핸드셰이크 요청 (경로, 헤더)도 함께 반환
+pub async fn spawn_mock_ws_server_with_handshakes(sessions: Vec<Vec<String>>) -> (String, Arc<Mutex<Vec<String>>>, Arc<Mutex<Vec<CapturedRequest>>>) {
+    use futures_util::{SinkExt, StreamExt};
+    use tungstenite::{handshake::server::{Request, Response}, Message};
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let ws_url = format!("ws://{}", listener.local_addr().unwrap());
+    let subscriptions = Arc::new(Mutex::new(Vec::new()));
+    let handshakes = Arc::new(Mutex::new(Vec::new()));
+
+    let subscriptions_ref = subscriptions.clone();
+    let handshakes_ref = handshakes.clone();
+    tokio::spawn(async move {
+        let session_count = sessions.len();
+        for (index, messages) in sessions.into_iter().enumerate() {
+            let (socket, _) = listener.accept().await.unwrap();
+            let handshakes = handshakes_ref.clone();
+            let mut ws_stream = tokio_tungstenite::accept_hdr_async(socket, move |request: &Request, response: Response| {
+                handshakes.lock().unwrap().push(CapturedRequest {
+                    method: request.method().to_string(),
+                    path: request.uri().path().to_string(),
+                    headers: request.headers().iter()
+                        .map(|(key, value)| (key.to_string(), value.to_str().unwrap_or_default().to_string()))
+                        .collect(),
+                    body: String::new(),
+                });
+                Ok(response)
+            }).await.unwrap();
+            if let Some(Ok(Message::Text(text))) = ws_stream.next().await {
+                subscriptions_ref.lock().unwrap().push(text.to_string());
+            }
+            for message in messages {
+                ws_stream.send(Message::Text(message.into())).await.unwrap();
+            }
+            if index + 1 == session_count {
+                while let Some(Ok(_)) = ws_stream.next().await {}
+            }
+        }
+    });
+
+    (ws_url, subscriptions, handshakes)
+}
@@ -0,0 +1,127 @@
+use ctb::backtest::{ledger::{ExitReason, TradeRecord}, monte_carlo::{percentile, run_monte_carlo, Distribution, MonteCarloConfig, Resampling}};
+
+// 자산 전체로 진입한 거래 (pnl은 직전 자산 기준)
+fn trade(entry_asset: f64, pnl_pct: f64) -> TradeRecord {
+    TradeRecord {
+        code: "KRW-BTC".to_string(),
+        entry_date: "2024-01-01T00:00:00".to_string(),
+        exit_date: "2024-01-01T00:01:00".to_string(),
+        entry_price: 100.0,
+        exit_price: 100.0 * (1.0 + pnl_pct),
+        entry_asset,
+        size: entry_asset / 100.0,
+        fees: 0.0,
+        pnl: entry_asset * pnl_pct,
+        pnl_pct,
+        exit_reason: ExitReason::StrategySell,
+        mae_pct: 0.0,
+        mfe_pct: 0.0,
+        entry_reason: "".to_string(),
+        sell_reason: None,
+    }
+}
+
+// 1,000,000에서 +10%, -20%, +5% 복리
+fn create_trades() -> Vec<TradeRecord> {
+    vec![trade(1000000.0, 0.1), trade(1100000.0, -0.2), trade(880000.0, 0.05)]
+}
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!((actual - expected).abs() < 1e-6, "actual: {}, expected: {}", actual, expected);
+}
+
+#[test]
+fn test_percentile() {
+    let sorted = [1.0, 2.0, 3.0, 4.0, 5.0];
+    assert_close(percentile(&sorted, 0.0), 1.0);
+    assert_close(percentile(&sorted, 0.5), 3.0);
+    assert_close(percentile(&sorted, 0.25), 2.0);
+    assert_close(percentile(&sorted, 0.95), 4.8);
+    assert_close(percentile(&[], 0.5), 0.0);
+
+    let distribution = Distribution::new(&[5.0, 1.0, 3.0, 2.0, 4.0]);
+    assert_close(distribution.mean, 3.0);
+    assert_close(distribution.min, 1.0);
+    assert_close(distribution.max, 5.0);
+}
+
+#[test]
+fn test_shuffle_keeps_final_equity() {
+    let mut config = MonteCarloConfig::new();
+    config.iterations = 200;
+    let report = run_monte_carlo(&create_trades(), &config);
+
+    // 순서만 바뀌므로 최종 자산은 항상 같고 낙폭만 달라짐
+    assert_eq!(report.runs.len(), 200);
+    assert_close(report.final_equity.min, 924000.0);
+    assert_close(report.final_equity.max, 924000.0);
+    assert_close(report.total_return_pct.p50, -0.076);
+    // 손실 거래가 하나뿐이므로 어떤 순서든 최대 낙폭은 20%
+    assert!(report.max_drawdown_pct.max <= 0.2 + 1e-9);
+    assert!(report.max_drawdown_pct.min >= 0.2 - 1e-9);
+    assert_eq!(report.ruin_probability, 0.0);
+
+    assert_eq!(report.equity_bands.len(), 4);
+    assert_close(report.equity_bands[0].p50, 1000000.0);
+    assert_close(report.equity_bands[3].p50, 924000.0);
+}
+
+#[test]
+fn test_bootstrap_and_ruin() {
+    let mut config = MonteCarloConfig::new();
+    config.iterations = 500;
+    config.resampling = Resampling::Bootstrap;
+    config.seed = 42;
+    config.ruin_equity_pct = 0.8;
+    let report = run_monte_carlo(&create_trades(), &config);
+
+    // 다시 뽑으면 최종 자산이 달라짐 (최대 1.1^3, 최소 0.8^3)
+    assert!(report.final_equity.max > report.final_equity.min);
+    assert!(report.final_equity.max <= 1331000.0 + 1e-6);
+    assert!(report.final_equity.min >= 512000.0 - 1e-6);
+    // -20% 거래가 두 번 이상 나오면 파산 기준 (800,000) 이하
+    assert!(report.ruin_probability > 0.0 && report.ruin_probability < 1.0);
+
+    // 같은 seed면 같은 결과
+    let again = run_monte_carlo(&create_trades(), &config);
+    assert_eq!(again.runs, report.runs);
+}
+
+#[test]
+fn test_slippage_noise_lowers_equity() {
+    let mut config = MonteCarloConfig::new();
+    config.iterations = 100;
+    config.slippage_noise_pct = 0.01;
+    let report = run_monte_carlo(&create_trades(), &config);
+    assert!(report.final_equity.max < 924000.0);
+    // 진입마다 최대 1% 불리 -> 최대 3번
+    assert!(report.final_equity.min > 924000.0 * 0.97 * 0.99);
+}
+
+#[test]
+fn test_csv_output() {
+    let mut config = MonteCarloConfig::new();
+    config.iterations = 10;
+    let report = run_monte_carlo(&create_trades(), &config);
+
+    let mut summary = Vec::new();
+    report.write_summary_csv(&mut summary).unwrap();
+    let summary = String::from_utf8(summary).unwrap();
+    let lines = summary.lines().collect::<Vec<&str>>();
+    assert_eq!(lines[0], "metric,mean,min,p5,p25,p50,p75,p95,max");
+    assert!(lines[1].starts_with("final_equity,"));
+    assert_eq!(lines[4], "ruin_probability,0,,,,,,,");
+
+    let mut bands = Vec::new();
+    report.write_bands_csv(&mut bands).unwrap();
+    assert_eq!(String::from_utf8(bands).unwrap().lines().count(), 5);
+
+    let mut runs = Vec::new();
+    report.write_runs_csv(&mut runs).unwrap();
+    assert_eq!(String::from_utf8(runs).unwrap().lines().count(), 11);
+
+    // 거래가 없으면 시작 자산 그대로
+    let report = run_monte_carlo(&[], &config);
+    assert_close(report.final_equity.p50, 1000000.0);
+    assert_eq!(report.equity_bands.len(), 1);
+}
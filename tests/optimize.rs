@@ -0,0 +1,128 @@
+use ctb::{backtest::{fee::FeeSchedule, optimize::{grid_search, random_search, Objective, OptimizeConfig, ParamRange, ParamSpace}},
+core::candle::{Candle, CandleBase}, strategy::registry::{create_strategy_with_params, StrategyParamError}};
+use rand::{rngs::StdRng, SeedableRng};
+
+fn create_candles(count: usize) -> Vec<Candle> {
+    (0..count).map(|i| {
+        // 완만한 상승 추세 위의 진동
+        let price = 1000.0 + i as f64 * 0.5 + (i as f64 * 0.3).sin() * 20.0;
+        let opening = price - (i as f64 * 0.7).cos() * 5.0;
+        Candle {
+            base: CandleBase {
+                market: "KRW-BTC".to_string(),
+                candle_date_time_utc: format!("2024-01-{:02}T{:02}:{:02}:00", 1 + i / 1440, (i / 60) % 24, i % 60),
+                candle_date_time_kst: String::new(),
+                opening_price: opening,
+                high_price: price.max(opening) + 3.0,
+                low_price: price.min(opening) - 3.0,
+                trade_price: price,
+                timestamp: 0,
+                candle_acc_trade_price: 1000000.0,
+                candle_acc_trade_volume: 1000.0 + (i % 7) as f64 * 300.0,
+            }
+        }
+    }).collect()
+}
+
+fn create_space() -> ParamSpace {
+    let mut space = ParamSpace::new();
+    space.add(ParamRange::new("rsi_period", vec![7.0, 14.0]))
+        .add(ParamRange::step("min_weight_for_buy", 0.5, 1.5, 0.5));
+    space
+}
+
+fn create_config(objective: Objective) -> OptimizeConfig {
+    let mut config = OptimizeConfig::new("KRW-BTC", "candle_pattern", objective);
+    config.params.fees = FeeSchedule::flat(0.0005);
+    config
+}
+
+#[test]
+fn test_param_space_grid() {
+    assert_eq!(ParamRange::step("x", 0.1, 0.3, 0.1).values, vec![0.1, 0.2, 0.3]);
+    assert!(ParamRange::step("x", 0.0, 1.0, 0.0).values.is_empty());
+
+    let space = create_space();
+    assert_eq!(space.size(), 6);
+    let grid = space.grid();
+    assert_eq!(grid.len(), 6);
+    assert_eq!(grid[0], vec![("rsi_period".to_string(), 7.0), ("min_weight_for_buy".to_string(), 0.5)]);
+    assert_eq!(grid[1], vec![("rsi_period".to_string(), 7.0), ("min_weight_for_buy".to_string(), 1.0)]);
+    assert_eq!(grid[5], vec![("rsi_period".to_string(), 14.0), ("min_weight_for_buy".to_string(), 1.5)]);
+
+    // 파라미터가 없으면 기본 설정 하나
+    assert_eq!(ParamSpace::new().grid(), vec![vec![]]);
+}
+
+#[test]
+fn test_param_space_sample() {
+    let space = create_space();
+    let a = space.sample(4, &mut StdRng::seed_from_u64(7));
+    let b = space.sample(4, &mut StdRng::seed_from_u64(7));
+    assert_eq!(a, b);
+    assert_eq!(a.len(), 4);
+    for (i, set) in a.iter().enumerate() {
+        assert!(!a[i + 1..].contains(set), "duplicate sample: {:?}", set);
+    }
+    assert_eq!(space.sample(100, &mut StdRng::seed_from_u64(7)).len(), 6);
+}
+
+#[test]
+fn test_create_strategy_with_params() {
+    let params = vec![("rsi_period".to_string(), 10.0)];
+    assert!(create_strategy_with_params("candle_pattern", &params, false).is_ok());
+    assert!(create_strategy_with_params("swc", &[("bb_period".to_string(), 30.0)], false).is_ok());
+    assert!(create_strategy_with_params("vwap", &[], false).is_ok());
+
+    assert_eq!(create_strategy_with_params("candle_pattern", &[("unknown".to_string(), 1.0)], false).err(),
+        Some(StrategyParamError::UnknownParam { strategy: "candle_pattern".to_string(), param: "unknown".to_string() }));
+    assert_eq!(create_strategy_with_params("vwap", &params, false).err(),
+        Some(StrategyParamError::UnknownParam { strategy: "vwap".to_string(), param: "rsi_period".to_string() }));
+    assert_eq!(create_strategy_with_params("nope", &[], false).err(), Some(StrategyParamError::UnknownStrategy("nope".to_string())));
+}
+
+#[test]
+fn test_grid_search_ranks_results() {
+    let candles = create_candles(600);
+    let results = grid_search(&candles, &create_space(), &create_config(Objective::TotalReturn)).unwrap();
+    assert_eq!(results.results.len(), 6);
+    assert!(results.results.windows(2).all(|w| w[0].score >= w[1].score));
+    let best = results.best().unwrap();
+    assert_eq!(best.score, best.report.total_return_pct);
+
+    // 같은 조합은 같은 결과 (병렬 실행과 무관)
+    let again = grid_search(&candles, &create_space(), &create_config(Objective::TotalReturn)).unwrap();
+    assert_eq!(again.best().unwrap().params, best.params);
+    assert_eq!(again.best().unwrap().report.final_asset, best.report.final_asset);
+
+    // 다른 지표로 다시 정렬
+    let mut results = results;
+    results.sort_by(Objective::Sharpe);
+    assert!(results.results.windows(2).all(|w| w[0].report.sharpe_ratio >= w[1].report.sharpe_ratio));
+
+    let mut csv = Vec::new();
+    results.write_csv(&mut csv).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    assert!(csv.starts_with("rsi_period,min_weight_for_buy,score,total_return_pct,"));
+    assert_eq!(csv.lines().count(), 7);
+}
+
+#[test]
+fn test_random_search_and_errors() {
+    let candles = create_candles(300);
+    let results = random_search(&candles, &create_space(), 3, 1, &create_config(Objective::ReturnOverDrawdown)).unwrap();
+    assert_eq!(results.results.len(), 3);
+
+    let mut space = create_space();
+    space.add(ParamRange::new("unknown", vec![1.0]));
+    assert!(matches!(grid_search(&candles, &space, &create_config(Objective::Sharpe)), Err(StrategyParamError::UnknownParam { .. })));
+}
+
+#[test]
+fn test_min_trades() {
+    let candles = create_candles(300);
+    let mut config = create_config(Objective::TotalReturn);
+    config.min_trades = u32::MAX;
+    let results = grid_search(&candles, &create_space(), &config).unwrap();
+    assert!(results.results.iter().all(|result| result.score == f64::NEG_INFINITY));
+}
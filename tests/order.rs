@@ -0,0 +1,164 @@
+mod mock_server;
+
+use std::collections::BTreeMap;
+
+use ctb::upbit_api::{client::UpbitClient, error::UpbitError, market_rules::MarketRuleError, order::{cancel_order, get_closed_orders, get_open_orders, get_order, place_order, OrderKey, OrderRequest, OrderSide, OrderState, OrderType, TimeInForce}};
+use hmac::{Hmac, Mac};
+use jwt::VerifyWithKey;
+use mock_server::{spawn_mock_server, MockResponse};
+use sha2::{Digest, Sha512};
+
+const SECRET_KEY: &str = "test-secret";
+
+const ORDER_RESPONSE: &str = r#"{
+    "uuid": "cdd92199-2897-4e14-9448-f923320408ad",
+    "side": "bid",
+    "ord_type": "limit",
+    "price": "100.0",
+    "state": "wait",
+    "market": "KRW-BTC",
+    "created_at": "2018-04-10T15:42:23+09:00",
+    "volume": "0.01",
+    "remaining_volume": "0.01",
+    "reserved_fee": "0.0015",
+    "remaining_fee": "0.0015",
+    "paid_fee": "0.0",
+    "locked": "1.0015",
+    "executed_volume": "0.0",
+    "trades_count": 0
+}"#;
+
+fn query_hash_of(authorization: &str) -> String {
+    let token = authorization.trim_start_matches("Bearer ");
+    let key: Hmac<Sha512> = Hmac::new_from_slice(SECRET_KEY.as_bytes()).unwrap();
+    let claims: BTreeMap<String, serde_json::Value> = token.verify_with_key(&key).unwrap();
+    assert_eq!(claims["access_key"], "test-access");
+    claims["query_hash"].as_str().unwrap().to_string()
+}
+
+fn sha512_hex(query: &str) -> String {
+    let mut hasher = Sha512::new();
+    hasher.update(query);
+    hex::encode(hasher.finalize())
+}
+
+#[test]
+fn test_order_request_query_string() {
+    let request = OrderRequest::limit("KRW-BTC", OrderSide::Bid, 0.01, 100.0);
+    assert_eq!(request.to_query_string(), "market=KRW-BTC&side=bid&volume=0.01&price=100&ord_type=limit");
+
+    let request = OrderRequest::market_buy("KRW-BTC", 5000.0);
+    assert_eq!(request.to_query_string(), "market=KRW-BTC&side=bid&price=5000&ord_type=price");
+
+    let request = OrderRequest::market_sell("KRW-BTC", 0.5);
+    assert_eq!(request.to_query_string(), "market=KRW-BTC&side=ask&volume=0.5&ord_type=market");
+
+    let request = OrderRequest::best_sell("KRW-BTC", 0.5, TimeInForce::Ioc).with_identifier("my-order");
+    assert_eq!(request.to_query_string(), "market=KRW-BTC&side=ask&volume=0.5&ord_type=best&identifier=my-order&time_in_force=ioc");
+
+    let request = OrderRequest::best_sell("KRW-BTC", 0.5, TimeInForce::Ioc).with_identifier("a&b=c d");
+    assert_eq!(request.to_query_string(), "market=KRW-BTC&side=ask&volume=0.5&ord_type=best&identifier=a%26b%3Dc+d&time_in_force=ioc");
+    assert_eq!(request.to_body()["identifier"], "a&b=c d");
+
+    let request = OrderRequest::limit("KRW-BTC", OrderSide::Ask, 1.0, 200.0).with_time_in_force(TimeInForce::Fok);
+    assert_eq!(request.to_body()["time_in_force"], "fok");
+}
+
+#[tokio::test]
+async fn test_place_order() {
+    let (base_url, captured) = spawn_mock_server(vec![MockResponse::json(201, ORDER_RESPONSE)]).await;
+    let client = UpbitClient::new(&base_url, "test-access", SECRET_KEY);
+
+    let request = OrderRequest::limit("KRW-BTC", OrderSide::Bid, 0.01, 1000000.0);
+    let order = place_order(&client, &request).await.unwrap();
+    assert_eq!(order.uuid, "cdd92199-2897-4e14-9448-f923320408ad");
+    assert_eq!(order.ord_type, OrderType::Limit);
+    assert_eq!(order.state, OrderState::Wait);
+    assert_eq!(order.price, Some(100.0));
+    assert_eq!(order.locked, 1.0015);
+
+    let captured = captured.lock().unwrap();
+    let received = &captured[0];
+    assert_eq!(received.method, "POST");
+    assert_eq!(received.path, "/orders");
+    let body: serde_json::Value = serde_json::from_str(&received.body).unwrap();
+    assert_eq!(body["market"], "KRW-BTC");
+    assert_eq!(body["ord_type"], "limit");
+    assert_eq!(query_hash_of(received.header("Authorization").unwrap()), sha512_hex(&request.to_query_string()));
+}
+
+#[tokio::test]
+async fn test_cancel_and_get_order() {
+    let (base_url, captured) = spawn_mock_server(vec![
+        MockResponse::json(200, ORDER_RESPONSE),
+        MockResponse::json(200, ORDER_RESPONSE),
+        MockResponse::json(200, ORDER_RESPONSE),
+    ]).await;
+    let client = UpbitClient::new(&base_url, "test-access", SECRET_KEY);
+
+    let key = OrderKey::Uuid("cdd92199-2897-4e14-9448-f923320408ad".to_string());
+    cancel_order(&client, &key).await.unwrap();
+    get_order(&client, &OrderKey::Identifier("my-order".to_string())).await.unwrap();
+    get_order(&client, &OrderKey::Identifier("a&b=c d".to_string())).await.unwrap();
+
+    let captured = captured.lock().unwrap();
+    assert_eq!(captured[0].method, "DELETE");
+    assert_eq!(captured[0].path, "/order?uuid=cdd92199-2897-4e14-9448-f923320408ad");
+    assert_eq!(query_hash_of(captured[0].header("Authorization").unwrap()), sha512_hex("uuid=cdd92199-2897-4e14-9448-f923320408ad"));
+    assert_eq!(captured[1].method, "GET");
+    assert_eq!(captured[1].path, "/order?identifier=my-order");
+    assert_eq!(captured[2].path, "/order?identifier=a%26b%3Dc+d");
+    assert_eq!(query_hash_of(captured[2].header("Authorization").unwrap()), sha512_hex("identifier=a%26b%3Dc+d"));
+}
+
+#[tokio::test]
+async fn test_get_open_and_closed_orders() {
+    let list_response = format!("[{}]", ORDER_RESPONSE);
+    let (base_url, captured) = spawn_mock_server(vec![
+        MockResponse::json(200, &list_response),
+        MockResponse::json(200, &list_response),
+    ]).await;
+    let client = UpbitClient::new(&base_url, "test-access", SECRET_KEY);
+
+    let open_orders = get_open_orders(&client, Some("KRW-BTC"), 100).await.unwrap();
+    assert_eq!(open_orders.len(), 1);
+    let closed_orders = get_closed_orders(&client, Some("KRW-BTC"), &[OrderState::Done, OrderState::Cancel], 100).await.unwrap();
+    assert_eq!(closed_orders.len(), 1);
+
+    let captured = captured.lock().unwrap();
+    assert_eq!(captured[0].path, "/orders/open?market=KRW-BTC&limit=100");
+    assert_eq!(query_hash_of(captured[1].header("Authorization").unwrap()),
+        sha512_hex("market=KRW-BTC&states[]=done&states[]=cancel&limit=100"));
+}
+
+#[tokio::test]
+async fn test_order_error_response() {
+    let error_body = r#"{"error":{"name":"insufficient_funds_bid","message":"주문가능한 금액(KRW)이 부족합니다."}}"#;
+    let (base_url, _) = spawn_mock_server(vec![MockResponse::json(400, error_body)]).await;
+    let client = UpbitClient::new(&base_url, "test-access", SECRET_KEY);
+
+    let result = place_order(&client, &OrderRequest::market_buy("KRW-BTC", 5000.0)).await;
+    match result.unwrap_err() {
+        UpbitError::Http { status, error, .. } => {
+            assert_eq!(status, 400);
+            assert_eq!(error.unwrap().name, "insufficient_funds_bid");
+        }
+        e => panic!("unexpected error: {:?}", e),
+    }
+}
+
+#[tokio::test]
+async fn test_place_order_rejects_invalid_order() {
+    let (base_url, captured) = spawn_mock_server(vec![MockResponse::json(201, ORDER_RESPONSE)]).await;
+    let client = UpbitClient::new(&base_url, "test-access", SECRET_KEY);
+
+    // 최소 주문 금액 미만
+    let result = place_order(&client, &OrderRequest::limit("KRW-BTC", OrderSide::Bid, 0.01, 100.0)).await;
+    assert!(matches!(result, Err(UpbitError::InvalidOrder(MarketRuleError::BelowMinimumOrder { .. }))));
+    // 호가 단위 위반
+    let result = place_order(&client, &OrderRequest::limit("KRW-BTC", OrderSide::Bid, 0.01, 2000300.0)).await;
+    assert!(matches!(result, Err(UpbitError::InvalidOrder(MarketRuleError::InvalidTickSize { .. }))));
+
+    // 요청을 보내지 않음
+    assert!(captured.lock().unwrap().is_empty());
+}
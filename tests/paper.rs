@@ -0,0 +1,408 @@
+use std::{cell::RefCell, rc::Rc};
+
+use ctb::{backtest::{paper::{PaperBroker, PaperTrader}, risk::{RiskConfig, RiskManager}, sizing::PositionSizing},
+core::{candle::{Candle, CandleBase}, order_event::OrderEvent, orderbook::{Orderbook, OrderbookUnit}, position::PositionState, signal::Signal, trade::{AskBid, Change, StreamType, Trade}},
+strategy::lib::Strategy,
+upbit_api::{error::UpbitError, order::{OrderKey, OrderRequest, OrderSide, OrderState, TimeInForce}}};
+
+// 2024-01-01T00:00:00Z
+const BASE_TIMESTAMP: i64 = 1704067200000;
+
+fn create_orderbook(units: Vec<OrderbookUnit>) -> Orderbook {
+    Orderbook {
+        orderbook_type: "orderbook".to_string(),
+        code: "KRW-BTC".to_string(),
+        total_ask_size: units.iter().map(|unit| unit.ask_size).sum(),
+        total_bid_size: units.iter().map(|unit| unit.bid_size).sum(),
+        orderbook_units: units,
+        timestamp: BASE_TIMESTAMP,
+        level: 0,
+    }
+}
+
+fn default_orderbook() -> Orderbook {
+    create_orderbook(vec![
+        OrderbookUnit { ask_price: 50010.0, bid_price: 50000.0, ask_size: 0.1, bid_size: 0.3 },
+        OrderbookUnit { ask_price: 50020.0, bid_price: 49990.0, ask_size: 0.2, bid_size: 0.5 },
+        OrderbookUnit { ask_price: 50030.0, bid_price: 49980.0, ask_size: 1.0, bid_size: 1.0 },
+    ])
+}
+
+fn create_trade(price: f64, volume: f64, ask_bid: AskBid) -> Trade {
+    Trade {
+        trade_type: "trade".to_string(),
+        code: "KRW-BTC".to_string(),
+        trade_price: price,
+        trade_volume: volume,
+        ask_bid,
+        prev_closing_price: 0.0,
+        change: Change::Even,
+        change_price: 0.0,
+        trade_date: "".to_string(),
+        trade_time: "".to_string(),
+        trade_timestamp: BASE_TIMESTAMP + 1000,
+        timestamp: BASE_TIMESTAMP + 1000,
+        sequential_id: 0,
+        best_ask_price: 0.0,
+        best_ask_size: 0.0,
+        best_bid_price: 0.0,
+        best_bid_size: 0.0,
+        stream_type: StreamType::Realtime,
+    }
+}
+
+fn create_broker() -> PaperBroker {
+    let mut broker = PaperBroker::new("KRW", 1000000.0);
+    broker.on_orderbook(&default_orderbook());
+    broker
+}
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!((actual - expected).abs() < 1e-6, "actual: {}, expected: {}", actual, expected);
+}
+
+fn account(broker: &PaperBroker, currency: &str) -> (f64, f64) {
+    broker.accounts().iter().find(|account| account.currency == currency)
+        .map(|account| (account.balance, account.locked))
+        .unwrap_or((0.0, 0.0))
+}
+
+// 체결 이벤트의 (가격, 수량). 수량은 부동소수점 오차를 없애려고 소수점 8자리로 반올림
+fn fills(events: &[OrderEvent]) -> Vec<(f64, f64)> {
+    events.iter().filter_map(|event| match event {
+        OrderEvent::Fill { trade, .. } => Some((trade.price, (trade.volume * 1e8).round() / 1e8)),
+        OrderEvent::Cancel(_) => None,
+    }).collect()
+}
+
+#[test]
+fn test_market_orders_walk_the_book() {
+    let mut broker = create_broker();
+
+    // 50010에 0.1 (5001), 50020에 0.1 (5002)
+    let order = broker.place_order(&OrderRequest::market_buy("KRW-BTC", 10003.0)).unwrap();
+    assert_eq!(order.state, OrderState::Done);
+    assert_eq!(order.trades_count, 2);
+    assert_close(order.executed_volume, 0.2);
+    assert_close(order.paid_fee, 10003.0 * 0.0005);
+
+    let events = broker.take_events("KRW-BTC");
+    assert_eq!(fills(&events).len(), 2);
+    assert_eq!(events[0].order().state, OrderState::Wait);
+    assert_eq!(events[1].order().state, OrderState::Done);
+    assert!(broker.take_events("KRW-BTC").is_empty());
+
+    let (krw, krw_locked) = account(&broker, "KRW");
+    assert_close(krw, 1000000.0 - 10003.0 * 1.0005);
+    assert_close(krw_locked, 0.0);
+    let btc = broker.accounts().into_iter().find(|account| account.currency == "BTC").unwrap();
+    assert_close(btc.balance, 0.2);
+    assert_close(btc.avg_buy_price, 50015.0);
+
+    // 매도는 매수 호가에 체결
+    let order = broker.place_order(&OrderRequest::market_sell("KRW-BTC", 0.05)).unwrap();
+    assert_eq!(order.state, OrderState::Done);
+    assert_eq!(fills(&broker.take_events("KRW-BTC")), vec![(50000.0, 0.05)]);
+    assert_close(account(&broker, "KRW").0, 1000000.0 - 10003.0 * 1.0005 + 2500.0 * 0.9995);
+    assert_close(account(&broker, "BTC").0, 0.15);
+}
+
+#[test]
+fn test_resting_limit_order_respects_queue() {
+    let mut broker = create_broker();
+
+    // 50000 매수 호가에 0.3이 먼저 대기 중
+    let order = broker.place_order(&OrderRequest::limit("KRW-BTC", OrderSide::Bid, 0.2, 50000.0)).unwrap();
+    assert_eq!(order.state, OrderState::Wait);
+    assert_close(order.locked, 10000.0 * 1.0005);
+    assert_eq!(account(&broker, "KRW"), (1000000.0 - 10005.0, 10005.0));
+
+    // 앞선 0.3 중 0.2만 소진, 매수 체결은 매수 호가를 소진하지 않음
+    broker.on_trade(&create_trade(50000.0, 0.2, AskBid::Ask));
+    broker.on_trade(&create_trade(50000.0, 1.0, AskBid::Bid));
+    broker.on_trade(&create_trade(50010.0, 1.0, AskBid::Bid));
+    assert!(broker.take_events("KRW-BTC").is_empty());
+
+    // 남은 대기 0.1을 넘는 0.15만 체결
+    broker.on_trade(&create_trade(50000.0, 0.25, AskBid::Ask));
+    let events = broker.take_events("KRW-BTC");
+    assert_eq!(fills(&events), vec![(50000.0, 0.15)]);
+    assert!(matches!(&events[0], OrderEvent::Fill { order, .. } if order.state == OrderState::Wait));
+    assert_close(broker.open_orders(Some("KRW-BTC"))[0].remaining_volume.unwrap(), 0.05);
+
+    // 가격을 뚫고 내려가면 남은 수량 전부 주문 가격에 체결
+    broker.on_trade(&create_trade(49990.0, 0.01, AskBid::Ask));
+    let events = broker.take_events("KRW-BTC");
+    assert_eq!(fills(&events), vec![(50000.0, 0.05)]);
+    assert_eq!(events[0].order().state, OrderState::Done);
+    assert!(broker.open_orders(None).is_empty());
+    assert_eq!(account(&broker, "KRW"), (1000000.0 - 10005.0, 0.0));
+    assert_close(account(&broker, "BTC").0, 0.2);
+}
+
+#[test]
+fn test_orderbook_update_shrinks_queue() {
+    let mut broker = create_broker();
+    broker.place_order(&OrderRequest::limit("KRW-BTC", OrderSide::Bid, 0.2, 49990.0)).unwrap();
+
+    // 앞선 0.5 중 0.4가 취소됨
+    broker.on_orderbook(&create_orderbook(vec![
+        OrderbookUnit { ask_price: 50010.0, bid_price: 50000.0, ask_size: 0.1, bid_size: 0.3 },
+        OrderbookUnit { ask_price: 50020.0, bid_price: 49990.0, ask_size: 0.2, bid_size: 0.1 },
+    ]));
+    broker.on_trade(&create_trade(49990.0, 0.15, AskBid::Ask));
+    assert_eq!(fills(&broker.take_events("KRW-BTC")), vec![(49990.0, 0.05)]);
+
+    // 지정가 매도는 더 높은 가격의 체결에 전부 체결
+    broker.deposit("BTC", 1.0);
+    let order = broker.place_order(&OrderRequest::limit("KRW-BTC", OrderSide::Ask, 0.5, 50050.0)).unwrap();
+    // 앞에서 체결된 0.05는 주문 가능 잔고에 남음
+    let btc = account(&broker, "BTC");
+    assert_close(btc.0, 0.55);
+    assert_close(btc.1, 0.5);
+    broker.on_trade(&create_trade(50060.0, 0.01, AskBid::Bid));
+    assert_eq!(fills(&broker.take_events("KRW-BTC")), vec![(50050.0, 0.5)]);
+    assert_eq!(broker.get_order(&OrderKey::Uuid(order.uuid)).unwrap().state, OrderState::Done);
+}
+
+#[test]
+fn test_time_in_force() {
+    let mut broker = create_broker();
+
+    // IOC: 50020 이하 호가 0.3만 체결하고 나머지 취소
+    let order = broker.place_order(&OrderRequest::limit("KRW-BTC", OrderSide::Bid, 0.5, 50020.0).with_time_in_force(TimeInForce::Ioc)).unwrap();
+    assert_eq!(order.state, OrderState::Cancel);
+    assert_close(order.executed_volume, 0.3);
+    let events = broker.take_events("KRW-BTC");
+    assert_eq!(fills(&events), vec![(50010.0, 0.1), (50020.0, 0.2)]);
+    assert!(matches!(events.last().unwrap(), OrderEvent::Cancel(_)));
+    assert_close(account(&broker, "KRW").1, 0.0);
+
+    // FOK: 전부 체결할 수 없으면 체결 없이 취소
+    let order = broker.place_order(&OrderRequest::limit("KRW-BTC", OrderSide::Bid, 0.5, 50020.0).with_time_in_force(TimeInForce::Fok)).unwrap();
+    assert_eq!(order.state, OrderState::Cancel);
+    assert_eq!(order.executed_volume, 0.0);
+    assert_eq!(broker.take_events("KRW-BTC").len(), 1);
+
+    // 최유리 매도는 최우선 매수 호가에서만 체결
+    broker.deposit("BTC", 1.0);
+    let order = broker.place_order(&OrderRequest::best_sell("KRW-BTC", 0.5, TimeInForce::Ioc)).unwrap();
+    assert_close(order.executed_volume, 0.3);
+    assert_eq!(fills(&broker.take_events("KRW-BTC")), vec![(50000.0, 0.3)]);
+    assert_close(account(&broker, "BTC").0, 1.0);
+}
+
+#[test]
+fn test_cancel_and_errors() {
+    let mut broker = create_broker();
+    let order = broker.place_order(&OrderRequest::limit("KRW-BTC", OrderSide::Bid, 0.2, 49000.0).with_identifier("my-order")).unwrap();
+    assert_eq!(broker.get_order(&OrderKey::Identifier("my-order".to_string())).unwrap().uuid, order.uuid);
+
+    let canceled = broker.cancel_order(&OrderKey::Uuid(order.uuid.clone())).unwrap();
+    assert_eq!(canceled.state, OrderState::Cancel);
+    assert!(matches!(broker.take_events("KRW-BTC").as_slice(), [OrderEvent::Cancel(_)]));
+    assert_eq!(account(&broker, "KRW"), (1000000.0, 0.0));
+
+    let error_name = |result: Result<_, UpbitError>| result.err().and_then(|e| e.error_name().map(str::to_string));
+    assert_eq!(error_name(broker.cancel_order(&OrderKey::Uuid(order.uuid))).as_deref(), Some("order_not_cancellable"));
+    assert_eq!(error_name(broker.cancel_order(&OrderKey::Uuid("unknown".to_string()))).as_deref(), Some("order_not_found"));
+    assert_eq!(error_name(broker.place_order(&OrderRequest::market_buy("KRW-BTC", 2000000.0))).as_deref(), Some("insufficient_funds_bid"));
+    assert_eq!(error_name(broker.place_order(&OrderRequest::market_sell("KRW-BTC", 0.1))).as_deref(), Some("insufficient_funds_ask"));
+    // 호가 단위 위반은 주문 규칙 에러
+    assert!(matches!(broker.place_order(&OrderRequest::limit("KRW-BTC", OrderSide::Bid, 0.1, 50005.0)), Err(UpbitError::InvalidOrder(_))));
+    // 호가도 체결가도 없는 마켓의 시장가 주문
+    assert_eq!(error_name(broker.place_order(&OrderRequest::market_buy("KRW-ETH", 10000.0))).as_deref(), Some("market_not_ready"));
+    assert_eq!(account(&broker, "KRW"), (1000000.0, 0.0));
+}
+
+// 첫 캔들에 진입하고 받은 주문 이벤트를 기록하는 전략
+struct EntryStrategy {
+    entered: bool,
+    events: Rc<RefCell<Vec<(OrderSide, OrderState)>>>,
+}
+
+impl Strategy for EntryStrategy {
+    fn name(&self) -> &str {
+        "entry"
+    }
+
+    fn on_candle(&mut self, _candle: &Candle, _position: &mut PositionState) -> Signal {
+        if self.entered {
+            return Signal::Hold;
+        }
+        self.entered = true;
+        Signal::Buy { reason: "test".to_string(), initial_trailing_stop: 49500.0, take_profit: 50500.0, asset_pct: 0.5 }
+    }
+
+    fn on_order_event(&mut self, event: &OrderEvent, _position: &mut PositionState) -> Signal {
+        self.events.borrow_mut().push((event.order().side, event.order().state));
+        Signal::Hold
+    }
+}
+
+fn create_trader() -> (PaperTrader, Rc<RefCell<Vec<(OrderSide, OrderState)>>>) {
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let strategy = EntryStrategy { entered: false, events: events.clone() };
+    (PaperTrader::new("KRW-BTC", Box::new(strategy), false), events)
+}
+
+fn create_candle() -> Candle {
+    Candle {
+        base: CandleBase {
+            market: "KRW-BTC".to_string(),
+            candle_date_time_utc: "2024-01-01T00:00:00".to_string(),
+            candle_date_time_kst: "2024-01-01T09:00:00".to_string(),
+            opening_price: 50000.0,
+            high_price: 50000.0,
+            low_price: 50000.0,
+            trade_price: 50000.0,
+            timestamp: 0,
+            candle_acc_trade_price: 0.0,
+            candle_acc_trade_volume: 0.0,
+        }
+    }
+}
+
+#[test]
+fn test_trader_enters_and_takes_profit_with_resting_order() {
+    let mut broker = PaperBroker::new("KRW", 1000000.0);
+    let (mut trader, events) = create_trader();
+    trader.on_orderbook(&mut broker, &default_orderbook());
+    trader.on_candle(&mut broker, &create_candle());
+
+    // 시장가 매수 체결 후 보유 수량 전체로 익절가 지정가 매도
+    // 소수점 8자리 아래 수량만 남음
+    let btc = account(&broker, "BTC");
+    assert!(btc.0 < 1e-8);
+    assert!(btc.1 > 0.0);
+    assert_close(trader.position.size(), btc.1);
+    let open_orders = broker.open_orders(Some("KRW-BTC"));
+    assert_eq!(open_orders.len(), 1);
+    assert_eq!((open_orders[0].side, open_orders[0].price), (OrderSide::Ask, Some(50500.0)));
+    assert_eq!(trader.take_profit_order(), Some(open_orders[0].uuid.as_str()));
+    assert!(matches!(trader.position, PositionState::InPosition { take_profit_price: 50500.0, trailing_stop_price: 49500.0, .. }));
+
+    // 익절가 위에서 체결되면 익절 주문 체결로 포지션 종료
+    trader.on_trade(&mut broker, &create_trade(50510.0, 0.01, AskBid::Bid));
+    assert!(matches!(trader.position, PositionState::None));
+    assert!(trader.take_profit_order().is_none());
+    assert!(broker.open_orders(None).is_empty());
+    assert!(broker.equity("KRW") > 1000000.0);
+    assert_eq!(events.borrow().last(), Some(&(OrderSide::Ask, OrderState::Done)));
+    assert!(events.borrow().iter().any(|event| *event == (OrderSide::Bid, OrderState::Done)));
+}
+
+#[test]
+fn test_trader_trailing_stop_cancels_take_profit() {
+    let mut broker = PaperBroker::new("KRW", 1000000.0);
+    let (mut trader, events) = create_trader();
+    trader.on_orderbook(&mut broker, &default_orderbook());
+    trader.on_candle(&mut broker, &create_candle());
+    assert!(matches!(trader.position, PositionState::InPosition { .. }));
+
+    trader.on_trade(&mut broker, &create_trade(49400.0, 0.01, AskBid::Ask));
+    assert!(matches!(trader.position, PositionState::None));
+    assert!(broker.open_orders(None).is_empty());
+    let btc = account(&broker, "BTC");
+    assert!(btc.0 < 1e-8 && btc.1 == 0.0);
+    assert!(broker.equity("KRW") < 1000000.0);
+    // 익절 주문 취소 후 시장가 매도
+    let events = events.borrow();
+    let cancel_index = events.iter().position(|event| *event == (OrderSide::Ask, OrderState::Cancel)).unwrap();
+    assert!(events[cancel_index..].iter().any(|event| *event == (OrderSide::Ask, OrderState::Done)));
+}
+
+#[test]
+fn test_trader_sizing_and_returns() {
+    let mut broker = PaperBroker::new("KRW", 1000000.0);
+    let (mut trader, _) = create_trader();
+    trader.sizing = PositionSizing::FixedAmount { amount: 200000.0 };
+    trader.on_orderbook(&mut broker, &default_orderbook());
+    // 체결가가 있어야 진입 크기를 계산함
+    trader.on_trade(&mut broker, &create_trade(50000.0, 0.01, AskBid::Ask));
+    trader.on_candle(&mut broker, &create_candle());
+
+    // 신호의 50% 대신 200,000원 (수수료 포함) 매수
+    let krw = account(&broker, "KRW");
+    assert!((krw.0 - 800000.0).abs() < 1.0, "krw: {}", krw.0);
+
+    // 익절로 포지션이 끝나면 거래 손익률 기록
+    trader.on_trade(&mut broker, &create_trade(50510.0, 0.01, AskBid::Bid));
+    assert!(matches!(trader.position, PositionState::None));
+    assert_eq!(trader.returns.len(), 1);
+    assert!(trader.returns[0] > 0.0);
+}
+
+#[test]
+fn test_trader_applies_risk_limits() {
+    let mut broker = PaperBroker::new("KRW", 1000000.0);
+    let (mut trader, _) = create_trader();
+    trader.risk = Some(RiskManager::new(RiskConfig { max_consecutive_losses: Some(1), ..RiskConfig::new() }));
+    trader.on_orderbook(&mut broker, &default_orderbook());
+    trader.on_candle(&mut broker, &create_candle());
+    assert!(matches!(trader.position, PositionState::InPosition { .. }));
+
+    // 트레일링 스탑 청산 손실을 수수료까지 포함해 기록
+    trader.on_trade(&mut broker, &create_trade(49400.0, 0.01, AskBid::Ask));
+    assert!(matches!(trader.position, PositionState::None));
+    let risk = trader.risk.as_ref().unwrap();
+    assert_eq!(risk.consecutive_losses, 1);
+    assert!((risk.daily_pnl() - (broker.equity("KRW") - 1000000.0)).abs() < 0.01, "daily pnl: {}", risk.daily_pnl());
+    assert!(risk.cooldown_until.is_some());
+
+    // 연속 손실 대기 시간에는 진입하지 않음
+    let krw = account(&broker, "KRW").0;
+    let signal = Signal::Buy { reason: "test".to_string(), initial_trailing_stop: 49500.0, take_profit: 50500.0, asset_pct: 0.5 };
+    trader.handle_signal(&mut broker, &signal, "2024-01-01T00:10:00");
+    assert!(matches!(trader.position, PositionState::None));
+    assert_eq!(account(&broker, "KRW").0, krw);
+}
+
+#[test]
+fn test_trader_buys_on_btc_quote_market() {
+    let mut orderbook = create_orderbook(vec![
+        OrderbookUnit { ask_price: 0.05001, bid_price: 0.05, ask_size: 100.0, bid_size: 100.0 },
+    ]);
+    orderbook.code = "BTC-ETH".to_string();
+    let mut broker = PaperBroker::new("BTC", 1.0);
+    let mut trader = PaperTrader::new("BTC-ETH", Box::new(EntryStrategy { entered: true, events: Rc::default() }), false);
+    trader.on_orderbook(&mut broker, &orderbook);
+
+    // 1 BTC 미만의 시장가 매수 총액도 소수점 아래를 버리지 않음
+    let signal = Signal::Buy { reason: "test".to_string(), initial_trailing_stop: 0.049, take_profit: 0.0505, asset_pct: 0.5 };
+    trader.handle_signal(&mut broker, &signal, "2024-01-01T00:00:00");
+    assert!(matches!(trader.position, PositionState::InPosition { .. }));
+    let btc = account(&broker, "BTC");
+    assert!((btc.0 - 0.5).abs() < 1e-6, "btc: {}", btc.0);
+    assert!(trader.position.size() > 9.9);
+}
+
+#[test]
+fn test_trader_restore_replaces_take_profit_order() {
+    let mut broker = PaperBroker::new("KRW", 1000000.0);
+    let (mut trader, _) = create_trader();
+    trader.on_orderbook(&mut broker, &default_orderbook());
+    trader.on_candle(&mut broker, &create_candle());
+    assert!(trader.take_state_changed());
+    assert!(!trader.take_state_changed());
+    let state = trader.market_state();
+    assert_eq!(state.open_orders.len(), 1);
+
+    // 재시작한 broker에는 이전 익절 주문이 없으므로 보유 수량으로 다시 올림
+    let mut restarted = PaperBroker::new("KRW", 0.0);
+    restarted.deposit("BTC", state.position.size());
+    restarted.on_orderbook(&default_orderbook());
+    let mut restored = PaperTrader::new("KRW-BTC", Box::new(EntryStrategy { entered: true, events: Rc::default() }), false);
+    restored.restore(&mut restarted, &state);
+    assert!(matches!(restored.position, PositionState::InPosition { take_profit_price: 50500.0, trailing_stop_price: 49500.0, .. }));
+    let open_orders = restarted.open_orders(Some("KRW-BTC"));
+    assert_eq!(open_orders.len(), 1);
+    assert_eq!(restored.take_profit_order(), Some(open_orders[0].uuid.as_str()));
+    assert_ne!(restored.take_profit_order(), Some(state.open_orders[0].as_str()));
+    assert!(!restored.take_state_changed());
+
+    // 익절 체결로 포지션이 바뀌면 저장 필요
+    restored.on_trade(&mut restarted, &create_trade(50510.0, 0.01, AskBid::Bid));
+    assert!(matches!(restored.position, PositionState::None));
+    assert!(restored.take_state_changed());
+}
@@ -0,0 +1,207 @@
+use std::collections::HashMap;
+
+use ctb::{backtest::{fee::FeeSchedule, portfolio::{correlation, run_portfolio, simulate_portfolio, Allocation, Portfolio, PortfolioConfig}},
+core::{candle::{Candle, CandleBase, CandleTrait}, position::PositionState, signal::{Signal, SignalReason}}, strategy::{lib::Strategy, registry::StrategyParamError}};
+
+// 캔들 시간에 맞춰 정해진 신호를 내는 전략
+struct ScriptedStrategy {
+    signals: HashMap<String, Signal>,
+}
+
+impl ScriptedStrategy {
+    fn new(signals: Vec<(&str, Signal)>) -> Box<dyn Strategy> {
+        Box::new(Self { signals: signals.into_iter().map(|(date, signal)| (date.to_string(), signal)).collect() })
+    }
+}
+
+impl Strategy for ScriptedStrategy {
+    fn name(&self) -> &str {
+        "SCRIPTED"
+    }
+
+    fn on_candle(&mut self, candle: &Candle, _position: &mut PositionState) -> Signal {
+        self.signals.get(candle.get_candle_date_time_utc()).cloned().unwrap_or(Signal::Hold)
+    }
+}
+
+fn create_candle(code: &str, date: &str, price: f64) -> Candle {
+    Candle {
+        base: CandleBase {
+            market: code.to_string(),
+            candle_date_time_utc: date.to_string(),
+            candle_date_time_kst: date.to_string(),
+            opening_price: price,
+            high_price: price,
+            low_price: price,
+            trade_price: price,
+            timestamp: 0,
+            candle_acc_trade_price: 1000000.0,
+            candle_acc_trade_volume: 1000.0,
+        }
+    }
+}
+
+fn create_candles(code: &str, prices: &[f64]) -> (String, Vec<Candle>) {
+    let candles = prices.iter().enumerate()
+        .map(|(i, price)| create_candle(code, &format!("2024-01-01T00:{:02}:00", i), *price))
+        .collect();
+    (code.to_string(), candles)
+}
+
+fn buy(asset_pct: f64) -> Signal {
+    Signal::Buy {
+        reason: "breakout".to_string(),
+        initial_trailing_stop: 900.0,
+        take_profit: 2000.0,
+        asset_pct,
+    }
+}
+
+fn sell() -> Signal {
+    Signal::Sell(SignalReason { reason: "exit".to_string() })
+}
+
+fn create_config() -> PortfolioConfig {
+    let mut config = PortfolioConfig::new("SCRIPTED");
+    config.max_concurrent_positions = 3;
+    config.max_pct_per_market = 1.0;
+    config
+}
+
+fn create_portfolio(config: PortfolioConfig, codes: &[&str], strategies: Vec<Box<dyn Strategy>>) -> Portfolio {
+    let mut portfolio = Portfolio::new(config);
+    for (code, strategy) in codes.iter().zip(strategies) {
+        portfolio.add_market(code, strategy);
+        portfolio.markets.last_mut().unwrap().backtester.params.fees = FeeSchedule::flat(0.0);
+    }
+    portfolio
+}
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!((actual - expected).abs() < 1e-6, "actual: {}, expected: {}", actual, expected);
+}
+
+#[test]
+fn test_markets_share_cash() {
+    let mut portfolio = create_portfolio(create_config(), &["KRW-BTC", "KRW-ETH"], vec![
+        ScriptedStrategy::new(vec![("2024-01-01T00:00:00", buy(0.5))]),
+        ScriptedStrategy::new(vec![("2024-01-01T00:00:00", buy(0.5))]),
+    ]);
+    let markets = vec![create_candles("KRW-BTC", &[1000.0, 1100.0]), create_candles("KRW-ETH", &[1000.0, 1000.0])];
+    run_portfolio(&mut portfolio, &markets);
+
+    // BTC가 현금의 50% (500,000), ETH가 남은 현금의 50% (250,000) 사용
+    let btc = &portfolio.markets[0].backtester.trades;
+    let eth = &portfolio.markets[1].backtester.trades;
+    assert_close(btc[0].entry_asset, 500000.0);
+    assert_close(eth[0].entry_asset, 250000.0);
+
+    // 두 번째 시간: 250,000 + 550,000 + 250,000
+    assert_eq!(portfolio.equity_curve.len(), 2);
+    assert_close(portfolio.equity_curve[0].equity, 1000000.0);
+    assert_close(portfolio.equity_curve[1].equity, 1050000.0);
+    assert_close(portfolio.cash, 1050000.0);
+    assert_eq!(portfolio.open_positions(), 0);
+}
+
+#[test]
+fn test_max_concurrent_positions() {
+    let mut config = create_config();
+    config.max_concurrent_positions = 1;
+    let mut portfolio = create_portfolio(config, &["KRW-BTC", "KRW-ETH"], vec![
+        ScriptedStrategy::new(vec![("2024-01-01T00:00:00", buy(0.5)), ("2024-01-01T00:01:00", sell())]),
+        ScriptedStrategy::new(vec![("2024-01-01T00:00:00", buy(0.5)), ("2024-01-01T00:02:00", buy(0.5))]),
+    ]);
+    let markets = vec![create_candles("KRW-BTC", &[1000.0, 1000.0, 1000.0]), create_candles("KRW-ETH", &[1000.0, 1000.0, 1000.0])];
+    run_portfolio(&mut portfolio, &markets);
+
+    // 첫 시간에는 BTC만 진입, BTC 청산 후 ETH 진입
+    let eth = &portfolio.markets[1].backtester.trades;
+    assert_eq!(portfolio.markets[0].backtester.trades.len(), 1);
+    assert_eq!(eth.len(), 1);
+    assert_eq!(eth[0].entry_date, "2024-01-01T00:02:00");
+    assert_close(eth[0].entry_asset, 500000.0);
+}
+
+#[test]
+fn test_max_pct_per_market() {
+    let mut config = create_config();
+    config.max_pct_per_market = 0.3;
+    let mut portfolio = create_portfolio(config, &["KRW-BTC"], vec![
+        ScriptedStrategy::new(vec![
+            ("2024-01-01T00:00:00", buy(1.0)),
+            ("2024-01-01T00:01:00", Signal::AddToPosition { reason: "pyramid".to_string(), asset_pct: 1.0 }),
+        ]),
+    ]);
+    let markets = vec![create_candles("KRW-BTC", &[1000.0, 1000.0])];
+    run_portfolio(&mut portfolio, &markets);
+
+    // 평가액의 30%까지만 진입하고, 이미 한도를 채웠으므로 추가 진입은 무시
+    let trades = &portfolio.markets[0].backtester.trades;
+    assert_eq!(trades.len(), 1);
+    assert_close(trades[0].entry_asset, 300000.0);
+}
+
+#[test]
+fn test_equal_risk_allocation() {
+    let mut config = create_config();
+    config.allocation = Allocation::EqualRisk { risk_pct: 0.01 };
+    let mut portfolio = create_portfolio(config, &["KRW-BTC", "KRW-ETH"], vec![
+        ScriptedStrategy::new(vec![("2024-01-01T00:00:00", buy(1.0))]),
+        ScriptedStrategy::new(vec![("2024-01-01T00:00:00", Signal::Buy {
+            reason: "breakout".to_string(),
+            initial_trailing_stop: 950.0,
+            take_profit: 2000.0,
+            asset_pct: 1.0,
+        })]),
+    ]);
+    let markets = vec![create_candles("KRW-BTC", &[1000.0]), create_candles("KRW-ETH", &[1000.0])];
+    run_portfolio(&mut portfolio, &markets);
+
+    // 스탑 거리 10% -> 100,000, 5% -> 200,000 (스탑에 걸리면 둘 다 10,000 손실)
+    assert_close(portfolio.markets[0].backtester.trades[0].entry_asset, 100000.0);
+    assert_close(portfolio.markets[1].backtester.trades[0].entry_asset, 200000.0);
+}
+
+#[test]
+fn test_report_contribution_and_correlation() {
+    let mut portfolio = create_portfolio(create_config(), &["KRW-BTC", "KRW-ETH", "KRW-XRP"], vec![
+        ScriptedStrategy::new(vec![("2024-01-01T00:00:00", buy(0.5))]),
+        ScriptedStrategy::new(vec![("2024-01-01T00:00:00", buy(0.5))]),
+        ScriptedStrategy::new(vec![]),
+    ]);
+    let markets = vec![
+        create_candles("KRW-BTC", &[1000.0, 1100.0, 1050.0, 1200.0]),
+        create_candles("KRW-ETH", &[1000.0, 1000.0, 950.0, 1200.0]),
+        create_candles("KRW-XRP", &[1000.0, 900.0, 950.0, 800.0]),
+    ];
+    run_portfolio(&mut portfolio, &markets);
+    let report = portfolio.report();
+
+    assert_eq!(report.codes, vec!["KRW-BTC", "KRW-ETH", "KRW-XRP"]);
+    assert_close(report.markets[0].pnl, 100000.0);
+    assert_close(report.markets[0].contribution_pct, 0.1);
+    assert_close(report.markets[1].pnl, 50000.0);
+    assert_eq!(report.markets[2].trades, 0);
+    assert_eq!(report.report.total_trades, 2);
+    assert_close(report.report.final_asset, 1150000.0);
+
+    assert_close(report.correlation[0][0], 1.0);
+    assert_close(report.correlation[0][2], report.correlation[2][0]);
+    assert!(report.correlation[0][2] < 0.0);
+}
+
+#[test]
+fn test_correlation() {
+    assert_close(correlation(&[1.0, 2.0, 3.0], &[2.0, 4.0, 6.0]), 1.0);
+    assert_close(correlation(&[1.0, 2.0, 3.0], &[3.0, 2.0, 1.0]), -1.0);
+    assert_close(correlation(&[1.0, 2.0, 3.0], &[1.0, 1.0, 1.0]), 0.0);
+    assert_close(correlation(&[1.0], &[1.0]), 0.0);
+}
+
+#[test]
+fn test_simulate_portfolio_with_unknown_strategy() {
+    let markets = vec![("KRW-BTC".to_string(), Vec::new())];
+    let result = simulate_portfolio(markets, PortfolioConfig::new("nope"));
+    assert!(matches!(result, Err(StrategyParamError::UnknownStrategy(name)) if name == "nope"));
+}
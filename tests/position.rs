@@ -0,0 +1,188 @@
+use ctb::{backtest::{fee::FeeSchedule, fill::IntrabarPolicy, ledger::ExitReason, lib::{BacktestParams, BacktesterState}},
+core::{candle::{Candle, CandleBase}, position::PositionState, signal::{Signal, SignalReason, TakeProfitTarget}}};
+
+fn create_backtester() -> BacktesterState {
+    let mut params = BacktestParams::default("KRW-BTC", "TEST");
+    params.enable_webhook_log = false;
+    params.fees = FeeSchedule::flat(0.0);
+    BacktesterState::new(params)
+}
+
+fn buy(asset_pct: f64) -> Signal {
+    Signal::Buy {
+        reason: "breakout".to_string(),
+        initial_trailing_stop: 90.0,
+        take_profit: 200.0,
+        asset_pct,
+    }
+}
+
+fn create_candle(date: &str, opening: f64, high: f64, low: f64, trade: f64) -> Candle {
+    Candle {
+        base: CandleBase {
+            market: "KRW-BTC".to_string(),
+            candle_date_time_utc: date.to_string(),
+            candle_date_time_kst: date.to_string(),
+            opening_price: opening,
+            high_price: high,
+            low_price: low,
+            trade_price: trade,
+            timestamp: 0,
+            candle_acc_trade_price: 1000000.0,
+            candle_acc_trade_volume: 1000.0,
+        }
+    }
+}
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!((actual - expected).abs() < 1e-6, "actual: {}, expected: {}", actual, expected);
+}
+
+#[test]
+fn test_add_to_position_tracks_average_price() {
+    let mut backtester = create_backtester();
+    backtester.handle_signal(&buy(0.5), 100.0, "2024-01-01T00:00:00");
+    // 남은 현금 500,000의 50%
+    backtester.handle_signal(&Signal::AddToPosition { reason: "pyramid".to_string(), asset_pct: 0.5 }, 125.0, "2024-01-01T00:01:00");
+
+    let lots = backtester.position.lots();
+    assert_eq!(lots.len(), 2);
+    assert_eq!(lots[1].trade.entry_reason, "pyramid");
+    assert_close(backtester.position.size(), 5000.0 + 2000.0);
+    match backtester.position {
+        PositionState::InPosition { entry_price, entry_asset, .. } => {
+            assert_close(entry_asset, 750000.0);
+            assert_close(entry_price, 750000.0 / 7000.0);
+        }
+        PositionState::None => panic!("position closed"),
+    }
+    assert_close(backtester.current_asset, 250000.0);
+    assert_close(backtester.equity(125.0), 250000.0 + 7000.0 * 125.0);
+
+    // 포지션이 없으면 추가 진입은 무시
+    let mut backtester = create_backtester();
+    backtester.handle_signal(&Signal::AddToPosition { reason: "pyramid".to_string(), asset_pct: 0.5 }, 100.0, "2024-01-01T00:00:00");
+    assert!(matches!(backtester.position, PositionState::None));
+}
+
+#[test]
+fn test_reduce_position_fifo() {
+    let mut backtester = create_backtester();
+    backtester.handle_signal(&buy(0.5), 100.0, "2024-01-01T00:00:00");
+    backtester.handle_signal(&Signal::AddToPosition { reason: "pyramid".to_string(), asset_pct: 0.5 }, 125.0, "2024-01-01T00:01:00");
+
+    // 전체 7000개 중 6000개 청산: 첫 lot 5000개 전부, 두 번째 lot 1000개
+    let size = backtester.position.size();
+    backtester.handle_signal(&Signal::ReducePosition { reason: "scale out".to_string(), pct: 6000.0 / size }, 150.0, "2024-01-01T00:02:00");
+
+    // 포지션이 남아 있으면 거래로 기록하지 않음
+    assert!(backtester.trades.is_empty());
+    // 첫 lot 50%, 두 번째 lot 20% 이익
+    assert_close(backtester.current_asset, 250000.0 + 500000.0 * 1.5 + 125000.0 * 1.2);
+
+    // 두 번째 lot의 남은 수량만 보유
+    let lots = backtester.position.lots();
+    assert_eq!(lots.len(), 1);
+    assert_close(lots[0].size, 1000.0);
+    assert_close(lots[0].entry_asset, 125000.0);
+    match backtester.position {
+        PositionState::InPosition { entry_price, .. } => assert_close(entry_price, 125.0),
+        PositionState::None => panic!("position closed"),
+    }
+    assert_eq!(backtester.win_count, 0);
+
+    // 전략 매도는 남은 lot 전부 청산하고 포지션 전체를 거래 하나로 기록
+    backtester.handle_signal(&Signal::Sell(SignalReason { reason: "exit".to_string() }), 100.0, "2024-01-01T00:03:00");
+    assert!(matches!(backtester.position, PositionState::None));
+    assert_eq!(backtester.trades.len(), 1);
+    let trade = &backtester.trades[0];
+    assert_eq!(trade.entry_date, "2024-01-01T00:00:00");
+    assert_eq!(trade.exit_date, "2024-01-01T00:03:00");
+    assert_eq!(trade.exit_reason, ExitReason::StrategySell);
+    assert_eq!(trade.sell_reason.as_deref(), Some("exit"));
+    assert_close(trade.size, 7000.0);
+    assert_close(trade.entry_asset, 750000.0);
+    assert_close(trade.entry_price, 750000.0 / 7000.0);
+    // 수량 가중 평균 청산가
+    assert_close(trade.exit_price, (6000.0 * 150.0 + 1000.0 * 100.0) / 7000.0);
+    // 250,000 + 25,000 - 25,000
+    assert_close(trade.pnl, 250000.0);
+    assert_close(trade.pnl_pct, 250000.0 / 750000.0);
+    assert_eq!((backtester.win_count, backtester.loss_count), (1, 0));
+    assert_close(backtester.current_asset, 1250000.0);
+}
+
+#[test]
+fn test_take_profit_targets_with_price() {
+    let mut backtester = create_backtester();
+    backtester.handle_signal(&buy(1.0), 100.0, "2024-01-01T00:00:00");
+    backtester.handle_signal(&Signal::SetTakeProfitTargets(vec![
+        TakeProfitTarget { price: 130.0, pct: 0.5 },
+        TakeProfitTarget { price: 120.0, pct: 0.5 },
+    ]), 100.0, "2024-01-01T00:00:00");
+
+    // 두 목표를 한 번에 넘음: 120에서 절반, 130에서 남은 절반의 절반
+    backtester.check_and_close_position(135.0, "2024-01-01T00:01:00");
+    assert!(backtester.trades.is_empty());
+    assert_close(backtester.position.size(), 2500.0);
+    assert_close(backtester.current_asset, 5000.0 * 120.0 + 2500.0 * 130.0);
+
+    // 남은 수량은 트레일링 스탑으로 청산
+    backtester.check_and_close_position(89.0, "2024-01-01T00:02:00");
+    assert!(matches!(backtester.position, PositionState::None));
+    assert_eq!(backtester.trades.len(), 1);
+    let trade = &backtester.trades[0];
+    assert_eq!(trade.exit_reason, ExitReason::TrailingStop);
+    assert_close(trade.size, 10000.0);
+    assert_close(trade.exit_price, (5000.0 * 120.0 + 2500.0 * 130.0 + 2500.0 * 89.0) / 10000.0);
+    assert_close(trade.pnl_pct, 0.1475);
+    assert_close(trade.mfe_pct, 0.35);
+    assert_close(trade.mae_pct, -0.11);
+}
+
+#[test]
+fn test_take_profit_targets_with_candle() {
+    let mut backtester = create_backtester();
+    backtester.handle_signal(&buy(1.0), 100.0, "2024-01-01T00:00:00");
+    backtester.handle_signal(&Signal::SetTakeProfitTargets(vec![TakeProfitTarget { price: 120.0, pct: 0.5 }]), 100.0, "2024-01-01T00:00:00");
+
+    // 목표와 스탑이 한 봉에 모두 닿으면 Pessimistic은 스탑 먼저
+    let mut pessimistic = backtester.clone();
+    let candle = create_candle("2024-01-01T00:01:00", 100.0, 125.0, 85.0, 100.0);
+    pessimistic.check_and_close_position_with_candle(&candle, IntrabarPolicy::Pessimistic, &[]);
+    assert_eq!(pessimistic.trades.len(), 1);
+    assert_eq!(pessimistic.trades[0].exit_reason, ExitReason::TrailingStop);
+
+    // Optimistic은 분할 익절 후 남은 수량을 스탑으로 청산
+    let mut optimistic = backtester.clone();
+    optimistic.check_and_close_position_with_candle(&candle, IntrabarPolicy::Optimistic, &[]);
+    assert_eq!(optimistic.trades.len(), 1);
+    assert_eq!(optimistic.trades[0].exit_reason, ExitReason::TrailingStop);
+    assert!(optimistic.trades[0].pnl > pessimistic.trades[0].pnl);
+    assert!(matches!(optimistic.position, PositionState::None));
+
+    // 목표만 닿으면 분할 익절 후 포지션 유지
+    let candle = create_candle("2024-01-01T00:01:00", 100.0, 125.0, 95.0, 110.0);
+    backtester.check_and_close_position_with_candle(&candle, IntrabarPolicy::Pessimistic, &[]);
+    assert!(backtester.trades.is_empty());
+    assert_close(backtester.current_asset, 5000.0 * 120.0);
+    assert_close(backtester.position.size(), 5000.0);
+    match &backtester.position {
+        PositionState::InPosition { take_profit_targets, .. } => assert!(take_profit_targets.is_empty()),
+        PositionState::None => panic!("position closed"),
+    }
+}
+
+#[test]
+fn test_close_at_end_records_one_trade() {
+    let mut backtester = create_backtester();
+    backtester.handle_signal(&buy(0.5), 100.0, "2024-01-01T00:00:00");
+    backtester.handle_signal(&Signal::AddToPosition { reason: "pyramid".to_string(), asset_pct: 0.5 }, 110.0, "2024-01-01T00:01:00");
+    backtester.close_at_end(120.0, "2024-01-01T00:02:00");
+
+    assert_eq!(backtester.trades.len(), 1);
+    let trade = &backtester.trades[0];
+    assert_eq!((trade.exit_reason, trade.sell_reason.as_deref()), (ExitReason::EndOfTest, None));
+    assert_eq!(trade.entry_date, "2024-01-01T00:00:00");
+    assert_eq!(trade.entry_reason, "breakout");
+}
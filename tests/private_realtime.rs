@@ -0,0 +1,149 @@
+mod mock_server;
+
+use std::{cell::RefCell, collections::BTreeMap, rc::Rc, time::Duration};
+
+use ctb::{core::{my_asset::MyAsset, my_order::{MyOrder, MyOrderState, MyOrderType}, trade::AskBid},
+upbit_api::{client::UpbitClient, realtime::lib::{dispatch_private_message, listen_private_data, private_subscription_request, private_ws_url, PrivateRealtimeCallback, RealtimeConfig}}};
+use hmac::{Hmac, Mac};
+use jwt::VerifyWithKey;
+use mock_server::spawn_mock_ws_server_with_handshakes;
+use serde_json::{json, Value};
+use sha2::Sha512;
+use tokio::sync::mpsc;
+
+fn my_order_message(uuid: &str, state: &str) -> String {
+    json!({
+        "ty": "myOrder", "cd": "KRW-BTC", "uid": uuid, "ab": "BID", "ot": "limit", "s": state,
+        "tid": "trade-1", "p": 50000000.0, "ap": 50000000.0, "v": 0.001, "rv": 0.0, "ev": 0.001, "tc": 1,
+        "rsf": 25.0, "rmf": 0.0, "pf": 25.0, "l": 0.0, "ef": 50000.0, "tif": null, "tf": 25.0, "im": true,
+        "id": null, "ttms": 1704067200000i64, "otms": 1704067100000i64, "tms": 1704067200001i64, "st": "REALTIME"
+    }).to_string()
+}
+
+fn my_asset_message() -> String {
+    json!({
+        "ty": "myAsset", "auid": "asset-1",
+        "ast": [{"cu": "KRW", "b": 950000.0, "l": 0.0}, {"cu": "BTC", "b": 0.001, "l": 0.0}],
+        "asttms": 1704067200000i64, "tms": 1704067200001i64, "st": "REALTIME"
+    }).to_string()
+}
+
+#[derive(Default)]
+struct Received {
+    orders: Vec<MyOrder>,
+    assets: Vec<MyAsset>,
+    exit_count: u32,
+}
+
+fn callback(received: Rc<RefCell<Received>>, shutdown_send: Option<mpsc::Sender<()>>) -> PrivateRealtimeCallback {
+    let order_received = received.clone();
+    let asset_received = received.clone();
+    PrivateRealtimeCallback {
+        my_order_fn: Box::new(move |order| order_received.borrow_mut().orders.push(order.clone())),
+        my_asset_fn: Box::new(move |asset| {
+            asset_received.borrow_mut().assets.push(asset.clone());
+            if let Some(shutdown_send) = &shutdown_send {
+                shutdown_send.try_send(()).unwrap();
+            }
+        }),
+        gap_fn: Box::new(|_| {}),
+        exit_fn: Box::new(move || received.borrow_mut().exit_count += 1),
+    }
+}
+
+#[test]
+fn test_parse_my_order_and_asset() {
+    let order: MyOrder = serde_json::from_str(&my_order_message("order-1", "trade")).unwrap();
+    assert_eq!(order.uuid, "order-1");
+    assert_eq!(order.ask_bid, AskBid::Bid);
+    assert_eq!(order.order_type, MyOrderType::Limit);
+    assert!(order.is_trade());
+    assert!(!order.is_finished());
+    assert_eq!(order.is_maker, Some(true));
+    assert_eq!(order.time_in_force, None);
+
+    let order: MyOrder = serde_json::from_str(&my_order_message("order-1", "done")).unwrap();
+    assert_eq!(order.state, MyOrderState::Done);
+    assert!(order.is_finished());
+
+    let asset: MyAsset = serde_json::from_str(&my_asset_message()).unwrap();
+    assert_eq!(asset.assets.len(), 2);
+    assert_eq!(asset.get("BTC").unwrap().balance, 0.001);
+    assert!(asset.get("ETH").is_none());
+}
+
+#[test]
+fn test_dispatch_private_message() {
+    let received = Rc::new(RefCell::new(Received::default()));
+    let mut callback = callback(received.clone(), None);
+    dispatch_private_message(&my_order_message("order-1", "wait"), &mut callback);
+    dispatch_private_message(&my_asset_message(), &mut callback);
+    // 상태 응답과 잘못된 메시지는 무시
+    dispatch_private_message(r#"{"status":"UP"}"#, &mut callback);
+    dispatch_private_message(r#"{"ty":"myOrder"}"#, &mut callback);
+
+    let received = received.borrow();
+    assert_eq!(received.orders.len(), 1);
+    assert_eq!(received.orders[0].state, MyOrderState::Wait);
+    assert_eq!(received.assets.len(), 1);
+}
+
+#[test]
+fn test_private_subscription_request() {
+    let request = private_subscription_request(&[]);
+    assert_eq!(request[1], json!({"type": "myOrder"}));
+    assert_eq!(request[2], json!({"type": "myAsset"}));
+    assert_eq!(request[3], json!({"format": "SIMPLE"}));
+    let request = private_subscription_request(&["KRW-BTC"]);
+    assert_eq!(request[1]["codes"], json!(["KRW-BTC"]));
+
+    let client = UpbitClient::public("http://127.0.0.1").with_ws_url("ws://127.0.0.1:1234/websocket/v1/");
+    assert_eq!(private_ws_url(&client), "ws://127.0.0.1:1234/websocket/v1/private");
+}
+
+#[tokio::test]
+async fn test_listen_private_data_with_jwt() {
+    let (ws_url, subscriptions, handshakes) = spawn_mock_ws_server_with_handshakes(vec![
+        vec![my_order_message("order-1", "trade"), my_asset_message()],
+    ]).await;
+    let client = UpbitClient::new("http://127.0.0.1", "test-access", "test-secret").with_ws_url(&ws_url);
+
+    let (shutdown_send, mut shutdown_recv) = mpsc::channel(1);
+    let received = Rc::new(RefCell::new(Received::default()));
+    let mut callback = callback(received.clone(), Some(shutdown_send));
+    let config = RealtimeConfig { initial_backoff: Duration::from_millis(20), ..RealtimeConfig::new() };
+
+    tokio::time::timeout(Duration::from_secs(5),
+        listen_private_data(&client, &[], &mut shutdown_recv, &mut callback, &config),
+    ).await.unwrap();
+
+    let received = received.borrow();
+    assert_eq!(received.orders.len(), 1);
+    assert_eq!(received.assets.len(), 1);
+    assert_eq!(received.exit_count, 1);
+
+    // private 경로로 Bearer JWT를 붙여 연결
+    let handshakes = handshakes.lock().unwrap();
+    assert_eq!(handshakes[0].path, "/private");
+    let token = handshakes[0].header("Authorization").unwrap().strip_prefix("Bearer ").unwrap();
+    let key: Hmac<Sha512> = Hmac::new_from_slice(b"test-secret").unwrap();
+    let claims: BTreeMap<String, Value> = token.verify_with_key(&key).unwrap();
+    assert_eq!(claims["access_key"], "test-access");
+
+    let request: Value = serde_json::from_str(&subscriptions.lock().unwrap()[0]).unwrap();
+    assert_eq!(request[1]["type"], "myOrder");
+    assert_eq!(request[2]["type"], "myAsset");
+}
+
+#[tokio::test]
+async fn test_listen_private_data_without_credentials() {
+    let client = UpbitClient::public("http://127.0.0.1").with_ws_url("ws://127.0.0.1:1");
+    let (_shutdown_send, mut shutdown_recv) = mpsc::channel(1);
+    let received = Rc::new(RefCell::new(Received::default()));
+    let mut callback = callback(received.clone(), None);
+
+    tokio::time::timeout(Duration::from_secs(5),
+        listen_private_data(&client, &[], &mut shutdown_recv, &mut callback, &RealtimeConfig::new()),
+    ).await.unwrap();
+    assert_eq!(received.borrow().exit_count, 1);
+}
@@ -0,0 +1,96 @@
+mod mock_server;
+
+use std::time::{Duration, Instant};
+
+use ctb::upbit_api::{client::UpbitClient, error::{RemainingReq, UpbitError}, market::get_market_info, rate_limit::{RateLimitGroup, RateLimiter}};
+use mock_server::{spawn_mock_server, MockResponse};
+use reqwest::Method;
+
+const TOO_MANY_REQUESTS: &str = r#"{"error":{"name":"too_many_requests","message":"Too many API requests."}}"#;
+
+#[test]
+fn test_rate_limit_group_for_request() {
+    assert_eq!(RateLimitGroup::for_request(&Method::GET, "/candles/minutes/1"), RateLimitGroup::Quotation);
+    assert_eq!(RateLimitGroup::for_request(&Method::GET, "/market/all"), RateLimitGroup::Quotation);
+    assert_eq!(RateLimitGroup::for_request(&Method::GET, "/accounts"), RateLimitGroup::Exchange);
+    assert_eq!(RateLimitGroup::for_request(&Method::GET, "/orders/open"), RateLimitGroup::Exchange);
+    assert_eq!(RateLimitGroup::for_request(&Method::POST, "/orders"), RateLimitGroup::Order);
+    assert_eq!(RateLimitGroup::for_request(&Method::DELETE, "/order"), RateLimitGroup::Order);
+}
+
+#[tokio::test]
+async fn test_rate_limiter_throttles_after_burst() {
+    let limiter = RateLimiter::new().with_limit(RateLimitGroup::Quotation, 5.0);
+
+    let start = Instant::now();
+    for _ in 0..5 {
+        limiter.acquire(RateLimitGroup::Quotation).await;
+    }
+    assert!(start.elapsed() < Duration::from_millis(100));
+
+    // 버킷이 비면 초당 5개 속도로 대기
+    limiter.acquire(RateLimitGroup::Quotation).await;
+    limiter.acquire(RateLimitGroup::Quotation).await;
+    assert!(start.elapsed() >= Duration::from_millis(350));
+
+    // 다른 그룹은 영향을 받지 않음
+    let start = Instant::now();
+    limiter.acquire(RateLimitGroup::Order).await;
+    assert!(start.elapsed() < Duration::from_millis(100));
+}
+
+#[tokio::test]
+async fn test_rate_limiter_waits_when_remaining_req_is_zero() {
+    let limiter = RateLimiter::new();
+    limiter.update(RateLimitGroup::Quotation, &RemainingReq::parse("group=candles; min=1800; sec=0").unwrap());
+
+    let start = Instant::now();
+    limiter.acquire(RateLimitGroup::Quotation).await;
+    assert!(start.elapsed() >= Duration::from_millis(900));
+}
+
+#[tokio::test]
+async fn test_clients_share_rate_limiter_per_base_url() {
+    let public = UpbitClient::public("http://shared-limiter.test");
+    let private = UpbitClient::new("http://shared-limiter.test/", "access", "secret");
+    let other = UpbitClient::public("http://other-limiter.test");
+    public.rate_limiter().block(RateLimitGroup::Order, Duration::from_millis(300));
+
+    // 다른 서버를 호출하는 클라이언트는 영향을 받지 않음
+    let start = Instant::now();
+    other.rate_limiter().acquire(RateLimitGroup::Order).await;
+    assert!(start.elapsed() < Duration::from_millis(100));
+
+    // 따로 만든 클라이언트라도 같은 서버면 버킷을 공유함
+    private.rate_limiter().acquire(RateLimitGroup::Order).await;
+    assert!(start.elapsed() >= Duration::from_millis(250));
+}
+
+#[tokio::test]
+async fn test_retry_after_too_many_requests() {
+    let (base_url, captured) = spawn_mock_server(vec![
+        MockResponse::json(429, TOO_MANY_REQUESTS).with_header("Remaining-Req", "group=market; min=573; sec=1"),
+        MockResponse::json(200, "[]").with_header("Remaining-Req", "group=market; min=572; sec=9"),
+    ]).await;
+    let client = UpbitClient::public(&base_url).with_retry(3, Duration::from_millis(50));
+
+    let start = Instant::now();
+    let markets = get_market_info(&client).await.unwrap();
+    assert!(markets.is_empty());
+    assert_eq!(captured.lock().unwrap().len(), 2);
+    assert!(start.elapsed() >= Duration::from_millis(50));
+}
+
+#[tokio::test]
+async fn test_gives_up_after_max_retries() {
+    let (base_url, captured) = spawn_mock_server(vec![
+        MockResponse::json(429, TOO_MANY_REQUESTS),
+        MockResponse::json(429, TOO_MANY_REQUESTS),
+        MockResponse::json(429, TOO_MANY_REQUESTS),
+    ]).await;
+    let client = UpbitClient::public(&base_url).with_retry(2, Duration::from_millis(10));
+
+    let error = get_market_info(&client).await.unwrap_err();
+    assert!(matches!(error, UpbitError::RateLimited { .. }));
+    assert_eq!(captured.lock().unwrap().len(), 3);
+}
@@ -0,0 +1,295 @@
+mod mock_server;
+
+use std::{cell::RefCell, collections::HashMap, rc::Rc, time::Duration};
+
+use ctb::{backtest::simulate::{simulate_with_recorded_data, SimulationConfig}, core::candle::{Candle, CandleBase}, upbit_api::{client::UpbitClient, realtime::{lib::{listen_realtime_data_with_config, RealtimeCallback, RealtimeConfig, RealtimeGap}, record::{read_recording, read_warm_up_candles, replay_realtime_data, RealtimeRecorder, ReplaySpeed}}}};
+use mock_server::spawn_mock_ws_server;
+use serde_json::{json, Value};
+use tokio::sync::mpsc;
+
+fn trade_message(code: &str, sequential_id: i64) -> String {
+    json!({
+        "ty": "trade", "cd": code, "tp": 100.0, "tv": 1.0, "ab": "BID", "pcp": 100.0, "c": "EVEN", "cp": 0.0,
+        "td": "2024-01-01", "ttm": "00:00:00", "ttms": 1704067200000i64, "tms": 1704067200000i64,
+        "sid": sequential_id, "bap": 101.0, "bas": 1.0, "bbp": 99.0, "bbs": 1.0, "st": "REALTIME"
+    }).to_string()
+}
+
+fn candle_message(code: &str, minute: u32, price: f64) -> String {
+    json!({
+        "ty": "candle.1m", "cd": code,
+        "cdttmu": format!("2024-01-01T00:{:02}:00", minute), "cdttmk": format!("2024-01-01T09:{:02}:00", minute),
+        "op": price, "hp": price * 1.01, "lp": price * 0.99, "tp": price, "tms": 1704067200000u64 + minute as u64 * 60000,
+        "catp": price * 10.0, "catv": 10.0
+    }).to_string()
+}
+
+fn warm_up_candle(code: &str, minute: u32, price: f64) -> Candle {
+    Candle {
+        base: CandleBase {
+            market: code.to_string(),
+            candle_date_time_utc: format!("2023-12-31T23:{:02}:00", minute),
+            candle_date_time_kst: format!("2024-01-01T08:{:02}:00", minute),
+            opening_price: price,
+            high_price: price * 1.01,
+            low_price: price * 0.99,
+            trade_price: price,
+            timestamp: 1704063600000 + minute as u64 * 60000,
+            candle_acc_trade_price: price * 10.0,
+            candle_acc_trade_volume: 10.0,
+        },
+    }
+}
+
+fn temp_record_path() -> String {
+    std::env::temp_dir().join(format!("ctb-record-{}.ndjson.gz", uuid::Uuid::new_v4())).to_string_lossy().to_string()
+}
+
+#[derive(Default)]
+struct Received {
+    trades: Vec<i64>,
+    gaps: Vec<RealtimeGap>,
+    exit_count: u32,
+}
+
+fn callback(received: Rc<RefCell<Received>>, shutdown_send: mpsc::Sender<()>, shutdown_after: usize) -> RealtimeCallback {
+    let trade_received = received.clone();
+    let gap_received = received.clone();
+    RealtimeCallback {
+        orderbook_fn: Box::new(|_| {}),
+        trade_fn: Box::new(move |trade| {
+            let mut received = trade_received.borrow_mut();
+            received.trades.push(trade.sequential_id);
+            if received.trades.len() == shutdown_after {
+                shutdown_send.try_send(()).unwrap();
+            }
+        }),
+        ticker_fn: Box::new(|_| {}),
+        candle_fn: Box::new(|_| {}),
+        gap_fn: Box::new(move |gap| gap_received.borrow_mut().gaps.push(gap.clone())),
+        exit_fn: Box::new(move || received.borrow_mut().exit_count += 1),
+    }
+}
+
+fn test_config() -> RealtimeConfig {
+    RealtimeConfig {
+        initial_backoff: Duration::from_millis(20),
+        max_backoff: Duration::from_millis(100),
+        ..RealtimeConfig::new()
+    }
+}
+
+#[tokio::test]
+async fn test_reconnect_and_resubscribe() {
+    let (ws_url, subscriptions) = spawn_mock_ws_server(vec![
+        vec![trade_message("KRW-BTC", 1), r#"{"status":"UP"}"#.to_string()],
+        vec![trade_message("KRW-BTC", 2)],
+    ]).await;
+    let client = UpbitClient::public("http://127.0.0.1").with_ws_url(&ws_url);
+
+    let (shutdown_send, mut shutdown_recv) = mpsc::channel(1);
+    let received = Rc::new(RefCell::new(Received::default()));
+    let mut callback_maps = HashMap::new();
+    callback_maps.insert("KRW-BTC", callback(received.clone(), shutdown_send, 2));
+
+    tokio::time::timeout(Duration::from_secs(5),
+        listen_realtime_data_with_config(&client, &["KRW-BTC"], &mut shutdown_recv, &mut callback_maps, &test_config()),
+    ).await.unwrap();
+
+    let received = received.borrow();
+    assert_eq!(received.trades, vec![1, 2]);
+    assert_eq!(received.gaps.len(), 1);
+    assert!(received.gaps[0].duration_ms() >= 0);
+    assert_eq!(received.exit_count, 1);
+
+    // 재연결 후에도 같은 종목을 구독함
+    let subscriptions = subscriptions.lock().unwrap();
+    assert_eq!(subscriptions.len(), 2);
+    for subscription in subscriptions.iter() {
+        let request: Value = serde_json::from_str(subscription).unwrap();
+        assert_eq!(request[1]["type"], "trade");
+        assert_eq!(request[1]["codes"], json!(["KRW-BTC"]));
+    }
+}
+
+#[tokio::test]
+async fn test_backoff_when_server_closes_immediately() {
+    // 연결은 받지만 에러 응답만 보내고 바로 끊는 서버 (인증 거절, 요청 수 초과 등)
+    let error_message = r#"{"error":{"name":"TOO_MANY_REQ","message":"too many requests"}}"#.to_string();
+    let (ws_url, subscriptions) = spawn_mock_ws_server(vec![vec![error_message]; 4]).await;
+    let client = UpbitClient::public("http://127.0.0.1").with_ws_url(&ws_url);
+
+    let (shutdown_send, mut shutdown_recv) = mpsc::channel(1);
+    let received = Rc::new(RefCell::new(Received::default()));
+    let mut callback_maps = HashMap::new();
+    callback_maps.insert("KRW-BTC", callback(received.clone(), shutdown_send, 1));
+
+    let config = RealtimeConfig { max_reconnect_attempts: Some(2), ..test_config() };
+    let started = std::time::Instant::now();
+    tokio::time::timeout(Duration::from_secs(5),
+        listen_realtime_data_with_config(&client, &["KRW-BTC"], &mut shutdown_recv, &mut callback_maps, &config),
+    ).await.unwrap();
+
+    // 바로 끊긴 연결도 실패로 세어 백오프 후 재연결하고, 허용 횟수를 넘으면 종료
+    assert_eq!(subscriptions.lock().unwrap().len(), 3);
+    assert!(started.elapsed() >= Duration::from_millis(20 + 40));
+    assert_eq!(received.borrow().exit_count, 1);
+}
+
+#[tokio::test]
+async fn test_gives_up_after_max_reconnect_attempts() {
+    // 아무도 listen하지 않는 포트
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let ws_url = format!("ws://{}", listener.local_addr().unwrap());
+    drop(listener);
+    let client = UpbitClient::public("http://127.0.0.1").with_ws_url(&ws_url);
+
+    let (shutdown_send, mut shutdown_recv) = mpsc::channel(1);
+    let received = Rc::new(RefCell::new(Received::default()));
+    let mut callback_maps = HashMap::new();
+    callback_maps.insert("KRW-BTC", callback(received.clone(), shutdown_send, 1));
+
+    let config = RealtimeConfig { max_reconnect_attempts: Some(2), ..test_config() };
+    tokio::time::timeout(Duration::from_secs(5),
+        listen_realtime_data_with_config(&client, &["KRW-BTC"], &mut shutdown_recv, &mut callback_maps, &config),
+    ).await.unwrap();
+
+    let received = received.borrow();
+    assert!(received.trades.is_empty());
+    assert!(received.gaps.is_empty());
+    assert_eq!(received.exit_count, 1);
+}
+
+#[test]
+fn test_recorder_writes_compressed_messages() {
+    let path = temp_record_path();
+    let mut recorder = RealtimeRecorder::create(&path).unwrap();
+    assert!(recorder.record(1000, &trade_message("KRW-BTC", 1)).unwrap());
+    // 상태 응답은 기록하지 않음
+    assert!(!recorder.record(1001, r#"{"status":"UP"}"#).unwrap());
+    assert!(recorder.record(1002, &candle_message("KRW-BTC", 0, 100.0)).unwrap());
+    recorder.finish().unwrap();
+
+    let bytes = std::fs::read(&path).unwrap();
+    assert_eq!(&bytes[..2], &[0x1f, 0x8b]);
+
+    let recorded = read_recording(&path).unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(recorded.len(), 2);
+    assert_eq!(recorded[0].received_at, 1000);
+    assert_eq!(recorded[0].message, trade_message("KRW-BTC", 1));
+    assert_eq!(recorded[1].received_at, 1002);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn test_record_live_session_and_replay() {
+    let (ws_url, _) = spawn_mock_ws_server(vec![
+        vec![trade_message("KRW-BTC", 1), trade_message("KRW-ETH", 2), trade_message("KRW-BTC", 3)],
+    ]).await;
+    let client = UpbitClient::public("http://127.0.0.1").with_ws_url(&ws_url);
+    let path = temp_record_path();
+
+    let (shutdown_send, mut shutdown_recv) = mpsc::channel(1);
+    let received = Rc::new(RefCell::new(Received::default()));
+    let mut callback_maps = HashMap::new();
+    callback_maps.insert("KRW-BTC", callback(received.clone(), shutdown_send, 2));
+    let config = RealtimeConfig { record_path: Some(path.clone()), ..test_config() };
+    tokio::time::timeout(Duration::from_secs(5),
+        listen_realtime_data_with_config(&client, &["KRW-BTC", "KRW-ETH"], &mut shutdown_recv, &mut callback_maps, &config),
+    ).await.unwrap();
+    assert_eq!(received.borrow().trades, vec![1, 3]);
+
+    // 같은 콜백 구성으로 재생하면 같은 순서로 전달됨
+    let (shutdown_send, _shutdown_recv) = mpsc::channel(1);
+    let replayed = Rc::new(RefCell::new(Received::default()));
+    let mut callback_maps = HashMap::new();
+    callback_maps.insert("KRW-BTC", callback(replayed.clone(), shutdown_send, usize::MAX));
+    let count = replay_realtime_data(&path, ReplaySpeed::MaxSpeed, &mut callback_maps).await.unwrap();
+
+    assert_eq!(count, 3);
+    assert_eq!(replayed.borrow().trades, vec![1, 3]);
+    assert_eq!(replayed.borrow().exit_count, 1);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn test_replay_in_real_time_keeps_intervals() {
+    let path = temp_record_path();
+    let mut recorder = RealtimeRecorder::create(&path).unwrap();
+    recorder.record(1000, &trade_message("KRW-BTC", 1)).unwrap();
+    recorder.record(1150, &trade_message("KRW-BTC", 2)).unwrap();
+    recorder.finish().unwrap();
+
+    let (shutdown_send, _shutdown_recv) = mpsc::channel(1);
+    let received = Rc::new(RefCell::new(Received::default()));
+    let mut callback_maps = HashMap::new();
+    callback_maps.insert("KRW-BTC", callback(received.clone(), shutdown_send, usize::MAX));
+
+    let start = std::time::Instant::now();
+    replay_realtime_data(&path, ReplaySpeed::RealTime, &mut callback_maps).await.unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(150));
+    assert_eq!(received.borrow().trades, vec![1, 2]);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn test_warm_up_candles_are_recorded_but_not_replayed() {
+    let (ws_url, _) = spawn_mock_ws_server(vec![vec![trade_message("KRW-BTC", 1)]]).await;
+    let client = UpbitClient::public("http://127.0.0.1").with_ws_url(&ws_url);
+    let path = temp_record_path();
+
+    let (shutdown_send, mut shutdown_recv) = mpsc::channel(1);
+    let received = Rc::new(RefCell::new(Received::default()));
+    let mut callback_maps = HashMap::new();
+    callback_maps.insert("KRW-BTC", callback(received.clone(), shutdown_send, 1));
+    let candles = (0..3).map(|minute| warm_up_candle("KRW-BTC", minute, 100.0 + minute as f64)).collect::<Vec<_>>();
+    let config = RealtimeConfig {
+        record_path: Some(path.clone()),
+        warm_up_candles: HashMap::from([("KRW-BTC".to_string(), candles)]),
+        ..test_config()
+    };
+    tokio::time::timeout(Duration::from_secs(5),
+        listen_realtime_data_with_config(&client, &["KRW-BTC"], &mut shutdown_recv, &mut callback_maps, &config),
+    ).await.unwrap();
+
+    let warm_up_candles = read_warm_up_candles(&path).unwrap();
+    let recorded = &warm_up_candles["KRW-BTC"];
+    assert_eq!(recorded.len(), 3);
+    assert_eq!(recorded[2].base.trade_price, 102.0);
+    assert_eq!(recorded[0].base.candle_date_time_utc, "2023-12-31T23:00:00");
+
+    // 재생할 때는 warm up 캔들을 메시지로 전달하지 않음
+    let (shutdown_send, _shutdown_recv) = mpsc::channel(1);
+    let replayed = Rc::new(RefCell::new(Received::default()));
+    let mut callback_maps = HashMap::new();
+    callback_maps.insert("KRW-BTC", callback(replayed.clone(), shutdown_send, usize::MAX));
+    let count = replay_realtime_data(&path, ReplaySpeed::MaxSpeed, &mut callback_maps).await.unwrap();
+    assert_eq!(count, 1);
+    assert_eq!(replayed.borrow().trades, vec![1]);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn test_simulate_with_recorded_data_is_deterministic() {
+    let path = temp_record_path();
+    let mut recorder = RealtimeRecorder::create(&path).unwrap();
+    for minute in 0..60 {
+        let price = 100.0 + (minute as f64 * 0.7).sin() * 5.0 + minute as f64 * 0.1;
+        recorder.record(minute as i64 * 60000, &candle_message("KRW-BTC", minute, price)).unwrap();
+        recorder.record(minute as i64 * 60000 + 1, &trade_message("KRW-BTC", minute as i64)).unwrap();
+    }
+    recorder.finish().unwrap();
+
+    let mut config = SimulationConfig::new();
+    config.enable_log = false;
+    config.enable_webhook_log = false;
+    config.strategy_name = "swc".to_string();
+
+    let first = simulate_with_recorded_data(&path, &["KRW-BTC"], ReplaySpeed::MaxSpeed, &config).await.unwrap();
+    let second = simulate_with_recorded_data(&path, &["KRW-BTC"], ReplaySpeed::MaxSpeed, &config).await.unwrap();
+    assert_eq!(first.len(), 1);
+    assert_eq!(first[0].win_count, second[0].win_count);
+    assert_eq!(first[0].loss_count, second[0].loss_count);
+    assert_eq!(first[0].current_asset, second[0].current_asset);
+    std::fs::remove_file(&path).unwrap();
+}
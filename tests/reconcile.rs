@@ -0,0 +1,246 @@
+mod mock_server;
+
+use std::path::PathBuf;
+
+use ctb::{backtest::{reconcile::{adopted_position, find_mismatches, reconcile, Mismatch, MismatchPolicy, ReconcileConfig, Resolution},
+state_store::{MarketState, StateStore, TradingState}},
+core::position::{Lot, OpenTrade, PositionState}, upbit_api::{account::Account, client::UpbitClient, order::Order}};
+use mock_server::{spawn_mock_server, MockResponse};
+
+fn account_json(currency: &str, balance: f64, locked: f64, avg_buy_price: f64) -> serde_json::Value {
+    serde_json::json!({
+        "currency": currency,
+        "balance": balance.to_string(),
+        "locked": locked.to_string(),
+        "avg_buy_price": avg_buy_price.to_string(),
+        "avg_buy_price_modified": false,
+        "unit_currency": "KRW",
+    })
+}
+
+fn order_json(uuid: &str, market: &str, side: &str) -> serde_json::Value {
+    serde_json::json!({
+        "uuid": uuid,
+        "side": side,
+        "ord_type": "limit",
+        "price": "50000000.0",
+        "state": "wait",
+        "market": market,
+        "created_at": "2024-01-01T00:00:00+09:00",
+        "volume": "0.01",
+        "remaining_volume": "0.01",
+        "reserved_fee": "0.0",
+        "remaining_fee": "0.0",
+        "paid_fee": "0.0",
+        "locked": "0.01",
+        "executed_volume": "0.0",
+        "trades_count": 0,
+    })
+}
+
+fn accounts(values: Vec<serde_json::Value>) -> Vec<Account> {
+    serde_json::from_value(serde_json::Value::Array(values)).unwrap()
+}
+
+fn orders(values: Vec<serde_json::Value>) -> Vec<Order> {
+    serde_json::from_value(serde_json::Value::Array(values)).unwrap()
+}
+
+fn position(size: f64, entry_price: f64) -> PositionState {
+    PositionState::InPosition {
+        entry_price,
+        entry_asset: size * entry_price,
+        take_profit_price: entry_price * 1.1,
+        trailing_stop_price: entry_price * 0.95,
+        lots: vec![Lot { entry_price, entry_asset: size * entry_price, size, trade: OpenTrade::new("2024-01-01T00:00:00", "breakout", entry_price) }],
+        take_profit_targets: Vec::new(),
+    }
+}
+
+fn temp_store(name: &str) -> (StateStore, PathBuf) {
+    let path = std::env::temp_dir().join(format!("ctb_state_{}_{}", name, std::process::id())).join("state.json");
+    let _ = std::fs::remove_file(&path);
+    (StateStore::new(&path), path)
+}
+
+#[test]
+fn test_state_store_round_trip() {
+    let (store, path) = temp_store("round_trip");
+    assert!(store.load().unwrap().markets.is_empty());
+
+    let mut state = TradingState::new();
+    let market = state.market_mut("KRW-BTC", "candle_pattern");
+    market.position = position(0.01, 50000000.0);
+    market.open_orders.push("order-1".to_string());
+    market.strategy_state = Some(serde_json::json!({ "trail_distance": 1000.0 }));
+    store.save(&state).unwrap();
+
+    let loaded = store.load().unwrap();
+    let market = &loaded.markets["KRW-BTC"];
+    assert_eq!(market.strategy_name, "candle_pattern");
+    assert_eq!(market.open_orders, vec!["order-1".to_string()]);
+    assert_eq!(market.strategy_state, Some(serde_json::json!({ "trail_distance": 1000.0 })));
+    assert!(matches!(market.position, PositionState::InPosition { trailing_stop_price: 47500000.0, .. }));
+    assert!((market.position.size() - 0.01).abs() < 1e-12);
+
+    let _ = std::fs::remove_dir_all(path.parent().unwrap());
+}
+
+#[test]
+fn test_find_mismatches() {
+    let mut state = TradingState::new();
+    state.market_mut("KRW-BTC", "test").position = position(0.01, 50000000.0);
+    state.market_mut("KRW-ETH", "test").position = position(1.0, 3000000.0);
+    state.market_mut("KRW-ETH", "test").open_orders.push("stored-order".to_string());
+    state.market_mut("KRW-XRP", "test").open_orders.push("gone-order".to_string());
+
+    let accounts = accounts(vec![
+        account_json("KRW", 1000000.0, 0.0, 0.0),
+        // 일부가 주문에 묶여 있어도 같은 수량
+        account_json("BTC", 0.006, 0.004, 50000000.0),
+        account_json("ETH", 0.5, 0.0, 3000000.0),
+        account_json("SOL", 2.0, 0.0, 200000.0),
+        // 최소 주문 금액 미만은 무시
+        account_json("DOGE", 10.0, 0.0, 100.0),
+    ]);
+    let open_orders = orders(vec![order_json("stored-order", "KRW-ETH", "ask"), order_json("manual-order", "KRW-BTC", "bid")]);
+
+    let config = ReconcileConfig::new();
+    let mismatches = find_mismatches(&state, &accounts, &open_orders, &config);
+    assert_eq!(mismatches, vec![
+        Mismatch::UntrackedHolding { market: "KRW-SOL".to_string(), volume: 2.0, avg_buy_price: 200000.0 },
+        Mismatch::VolumeMismatch { market: "KRW-ETH".to_string(), stored: 1.0, actual: 0.5, avg_buy_price: 3000000.0 },
+        Mismatch::UnknownOrder { market: "KRW-BTC".to_string(), uuid: "manual-order".to_string() },
+        Mismatch::MissingOrder { market: "KRW-XRP".to_string(), uuid: "gone-order".to_string() },
+    ]);
+
+    // 관리하지 않는 마켓은 비교하지 않음
+    let mut config = ReconcileConfig::new();
+    config.markets = vec!["KRW-BTC".to_string()];
+    let mismatches = find_mismatches(&state, &accounts, &open_orders, &config);
+    assert_eq!(mismatches, vec![
+        Mismatch::UnknownOrder { market: "KRW-BTC".to_string(), uuid: "manual-order".to_string() },
+        Mismatch::MissingOrder { market: "KRW-XRP".to_string(), uuid: "gone-order".to_string() },
+    ]);
+}
+
+#[tokio::test]
+async fn test_reconcile_adopts_and_cancels() {
+    let (store, path) = temp_store("adopt");
+    let mut state = TradingState::new();
+    state.market_mut("KRW-ETH", "test").position = position(1.0, 3000000.0);
+    state.market_mut("KRW-ETH", "test").open_orders.push("gone-order".to_string());
+    store.save(&state).unwrap();
+
+    let accounts_body = serde_json::to_string(&vec![
+        account_json("KRW", 1000000.0, 0.0, 0.0),
+        account_json("ETH", 0.5, 0.0, 3000000.0),
+        account_json("SOL", 2.0, 0.0, 200000.0),
+    ]).unwrap();
+    let open_orders_body = serde_json::to_string(&vec![order_json("manual-order", "KRW-BTC", "bid")]).unwrap();
+    let cancel_body = serde_json::to_string(&order_json("manual-order", "KRW-BTC", "bid")).unwrap();
+    let (base_url, captured) = spawn_mock_server(vec![
+        MockResponse::json(200, &accounts_body),
+        MockResponse::json(200, &open_orders_body),
+        MockResponse::json(200, &cancel_body),
+    ]).await;
+    let client = UpbitClient::new(&base_url, "test-access", "test-secret");
+
+    let mut config = ReconcileConfig::new();
+    config.policy = MismatchPolicy::Adopt;
+    config.cancel_unknown_orders = true;
+    let resolved = reconcile(&client, &store, &config, "2024-01-02T00:00:00").await.unwrap();
+    assert_eq!(resolved.iter().map(|(_, resolution)| resolution.clone()).collect::<Vec<Resolution>>(),
+        vec![Resolution::Adopted, Resolution::Adopted, Resolution::Cancelled, Resolution::Removed]);
+
+    let captured = captured.lock().unwrap();
+    assert_eq!(captured[0].path, "/accounts");
+    assert_eq!(captured[1].path, "/orders/open?limit=100");
+    assert_eq!((captured[2].method.as_str(), captured[2].path.as_str()), ("DELETE", "/order?uuid=manual-order"));
+
+    // 수량은 계좌에 맞추고 익절가/스탑은 유지, 새 보유분은 평균 매수가로 채택
+    let state = store.load().unwrap();
+    let eth = &state.markets["KRW-ETH"];
+    assert!((eth.position.size() - 0.5).abs() < 1e-12);
+    assert!(matches!(eth.position, PositionState::InPosition { entry_price: 3000000.0, trailing_stop_price: 2850000.0, .. }));
+    assert!(eth.open_orders.is_empty());
+    let sol = &state.markets["KRW-SOL"];
+    assert!((sol.position.size() - 2.0).abs() < 1e-12);
+    assert!(matches!(sol.position, PositionState::InPosition { entry_price: 200000.0, take_profit_price: 210000.0, .. }));
+
+    let _ = std::fs::remove_dir_all(path.parent().unwrap());
+}
+
+#[tokio::test]
+async fn test_reconcile_flattens_untracked_holding() {
+    let (store, path) = temp_store("flatten");
+    let accounts_body = serde_json::to_string(&vec![account_json("KRW", 1000000.0, 0.0, 0.0), account_json("SOL", 2.0, 0.0, 200000.0)]).unwrap();
+    let mut sell_order = order_json("sell-order", "KRW-SOL", "ask");
+    sell_order["ord_type"] = "market".into();
+    let (base_url, captured) = spawn_mock_server(vec![
+        MockResponse::json(200, &accounts_body),
+        MockResponse::json(200, "[]"),
+        MockResponse::json(201, &serde_json::to_string(&sell_order).unwrap()),
+    ]).await;
+    let client = UpbitClient::new(&base_url, "test-access", "test-secret");
+
+    let mut config = ReconcileConfig::new();
+    config.policy = MismatchPolicy::Flatten;
+    let resolved = reconcile(&client, &store, &config, "2024-01-02T00:00:00").await.unwrap();
+    assert_eq!(resolved, vec![(
+        Mismatch::UntrackedHolding { market: "KRW-SOL".to_string(), volume: 2.0, avg_buy_price: 200000.0 },
+        Resolution::Flattened("sell-order".to_string()),
+    )]);
+    let captured = captured.lock().unwrap();
+    assert_eq!(captured[2].method, "POST");
+    assert!(captured[2].body.contains("\"ord_type\":\"market\""));
+    assert!(matches!(store.load().unwrap().markets["KRW-SOL"].position, PositionState::None));
+
+    let _ = std::fs::remove_dir_all(path.parent().unwrap());
+}
+
+#[tokio::test]
+async fn test_reconcile_keeps_going_after_failures() {
+    let (store, path) = temp_store("failures");
+    let mut state = TradingState::new();
+    state.market_mut("KRW-XRP", "test").open_orders.push("gone-order".to_string());
+    store.save(&state).unwrap();
+
+    // SOL은 모두 주문에 묶여 있어 매도할 수 없고, 주문 취소는 거절됨
+    let accounts_body = serde_json::to_string(&vec![account_json("KRW", 1000000.0, 0.0, 0.0), account_json("SOL", 0.0, 2.0, 200000.0)]).unwrap();
+    let open_orders_body = serde_json::to_string(&vec![order_json("manual-order", "KRW-SOL", "ask")]).unwrap();
+    let error_body = r#"{"error":{"name":"order_not_found","message":"주문을 찾지 못했습니다."}}"#;
+    let (base_url, captured) = spawn_mock_server(vec![
+        MockResponse::json(200, &accounts_body),
+        MockResponse::json(200, &open_orders_body),
+        MockResponse::json(404, error_body),
+    ]).await;
+    let client = UpbitClient::new(&base_url, "test-access", "test-secret");
+
+    let mut config = ReconcileConfig::new();
+    config.policy = MismatchPolicy::Flatten;
+    config.cancel_unknown_orders = true;
+    let resolved = reconcile(&client, &store, &config, "2024-01-02T00:00:00").await.unwrap();
+    assert_eq!(resolved.len(), 3);
+    assert!(matches!(resolved[0].1, Resolution::Failed(_)));
+    assert!(matches!(resolved[1].1, Resolution::Failed(_)));
+    assert_eq!(resolved[2].1, Resolution::Removed);
+    // 매도할 수량이 없으면 주문을 보내지 않음
+    assert_eq!(captured.lock().unwrap().len(), 3);
+
+    // 실패한 항목은 그대로 두고 나머지 처리 결과는 저장
+    let state = store.load().unwrap();
+    assert!(state.markets["KRW-XRP"].open_orders.is_empty());
+    assert!(!state.markets.contains_key("KRW-SOL"));
+
+    let _ = std::fs::remove_dir_all(path.parent().unwrap());
+}
+
+#[test]
+fn test_adopted_position_and_market_state() {
+    let position = adopted_position(2.0, 100.0, 105.0, "2024-01-01T00:00:00");
+    assert!(matches!(position, PositionState::InPosition { entry_price: 100.0, entry_asset: 200.0, take_profit_price: 105.0, trailing_stop_price: 0.0, .. }));
+    let state = MarketState::new("KRW-BTC", "test");
+    assert!(matches!(state.position, PositionState::None));
+    assert!(state.open_orders.is_empty());
+}
@@ -0,0 +1,176 @@
+use ctb::{backtest::{fee::FeeSchedule, ledger::{parse_backtest_date, EquityPoint, ExitReason, TradeRecord}, lib::{BacktestParams, BacktesterState}, report::BacktestReport}, core::signal::{Signal, SignalReason}};
+
+fn trade(entry_date: &str, exit_date: &str, pnl_pct: f64) -> TradeRecord {
+    TradeRecord {
+        code: "KRW-BTC".to_string(),
+        entry_date: entry_date.to_string(),
+        exit_date: exit_date.to_string(),
+        entry_price: 100.0,
+        exit_price: 100.0 * (1.0 + pnl_pct),
+        entry_asset: 1000.0,
+        size: 10.0,
+        fees: 0.0,
+        pnl: 1000.0 * pnl_pct,
+        pnl_pct,
+        exit_reason: ExitReason::StrategySell,
+        mae_pct: 0.0,
+        mfe_pct: 0.0,
+        entry_reason: "".to_string(),
+        sell_reason: None,
+    }
+}
+
+fn point(date: &str, equity: f64) -> EquityPoint {
+    EquityPoint { date: date.to_string(), equity }
+}
+
+#[test]
+fn test_parse_backtest_date() {
+    assert_eq!(parse_backtest_date("2024-01-01T00:00:00"), Some(1704067200000));
+    assert_eq!(parse_backtest_date("2024-01-01T00:00:00Z"), Some(1704067200000));
+    assert_eq!(parse_backtest_date("1704067200000"), Some(1704067200000));
+    assert_eq!(parse_backtest_date("invalid"), None);
+}
+
+#[test]
+fn test_trade_statistics() {
+    let trades = vec![
+        trade("2024-01-01T00:00:00", "2024-01-01T00:10:00", 0.02),
+        trade("2024-01-01T01:00:00", "2024-01-01T01:10:00", -0.01),
+        trade("2024-01-01T02:00:00", "2024-01-01T02:10:00", -0.01),
+        trade("2024-01-01T03:00:00", "2024-01-01T03:30:00", 0.03),
+    ];
+    let equity_curve = vec![point("2024-01-01T00:00:00", 1000.0), point("2024-01-01T04:00:00", 1030.0)];
+    let report = BacktestReport::new(1000.0, &trades, &equity_curve);
+
+    assert_eq!(report.total_trades, 4);
+    assert_eq!(report.win_count, 2);
+    assert_eq!(report.win_rate, 0.5);
+    assert!((report.profit_factor - 50.0 / 20.0).abs() < 1e-9);
+    assert!((report.expectancy - 7.5).abs() < 1e-9);
+    assert!((report.avg_win_pct - 0.025).abs() < 1e-9);
+    assert!((report.avg_loss_pct + 0.01).abs() < 1e-9);
+    assert_eq!(report.longest_losing_streak, 2);
+    // (10 + 10 + 10 + 30) / 4분
+    assert_eq!(report.avg_holding_ms, Some(15 * 60_000));
+    // 60분 / 240분
+    assert!((report.time_in_market_pct.unwrap() - 0.25).abs() < 1e-9);
+    assert!((report.total_return_pct - 0.03).abs() < 1e-9);
+}
+
+#[test]
+fn test_time_in_market_counts_overlap_once() {
+    // 여러 종목이 겹쳐 보유한 시간은 한 번만 셈: 00:00~01:30, 02:00~03:00
+    let trades = vec![
+        trade("2024-01-01T00:00:00", "2024-01-01T01:00:00", 0.01),
+        trade("2024-01-01T00:30:00", "2024-01-01T01:30:00", 0.01),
+        trade("2024-01-01T00:40:00", "2024-01-01T00:50:00", 0.01),
+        trade("2024-01-01T02:00:00", "2024-01-01T03:00:00", 0.01),
+    ];
+    let equity_curve = vec![point("2024-01-01T00:00:00", 1000.0), point("2024-01-01T04:00:00", 1040.0)];
+    let report = BacktestReport::new(1000.0, &trades, &equity_curve);
+    assert!((report.time_in_market_pct.unwrap() - 150.0 / 240.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_drawdown_and_ratios() {
+    let equity_curve = vec![
+        point("2024-01-01T00:00:00", 1000.0),
+        point("2024-01-01T01:00:00", 1100.0),
+        point("2024-01-01T02:00:00", 990.0),
+        point("2024-01-01T03:00:00", 1050.0),
+        point("2024-01-01T04:00:00", 1120.0),
+        point("2024-01-01T05:00:00", 1100.0),
+    ];
+    let report = BacktestReport::new(1000.0, &[], &equity_curve);
+
+    assert!((report.max_drawdown_pct - 0.1).abs() < 1e-9);
+    // 01:00 최고점에서 04:00 회복까지 3시간
+    assert_eq!(report.max_drawdown_duration_ms, Some(3 * 3600 * 1000));
+    assert!(report.cagr.unwrap() > 0.0);
+    let calmar_ratio = report.cagr.unwrap() / 0.1;
+    assert!((report.calmar_ratio.unwrap() / calmar_ratio - 1.0).abs() < 1e-9);
+    assert!(report.sharpe_ratio > 0.0);
+    assert!(report.sortino_ratio > report.sharpe_ratio);
+    // 거래가 없으면 0
+    assert_eq!(report.profit_factor, 0.0);
+    assert_eq!(report.avg_holding_ms, None);
+}
+
+#[test]
+fn test_drawdown_without_decline() {
+    let equity_curve = vec![
+        point("2024-01-01T00:00:00", 1000.0),
+        point("2024-01-01T01:00:00", 1000.0),
+        point("2024-01-01T02:00:00", 1100.0),
+    ];
+    let report = BacktestReport::new(1000.0, &[], &equity_curve);
+    assert_eq!(report.max_drawdown_pct, 0.0);
+    assert_eq!(report.max_drawdown_duration_ms, Some(0));
+}
+
+#[test]
+fn test_drawdown_without_recovery() {
+    let equity_curve = vec![
+        point("2024-01-01T00:00:00", 1000.0),
+        point("2024-01-01T01:00:00", 900.0),
+        point("2024-01-01T06:00:00", 950.0),
+    ];
+    let report = BacktestReport::new(1000.0, &[], &equity_curve);
+    assert!((report.max_drawdown_pct - 0.1).abs() < 1e-9);
+    assert_eq!(report.max_drawdown_duration_ms, Some(6 * 3600 * 1000));
+}
+
+#[test]
+fn test_backtester_keeps_ledger_and_equity_curve() {
+    let mut params = BacktestParams::default("KRW-BTC", "TEST");
+    params.enable_webhook_log = false;
+    params.fees = FeeSchedule::flat(0.0);
+    let mut backtester = BacktesterState::new(params);
+
+    backtester.check_and_close_position(100.0, "2024-01-01T00:00:00");
+    backtester.handle_signal(&Signal::Buy {
+        reason: "TEST".to_string(),
+        initial_trailing_stop: 90.0,
+        take_profit: 120.0,
+        asset_pct: 1.0,
+    }, 100.0, "2024-01-01T00:00:00");
+    backtester.check_and_close_position(95.0, "2024-01-01T00:01:00");
+    backtester.check_and_close_position(110.0, "2024-01-01T00:02:00");
+    backtester.handle_signal(&Signal::Sell(SignalReason { reason: "TEST".to_string() }), 110.0, "2024-01-01T00:02:00");
+
+    assert_eq!(backtester.trades.len(), 1);
+    let record = &backtester.trades[0];
+    assert_eq!(record.entry_date, "2024-01-01T00:00:00");
+    assert_eq!(record.exit_date, "2024-01-01T00:02:00");
+    assert!((record.pnl_pct - 0.1).abs() < 1e-9);
+
+    let equities = backtester.equity_curve.iter().map(|point| point.equity.round()).collect::<Vec<f64>>();
+    assert_eq!(equities, vec![1000000.0, 950000.0, 1100000.0]);
+
+    let report = backtester.report();
+    assert!((report.max_drawdown_pct - 0.05).abs() < 1e-9);
+    assert_eq!(report.avg_holding_ms, Some(120_000));
+    assert_eq!(report.time_in_market_pct, Some(1.0));
+}
+
+#[test]
+fn test_equity_curve_collapses_ticks_within_minute() {
+    let mut params = BacktestParams::default("KRW-BTC", "TEST");
+    params.enable_webhook_log = false;
+    params.fees = FeeSchedule::flat(0.0);
+    let mut backtester = BacktesterState::new(params);
+
+    backtester.handle_signal(&Signal::Buy {
+        reason: "TEST".to_string(),
+        initial_trailing_stop: 50.0,
+        take_profit: 200.0,
+        asset_pct: 1.0,
+    }, 100.0, "1704067200000");
+    backtester.check_and_close_position(101.0, "1704067210000");
+    backtester.check_and_close_position(99.0, "1704067259999");
+    backtester.check_and_close_position(102.0, "1704067260000");
+
+    let points = backtester.equity_curve.iter().map(|point| (point.date.as_str(), point.equity.round())).collect::<Vec<(&str, f64)>>();
+    assert_eq!(points, vec![("1704067259999", 990000.0), ("1704067260000", 1020000.0)]);
+}
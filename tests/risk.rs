@@ -0,0 +1,147 @@
+use ctb::{backtest::{fee::FeeSchedule, lib::{BacktestParams, BacktesterState, INITIAL_ASSET}, risk::{KillSwitch, RejectReason, RiskConfig, RiskDecision, RiskManager}},
+core::{position::PositionState, signal::{Signal, SignalReason}}};
+
+fn create_backtester(config: RiskConfig) -> BacktesterState {
+    let mut params = BacktestParams::default("KRW-BTC", "TEST");
+    params.enable_webhook_log = false;
+    params.enable_trade_log = false;
+    params.fees = FeeSchedule::flat(0.0);
+    let mut backtester = BacktesterState::new(params);
+    backtester.risk = Some(RiskManager::new(config));
+    backtester
+}
+
+fn buy(stop: f64, asset_pct: f64) -> Signal {
+    Signal::Buy {
+        reason: "breakout".to_string(),
+        initial_trailing_stop: stop,
+        take_profit: 200.0,
+        asset_pct,
+    }
+}
+
+fn sell() -> Signal {
+    Signal::Sell(SignalReason { reason: "exit".to_string() })
+}
+
+fn entry_asset(backtester: &BacktesterState) -> f64 {
+    match backtester.position {
+        PositionState::InPosition { entry_asset, .. } => entry_asset,
+        PositionState::None => 0.0,
+    }
+}
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!((actual - expected).abs() < 1e-6, "actual: {}, expected: {}", actual, expected);
+}
+
+#[test]
+fn test_risk_per_trade_resizes_entry() {
+    let mut config = RiskConfig::new();
+    config.max_risk_per_trade_pct = Some(0.01);
+    let mut backtester = create_backtester(config);
+
+    // 스탑까지 5% -> 자산의 1% 위험이면 20%만 진입
+    backtester.handle_signal(&buy(95.0, 1.0), 100.0, "2024-01-01T00:00:00");
+    assert_close(entry_asset(&backtester), INITIAL_ASSET * 0.2);
+
+    // 같은 스탑으로 추가 진입하면 남은 위험 한도가 없음
+    let risk = backtester.risk.as_mut().unwrap();
+    let decision = risk.check_entry(&Signal::AddToPosition { reason: "add".to_string(), asset_pct: 1.0 }, &backtester.position,
+        INITIAL_ASSET, backtester.current_asset, 100.0, "2024-01-01T00:01:00");
+    assert_eq!(decision, RiskDecision::Reject(RejectReason::RiskBudgetUsed));
+
+    // 스탑이 진입가 이상이면 거절
+    let decision = risk.check_entry(&buy(100.0, 1.0), &PositionState::None, INITIAL_ASSET, INITIAL_ASSET, 100.0, "2024-01-01T00:01:00");
+    assert_eq!(decision, RiskDecision::Reject(RejectReason::InvalidStop { stop_price: 100.0 }));
+
+    // 한도 안이면 그대로 진입
+    let decision = risk.check_entry(&buy(50.0, 0.01), &PositionState::None, INITIAL_ASSET, INITIAL_ASSET, 100.0, "2024-01-01T00:01:00");
+    assert_eq!(decision, RiskDecision::Allow);
+}
+
+#[test]
+fn test_exposure_limit() {
+    let mut config = RiskConfig::new();
+    config.max_exposure_pct = Some(0.3);
+    let mut backtester = create_backtester(config);
+
+    backtester.handle_signal(&buy(90.0, 0.2), 100.0, "2024-01-01T00:00:00");
+    assert_close(entry_asset(&backtester), INITIAL_ASSET * 0.2);
+
+    // 비중 30%까지만 추가 진입
+    backtester.handle_signal(&Signal::AddToPosition { reason: "add".to_string(), asset_pct: 0.5 }, 100.0, "2024-01-01T00:01:00");
+    assert_close(entry_asset(&backtester), INITIAL_ASSET * 0.3);
+
+    backtester.handle_signal(&Signal::AddToPosition { reason: "add".to_string(), asset_pct: 0.5 }, 100.0, "2024-01-01T00:02:00");
+    assert_close(entry_asset(&backtester), INITIAL_ASSET * 0.3);
+}
+
+#[test]
+fn test_daily_loss_limit() {
+    let mut config = RiskConfig::new();
+    config.max_daily_loss_pct = Some(0.04);
+    let mut backtester = create_backtester(config);
+
+    // 50% 진입 후 10% 하락 -> 자산의 5% 손실
+    backtester.handle_signal(&buy(80.0, 0.5), 100.0, "2024-01-01T01:00:00");
+    backtester.handle_signal(&sell(), 90.0, "2024-01-01T02:00:00");
+    assert_close(backtester.risk.as_ref().unwrap().daily_pnl(), -INITIAL_ASSET * 0.05);
+
+    backtester.handle_signal(&buy(80.0, 0.5), 100.0, "2024-01-01T03:00:00");
+    assert!(matches!(backtester.position, PositionState::None));
+
+    // 다음 날은 다시 진입
+    backtester.handle_signal(&buy(80.0, 0.5), 100.0, "2024-01-02T00:00:00");
+    assert!(matches!(backtester.position, PositionState::InPosition { .. }));
+    assert_close(backtester.risk.as_ref().unwrap().daily_pnl(), 0.0);
+}
+
+#[test]
+fn test_consecutive_losses_cooldown() {
+    let mut config = RiskConfig::new();
+    config.max_consecutive_losses = Some(2);
+    config.cooldown_ms = 60 * 60 * 1000;
+    let mut backtester = create_backtester(config);
+
+    for minute in ["00", "10"] {
+        backtester.handle_signal(&buy(90.0, 0.1), 100.0, &format!("2024-01-01T00:{}:00", minute));
+        backtester.handle_signal(&sell(), 99.0, &format!("2024-01-01T00:{}:30", minute));
+    }
+    let risk = backtester.risk.as_ref().unwrap();
+    assert_eq!(risk.consecutive_losses, 2);
+    assert_eq!(risk.cooldown_until, Some(ctb::backtest::ledger::parse_backtest_date("2024-01-01T01:10:30").unwrap()));
+
+    backtester.handle_signal(&buy(90.0, 0.1), 100.0, "2024-01-01T01:00:00");
+    assert!(matches!(backtester.position, PositionState::None));
+
+    // 대기 시간이 지나면 연속 손실을 초기화하고 진입
+    backtester.handle_signal(&buy(90.0, 0.1), 100.0, "2024-01-01T01:10:30");
+    assert!(matches!(backtester.position, PositionState::InPosition { .. }));
+    assert_eq!(backtester.risk.as_ref().unwrap().consecutive_losses, 0);
+
+    // 이익 거래는 연속 손실을 초기화
+    backtester.handle_signal(&sell(), 110.0, "2024-01-01T01:20:00");
+    assert_eq!(backtester.risk.as_ref().unwrap().consecutive_losses, 0);
+}
+
+#[test]
+fn test_kill_switch_is_shared() {
+    let kill_switch = KillSwitch::new();
+    let mut btc = create_backtester(RiskConfig::new());
+    btc.risk = Some(RiskManager::with_kill_switch(RiskConfig::new(), kill_switch.clone()));
+    let mut eth = btc.clone();
+
+    btc.handle_signal(&buy(90.0, 0.5), 100.0, "2024-01-01T00:00:00");
+    kill_switch.trip();
+    eth.handle_signal(&buy(90.0, 0.5), 100.0, "2024-01-01T00:00:00");
+    assert!(matches!(eth.position, PositionState::None));
+
+    // 청산은 그대로 처리
+    btc.handle_signal(&sell(), 110.0, "2024-01-01T00:01:00");
+    assert!(matches!(btc.position, PositionState::None));
+
+    kill_switch.reset();
+    eth.handle_signal(&buy(90.0, 0.5), 100.0, "2024-01-01T00:02:00");
+    assert!(matches!(eth.position, PositionState::InPosition { .. }));
+}
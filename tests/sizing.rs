@@ -0,0 +1,119 @@
+use std::collections::VecDeque;
+
+use ctb::{backtest::{fee::FeeSchedule, lib::{BacktestParams, BacktesterState, INITIAL_ASSET}, sizing::{kelly_fraction, PositionSizing, SizingContext}},
+core::{candle::{Candle, CandleBase}, position::PositionState, signal::{Signal, SignalReason}}};
+
+fn create_candle(date: &str, high: f64, low: f64, close: f64) -> Candle {
+    Candle {
+        base: CandleBase {
+            market: "KRW-BTC".to_string(),
+            candle_date_time_utc: date.to_string(),
+            candle_date_time_kst: date.to_string(),
+            opening_price: close,
+            high_price: high,
+            low_price: low,
+            trade_price: close,
+            timestamp: 0,
+            candle_acc_trade_price: 1000000.0,
+            candle_acc_trade_volume: 1000.0,
+        }
+    }
+}
+
+fn context<'a>(candles: &'a VecDeque<Candle>, returns: &'a [f64]) -> SizingContext<'a> {
+    SizingContext { equity: 1000000.0, cash: 500000.0, price: 100.0, candles, returns }
+}
+
+fn buy(stop: f64, asset_pct: f64) -> Signal {
+    Signal::Buy {
+        reason: "breakout".to_string(),
+        initial_trailing_stop: stop,
+        take_profit: 200.0,
+        asset_pct,
+    }
+}
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!((actual - expected).abs() < 1e-6, "actual: {}, expected: {}", actual, expected);
+}
+
+#[test]
+fn test_fixed_models() {
+    let candles = VecDeque::new();
+    let context = context(&candles, &[]);
+    assert_close(PositionSizing::SignalPct.asset_pct(0.7, 90.0, &context), 0.7);
+    assert_close(PositionSizing::FixedAmount { amount: 100000.0 }.asset_pct(0.7, 90.0, &context), 0.2);
+    assert_close(PositionSizing::FixedFraction { pct: 0.1 }.asset_pct(0.7, 90.0, &context), 0.2);
+    // 스탑까지 5%, 평가 자산의 1% 위험 -> 200,000원
+    assert_close(PositionSizing::FixedRisk { risk_pct: 0.01 }.asset_pct(0.7, 95.0, &context), 0.4);
+    assert_close(PositionSizing::FixedRisk { risk_pct: 0.01 }.asset_pct(0.7, 100.0, &context), 0.0);
+    // 현금보다 많이 진입하지 않음
+    assert_close(PositionSizing::FixedFraction { pct: 0.8 }.asset_pct(0.7, 90.0, &context), 1.0);
+}
+
+#[test]
+fn test_volatility_target() {
+    let sizing = PositionSizing::VolatilityTarget { target_pct: 0.01, period: 3 };
+    // 캔들이 부족하면 신호의 비율
+    assert_close(sizing.asset_pct(0.7, 90.0, &context(&VecDeque::new(), &[])), 0.7);
+
+    // 고가 - 저가가 항상 2 -> ATR 2 (2%), 평가 자산의 1%가 움직이는 금액은 500,000원
+    let candles = ["2024-01-01T00:00:00", "2024-01-01T00:05:00", "2024-01-01T00:10:00", "2024-01-01T00:15:00"]
+        .map(|date| create_candle(date, 101.0, 99.0, 100.0)).into();
+    assert_close(sizing.asset_pct(0.7, 90.0, &context(&candles, &[])), 1.0);
+    let sizing = PositionSizing::VolatilityTarget { target_pct: 0.005, period: 3 };
+    assert_close(sizing.asset_pct(0.7, 90.0, &context(&candles, &[])), 0.5);
+}
+
+#[test]
+fn test_kelly() {
+    // 승률 60%, 평균 이익 10%, 평균 손실 5% -> 0.6 - 0.4 / 2 = 0.4
+    let returns = [0.1, 0.1, 0.1, -0.05, -0.05];
+    assert_close(kelly_fraction(&returns), 0.4);
+    assert_close(kelly_fraction(&[0.1, 0.2]), 1.0);
+    assert_close(kelly_fraction(&[-0.1, -0.2]), 0.0);
+    assert_close(kelly_fraction(&[0.01, -0.1]), 0.0);
+    assert_close(kelly_fraction(&[]), 0.0);
+
+    let sizing = PositionSizing::Kelly { fraction: 0.5, min_trades: 5, max_pct: 0.25 };
+    // 거래가 부족하면 신호의 비율
+    assert_close(sizing.asset_pct(0.7, 90.0, &context(&VecDeque::new(), &returns[..4])), 0.7);
+    // 켈리의 절반 20% -> 200,000원
+    assert_close(sizing.asset_pct(0.7, 90.0, &context(&VecDeque::new(), &returns)), 0.4);
+    // 최대 비율 10%
+    let sizing = PositionSizing::Kelly { fraction: 1.0, min_trades: 5, max_pct: 0.1 };
+    assert_close(sizing.asset_pct(0.7, 90.0, &context(&VecDeque::new(), &returns)), 0.2);
+}
+
+#[test]
+fn test_apply_only_changes_entries() {
+    let sizing = PositionSizing::FixedFraction { pct: 0.1 };
+    let candles = VecDeque::new();
+    let context = context(&candles, &[]);
+    assert!(matches!(sizing.apply(&buy(90.0, 1.0), &PositionState::None, &context), Signal::Buy { asset_pct, .. } if (asset_pct - 0.2).abs() < 1e-9));
+    let sell = Signal::Sell(SignalReason { reason: "exit".to_string() });
+    assert_eq!(sizing.apply(&sell, &PositionState::None, &context), sell);
+    // 포지션이 있으면 Buy는 처리되지 않으므로 그대로
+    let position = PositionState::InPosition {
+        entry_price: 100.0, entry_asset: 0.0, take_profit_price: 200.0, trailing_stop_price: 90.0, lots: Vec::new(), take_profit_targets: Vec::new(),
+    };
+    assert_eq!(sizing.apply(&buy(90.0, 1.0), &position, &context), buy(90.0, 1.0));
+    let add = Signal::AddToPosition { reason: "add".to_string(), asset_pct: 1.0 };
+    assert!(matches!(sizing.apply(&add, &position, &context), Signal::AddToPosition { asset_pct, .. } if (asset_pct - 0.2).abs() < 1e-9));
+}
+
+#[test]
+fn test_backtester_applies_sizing() {
+    let mut params = BacktestParams::default("KRW-BTC", "TEST");
+    params.enable_webhook_log = false;
+    params.enable_trade_log = false;
+    params.fees = FeeSchedule::flat(0.0);
+    params.sizing = PositionSizing::FixedRisk { risk_pct: 0.02 };
+    let mut backtester = BacktesterState::new(params);
+
+    // 스탑까지 10%, 평가 자산의 2% 위험 -> 20% 진입
+    backtester.handle_signal(&buy(90.0, 1.0), 100.0, "2024-01-01T00:00:00");
+    let PositionState::InPosition { entry_asset, .. } = backtester.position else { panic!("not in position") };
+    assert_close(entry_asset, INITIAL_ASSET * 0.2);
+    assert_close(backtester.current_asset, INITIAL_ASSET * 0.8);
+}
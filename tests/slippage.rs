@@ -0,0 +1,180 @@
+use ctb::{backtest::{fee::FeeSchedule, ledger::ExitReason, lib::{BacktestParams, BacktesterState}, simulate::run_candles, slippage::{walk_orderbook, MarketContext, SlippageModel}},
+core::{candle::{Candle, CandleBase}, order_event::Liquidity, orderbook::{Orderbook, OrderbookUnit}, position::PositionState, signal::{Signal, SignalReason}}, strategy::lib::Strategy, upbit_api::order::OrderSide};
+
+fn create_candle(date: &str, high: f64, low: f64, trade: f64, acc_trade_price: f64) -> Candle {
+    Candle {
+        base: CandleBase {
+            market: "KRW-BTC".to_string(),
+            candle_date_time_utc: date.to_string(),
+            candle_date_time_kst: date.to_string(),
+            opening_price: trade,
+            high_price: high,
+            low_price: low,
+            trade_price: trade,
+            timestamp: 0,
+            candle_acc_trade_price: acc_trade_price,
+            candle_acc_trade_volume: acc_trade_price / trade,
+        }
+    }
+}
+
+fn create_orderbook() -> Orderbook {
+    Orderbook {
+        orderbook_type: "orderbook".to_string(),
+        code: "KRW-BTC".to_string(),
+        total_ask_size: 6.0,
+        total_bid_size: 6.0,
+        orderbook_units: vec![
+            OrderbookUnit { ask_price: 101.0, bid_price: 99.0, ask_size: 1.0, bid_size: 2.0 },
+            OrderbookUnit { ask_price: 102.0, bid_price: 98.0, ask_size: 2.0, bid_size: 2.0 },
+            OrderbookUnit { ask_price: 103.0, bid_price: 97.0, ask_size: 3.0, bid_size: 2.0 },
+        ],
+        timestamp: 0,
+        level: 0,
+    }
+}
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!((actual - expected).abs() < 1e-9, "actual: {}, expected: {}", actual, expected);
+}
+
+#[test]
+fn test_fee_schedule_for_market() {
+    assert_eq!(FeeSchedule::for_market("KRW-BTC"), FeeSchedule::flat(0.0005));
+    assert_eq!(FeeSchedule::for_market("BTC-ETH"), FeeSchedule::flat(0.0025));
+    assert_eq!(FeeSchedule::for_market("USDT-BTC"), FeeSchedule::flat(0.0025));
+
+    let fees = FeeSchedule::new(0.0002, 0.0007);
+    assert_eq!(fees.fee_pct(Liquidity::Maker), 0.0002);
+    assert_eq!(fees.fee_pct(Liquidity::Taker), 0.0007);
+}
+
+#[test]
+fn test_fixed_bps() {
+    let market = MarketContext::new();
+    let model = SlippageModel::FixedBps(10.0);
+    assert_close(model.fill_price(OrderSide::Bid, 100.0, 1.0, &market), 100.1);
+    assert_close(model.fill_price(OrderSide::Ask, 100.0, 1.0, &market), 99.9);
+    assert_close(SlippageModel::None.fill_price(OrderSide::Bid, 100.0, 1.0, &market), 100.0);
+}
+
+#[test]
+fn test_atr() {
+    let mut market = MarketContext::new();
+    let model = SlippageModel::Atr { period: 2, multiplier: 0.5 };
+    // 캔들이 부족하면 슬리피지 없음
+    assert_close(model.fill_price(OrderSide::Bid, 100.0, 1.0, &market), 100.0);
+
+    market.push_candle(&create_candle("2024-01-01T00:00:00", 102.0, 98.0, 100.0, 1000.0));
+    market.push_candle(&create_candle("2024-01-01T00:01:00", 103.0, 99.0, 100.0, 1000.0));
+    // 같은 시간의 캔들은 갱신됨
+    market.push_candle(&create_candle("2024-01-01T00:01:00", 104.0, 98.0, 100.0, 1000.0));
+    assert_eq!(market.candles.len(), 2);
+
+    // TR: 4, 6 -> ATR 5
+    assert_close(model.fill_price(OrderSide::Bid, 100.0, 1.0, &market), 102.5);
+    assert_close(model.fill_price(OrderSide::Ask, 100.0, 1.0, &market), 97.5);
+}
+
+#[test]
+fn test_volume_participation() {
+    let mut market = MarketContext::new();
+    market.push_candle(&create_candle("2024-01-01T00:00:00", 101.0, 99.0, 100.0, 40000.0));
+    let model = SlippageModel::VolumeParticipation { impact: 0.1 };
+
+    // 주문 금액 100 / 거래대금 40000 -> sqrt(0.0025) = 0.05 -> 0.5%
+    assert_close(model.fill_price(OrderSide::Bid, 100.0, 1.0, &market), 100.5);
+    // 주문 금액이 커질수록 슬리피지 증가
+    assert!(model.fill_price(OrderSide::Ask, 100.0, 4.0, &market) < model.fill_price(OrderSide::Ask, 100.0, 1.0, &market));
+}
+
+#[test]
+fn test_orderbook_walk() {
+    let orderbook = create_orderbook();
+    assert_close(walk_orderbook(&orderbook, OrderSide::Bid, 1.0).unwrap(), 101.0);
+    // 101 * 1 + 102 * 2
+    assert_close(walk_orderbook(&orderbook, OrderSide::Bid, 3.0).unwrap(), 305.0 / 3.0);
+    // 99 * 2 + 98 * 1
+    assert_close(walk_orderbook(&orderbook, OrderSide::Ask, 3.0).unwrap(), 296.0 / 3.0);
+    // 잔량을 넘는 수량은 마지막 호가에 체결: 99 * 2 + 98 * 2 + 97 * 2 + 97 * 2
+    assert_close(walk_orderbook(&orderbook, OrderSide::Ask, 8.0).unwrap(), 782.0 / 8.0);
+
+    let mut market = MarketContext::new();
+    // 호가가 없으면 신호 가격에 체결
+    assert_close(SlippageModel::OrderbookWalk.fill_price(OrderSide::Bid, 100.0, 1.0, &market), 100.0);
+    market.set_orderbook(&orderbook);
+    assert_close(SlippageModel::OrderbookWalk.fill_price(OrderSide::Bid, 100.0, 1.0, &market), 101.0);
+}
+
+#[test]
+fn test_backtester_fees_and_slippage() {
+    let mut params = BacktestParams::default("KRW-BTC", "TEST");
+    params.enable_webhook_log = false;
+    params.fees = FeeSchedule::new(0.0, 0.001);
+    params.slippage = SlippageModel::FixedBps(10.0);
+    let mut backtester = BacktesterState::new(params);
+
+    let buy = Signal::Buy {
+        reason: "breakout".to_string(),
+        initial_trailing_stop: 90.0,
+        take_profit: 120.0,
+        asset_pct: 0.5,
+    };
+
+    // 익절: 진입은 taker + 슬리피지, 청산은 maker 지정가
+    backtester.handle_signal(&buy, 100.0, "2024-01-01T00:00:00");
+    backtester.check_and_close_position(121.0, "2024-01-01T00:01:00");
+    let trade = &backtester.trades[0];
+    assert_eq!(trade.exit_reason, ExitReason::TakeProfit);
+    assert_close(trade.entry_price, 100.1);
+    assert_close(trade.exit_price, 120.0);
+    assert_close(trade.pnl_pct, 120.0 / 100.1 - 1.0 - 0.001);
+    assert_close(trade.fees, trade.entry_asset * 0.001);
+
+    // 전략 매도: 진입과 청산 모두 taker + 슬리피지
+    backtester.handle_signal(&buy, 100.0, "2024-01-01T00:02:00");
+    backtester.handle_signal(&Signal::Sell(SignalReason { reason: "exit".to_string() }), 110.0, "2024-01-01T00:03:00");
+    let trade = &backtester.trades[1];
+    assert_close(trade.exit_price, 109.89);
+    assert_close(trade.pnl_pct, 109.89 / 100.1 - 1.0 - 0.002);
+    assert_close(trade.fees, trade.entry_asset * 0.002);
+}
+
+// 첫 캔들에서 한 번만 매수하는 전략
+struct BuyOnce {
+    bought: bool,
+}
+
+impl Strategy for BuyOnce {
+    fn name(&self) -> &str {
+        "BUY_ONCE"
+    }
+
+    fn on_candle(&mut self, _candle: &Candle, _position: &mut PositionState) -> Signal {
+        if self.bought {
+            return Signal::Hold;
+        }
+        self.bought = true;
+        Signal::Buy { reason: "breakout".to_string(), initial_trailing_stop: 90.0, take_profit: 200.0, asset_pct: 1.0 }
+    }
+}
+
+#[test]
+fn test_stop_slippage_uses_closed_candles() {
+    let mut params = BacktestParams::default("KRW-BTC", "TEST");
+    params.enable_webhook_log = false;
+    params.slippage = SlippageModel::Atr { period: 1, multiplier: 1.0 };
+    let mut backtester = BacktesterState::new(params);
+    let candles = vec![
+        create_candle("2024-01-01T00:00:00", 100.0, 100.0, 100.0, 1000000.0),
+        // 스탑을 건드린 넓은 캔들. 이 캔들의 ATR (50)은 스탑 체결가에 반영되면 안 됨
+        create_candle("2024-01-01T00:01:00", 100.0, 50.0, 95.0, 1000000.0),
+    ];
+
+    run_candles(&candles, &[], &mut BuyOnce { bought: false }, &mut backtester);
+
+    let trade = &backtester.trades[0];
+    assert_eq!(trade.exit_reason, ExitReason::TrailingStop);
+    assert_close(trade.entry_price, 100.0);
+    assert_close(trade.exit_price, 90.0);
+}
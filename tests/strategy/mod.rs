@@ -0,0 +1,3 @@
+pub mod registry;
+
+pub mod of1;
\ No newline at end of file
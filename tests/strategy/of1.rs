@@ -0,0 +1,77 @@
+use ctb::{core::{candle::{Candle, CandleBase, CandleTrait}, position::PositionState, trade::{AskBid, Change, StreamType, Trade}}, strategy::{lib::Strategy, of1::{Of1Params, Of1Strategy}}};
+
+fn get_trade(trade_timestamp: i64, price: f64, volume: f64, ask_bid: AskBid) -> Trade {
+    Trade {
+        trade_type: "trade".to_string(),
+        code: "KRW-BTC".to_string(),
+        trade_price: price,
+        trade_volume: volume,
+        ask_bid,
+        prev_closing_price: 0.0,
+        change: Change::Even,
+        change_price: 0.0,
+        trade_date: "".to_string(),
+        trade_time: "".to_string(),
+        trade_timestamp,
+        timestamp: trade_timestamp,
+        sequential_id: 0,
+        best_ask_price: 0.0,
+        best_ask_size: 0.0,
+        best_bid_price: 0.0,
+        best_bid_size: 0.0,
+        stream_type: StreamType::Realtime,
+    }
+}
+
+#[test]
+fn test_of1_builds_candles_and_footprints_from_trades() {
+    // 2024-01-01T00:00:00Z
+    let base = 1704067200000;
+    let mut strategy = Of1Strategy::new(Of1Params::new());
+    let mut position = PositionState::None;
+
+    strategy.on_trade(&get_trade(base + 1_000, 100.0, 1.0, AskBid::Bid), &mut position);
+    strategy.on_trade(&get_trade(base + 2_000, 100.0, 2.0, AskBid::Ask), &mut position);
+    strategy.on_trade(&get_trade(base + 3_000, 101.0, 3.0, AskBid::Bid), &mut position);
+    assert!(strategy.state.history_candles.is_empty());
+    assert_eq!(strategy.state.current_mutation_candle.as_ref().unwrap().get_candle_acc_trade_volume(), 6.0);
+
+    // 다음 분의 체결이 오면 이전 봉이 마감됨
+    strategy.on_trade(&get_trade(base + 61_000, 102.0, 1.0, AskBid::Bid), &mut position);
+    assert_eq!(strategy.state.history_candles.len(), 1);
+    let candle = strategy.state.history_candles.back().unwrap();
+    assert_eq!(candle.get_candle_date_time_kst(), "2024-01-01T09:00:00");
+    assert_eq!(candle.get_trade_price(), 101.0);
+
+    let footprint = strategy.state.footprints.last().unwrap();
+    assert_eq!(footprint["100"].bid_volume, 1.0);
+    assert_eq!(footprint["100"].ask_volume, 2.0);
+    assert_eq!(footprint["101"].bid_volume, 3.0);
+
+    let current = strategy.state.current_mutation_candle.as_ref().unwrap();
+    assert_eq!(current.get_candle_date_time_kst(), "2024-01-01T09:01:00");
+}
+
+#[test]
+fn test_of1_warm_up_skips_unclosed_candle() {
+    let candles = ["2024-01-01T00:00:00", "2024-01-01T00:01:00", "2024-01-01T00:02:00"].map(|date| Candle {
+        base: CandleBase {
+            market: "KRW-BTC".to_string(),
+            candle_date_time_utc: date.to_string(),
+            candle_date_time_kst: date.to_string(),
+            opening_price: 100.0,
+            high_price: 101.0,
+            low_price: 99.0,
+            trade_price: 100.0,
+            timestamp: 0,
+            candle_acc_trade_price: 1000.0,
+            candle_acc_trade_volume: 10.0,
+        }
+    });
+    let mut strategy = Of1Strategy::new(Of1Params::new());
+    strategy.warm_up(&candles);
+
+    // 마지막 분봉은 아직 진행 중이므로 체결로 다시 만들어짐
+    assert_eq!(strategy.state.history_candles.len(), 2);
+    assert_eq!(strategy.state.history_candles.back().unwrap().get_candle_date_time_utc(), "2024-01-01T00:01:00");
+}
@@ -0,0 +1,90 @@
+use ctb::{
+    core::{candle::{Candle, CandleBase}, position::PositionState, signal::Signal},
+    strategy::registry::{create_candle_strategy, create_strategy, StrategyParamError, STRATEGY_NAMES},
+};
+
+fn create_candle(index: usize) -> Candle {
+    // 완만한 상승 추세 위에 사인파 변동을 얹은 가격
+    let close = 1000.0 + index as f64 * 0.5 + (index as f64 / 5.0).sin() * 20.0;
+    let open = close - (index as f64 / 3.0).cos() * 5.0;
+    Candle {
+        base: CandleBase {
+            market: "KRW-BTC".to_string(),
+            candle_date_time_utc: format!("2024-01-01T{:02}:{:02}:00", index / 60 % 24, index % 60),
+            candle_date_time_kst: format!("2024-01-01T{:02}:{:02}:00", (index / 60 + 9) % 24, index % 60),
+            opening_price: open,
+            high_price: open.max(close) + 3.0,
+            low_price: open.min(close) - 3.0,
+            trade_price: close,
+            timestamp: index as u64 * 60_000,
+            candle_acc_trade_price: close * 10.0,
+            candle_acc_trade_volume: 10.0 + (index % 7) as f64,
+        }
+    }
+}
+
+#[test]
+pub fn test_create_strategy_by_name() {
+    for name in STRATEGY_NAMES {
+        let strategy = create_strategy(name, false).unwrap();
+        assert_eq!(strategy.name(), name);
+    }
+    assert!(create_strategy("unknown", false).is_none());
+}
+
+#[test]
+pub fn test_strategies_run_on_candles() {
+    let candles = (0..400).map(create_candle).collect::<Vec<Candle>>();
+    for name in STRATEGY_NAMES {
+        let mut strategy = create_strategy(name, false).unwrap();
+        let mut position = PositionState::None;
+        for candle in &candles {
+            let signal = strategy.on_candle(candle, &mut position);
+            if let Signal::Buy { .. } = signal {
+                position = PositionState::InPosition {
+                    entry_price: candle.base.trade_price,
+                    entry_asset: 1.0,
+                    take_profit_price: candle.base.trade_price * 1.1,
+                    trailing_stop_price: candle.base.trade_price * 0.9,
+                    lots: Vec::new(),
+                    take_profit_targets: Vec::new(),
+                };
+            } else if let Signal::Sell(_) = signal {
+                position = PositionState::None;
+            }
+        }
+    }
+}
+
+#[test]
+pub fn test_market_state_strategy_holds_until_enough_candles() {
+    let mut strategy = create_strategy("vwma_ma", false).unwrap();
+    let mut position = PositionState::None;
+    for candle in (0..199).map(create_candle) {
+        assert_eq!(strategy.on_candle(&candle, &mut position), Signal::Hold);
+    }
+}
+
+#[test]
+pub fn test_strategy_snapshot_restore() {
+    let candles = (0..300).map(create_candle).collect::<Vec<Candle>>();
+    let mut strategy = create_strategy("candle_pattern", false).unwrap();
+    strategy.warm_up(&candles);
+    let snapshot = strategy.snapshot().unwrap();
+    let snapshot = serde_json::from_str::<serde_json::Value>(&serde_json::to_string(&snapshot).unwrap()).unwrap();
+
+    // 재시작: 같은 캔들로 warm_up 후 저장된 상태 복원
+    let mut restored = create_strategy("candle_pattern", false).unwrap();
+    restored.warm_up(&candles[..200]);
+    restored.restore(&snapshot);
+    assert_eq!(restored.snapshot(), Some(snapshot));
+
+    assert!(create_strategy("swc", false).unwrap().snapshot().is_none());
+}
+
+#[test]
+pub fn test_create_candle_strategy_rejects_trade_only_strategies() {
+    assert!(create_candle_strategy("candle_pattern", &[], false).is_ok());
+    assert_eq!(create_candle_strategy("of1", &[], false).err(), Some(StrategyParamError::RequiresTrades("of1".to_string())));
+    assert_eq!(create_candle_strategy("nope", &[], false).err(), Some(StrategyParamError::UnknownStrategy("nope".to_string())));
+}
@@ -0,0 +1,77 @@
+mod mock_server;
+
+use std::time::Duration;
+
+use ctb::upbit_api::{account::check_my_account, client::UpbitClient, error::{RemainingReq, UpbitError}, market::get_market_info};
+use mock_server::{spawn_mock_server, MockResponse};
+
+#[test]
+fn test_parse_remaining_req() {
+    let remaining = RemainingReq::parse("group=candles; min=1800; sec=29").unwrap();
+    assert_eq!(remaining, RemainingReq { group: "candles".to_string(), min: Some(1800), sec: 29 });
+
+    let remaining = RemainingReq::parse("group=order; sec=7").unwrap();
+    assert_eq!(remaining.min, None);
+    assert_eq!(remaining.sec, 7);
+
+    assert!(RemainingReq::parse("invalid").is_none());
+}
+
+#[tokio::test]
+async fn test_rate_limited_error() {
+    let (base_url, _) = spawn_mock_server(vec![
+        MockResponse::json(429, r#"{"error":{"name":"too_many_requests","message":"Too many API requests."}}"#)
+            .with_header("Remaining-Req", "group=market; min=573; sec=0"),
+    ]).await;
+
+    // 재시도 없이 429 응답을 그대로 받음
+    let client = UpbitClient::public(&base_url).with_retry(0, Duration::ZERO);
+    let error = get_market_info(&client).await.unwrap_err();
+    assert!(error.is_retryable());
+    match error {
+        UpbitError::RateLimited { remaining_req, error } => {
+            assert_eq!(remaining_req.unwrap().group, "market");
+            assert_eq!(error.unwrap().name, "too_many_requests");
+        }
+        e => panic!("unexpected error: {:?}", e),
+    }
+}
+
+#[tokio::test]
+async fn test_auth_error() {
+    let (base_url, _) = spawn_mock_server(vec![
+        MockResponse::json(401, r#"{"error":{"name":"jwt_verification","message":"Failed to verify Jwt token."}}"#),
+    ]).await;
+
+    let error = check_my_account(&UpbitClient::new(&base_url, "access", "secret")).await.unwrap_err();
+    assert!(!error.is_retryable());
+    assert_eq!(error.error_name(), Some("jwt_verification"));
+    assert!(matches!(error, UpbitError::Auth { .. }));
+}
+
+#[tokio::test]
+async fn test_http_and_decode_error() {
+    let (base_url, _) = spawn_mock_server(vec![
+        MockResponse::json(503, "Service Unavailable"),
+        MockResponse::json(200, "not json"),
+    ]).await;
+    let client = UpbitClient::public(&base_url);
+
+    let error = get_market_info(&client).await.unwrap_err();
+    assert!(error.is_retryable());
+    assert!(matches!(error, UpbitError::Http { status: 503, error: None, .. }));
+
+    let error = get_market_info(&client).await.unwrap_err();
+    assert!(matches!(error, UpbitError::Decode(_)));
+}
+
+#[tokio::test]
+async fn test_transport_error() {
+    // 아무도 listen하지 않는 포트
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    drop(listener);
+
+    let error = get_market_info(&UpbitClient::public(&base_url)).await.unwrap_err();
+    assert!(matches!(error, UpbitError::Transport(_)));
+}
@@ -0,0 +1,83 @@
+use ctb::{backtest::{optimize::{Objective, OptimizeConfig, ParamRange, ParamSpace}, walk_forward::{walk_forward, Search, WalkForwardConfig}},
+core::candle::{Candle, CandleBase}};
+
+fn create_candles(count: usize) -> Vec<Candle> {
+    (0..count).map(|i| {
+        let price = 1000.0 + (i as f64 * 0.05).sin() * 80.0 + (i as f64 * 0.4).sin() * 10.0;
+        let opening = price - (i as f64 * 0.7).cos() * 5.0;
+        Candle {
+            base: CandleBase {
+                market: "KRW-BTC".to_string(),
+                candle_date_time_utc: format!("2024-01-{:02}T{:02}:{:02}:00", 1 + i / 1440, (i / 60) % 24, i % 60),
+                candle_date_time_kst: String::new(),
+                opening_price: opening,
+                high_price: price.max(opening) + 3.0,
+                low_price: price.min(opening) - 3.0,
+                trade_price: price,
+                timestamp: 0,
+                candle_acc_trade_price: 1000000.0,
+                candle_acc_trade_volume: 1000.0 + (i % 7) as f64 * 300.0,
+            }
+        }
+    }).collect()
+}
+
+fn create_space() -> ParamSpace {
+    let mut space = ParamSpace::new();
+    space.add(ParamRange::new("min_weight_for_buy", vec![0.5, 1.0]))
+        .add(ParamRange::new("stop_loss_multiplier", vec![0.01, 0.02]));
+    space
+}
+
+#[test]
+fn test_windows() {
+    let config = WalkForwardConfig::new(100, 50);
+    assert_eq!(config.windows(250), vec![(0..100, 100..150), (50..150, 150..200), (100..200, 200..250)]);
+    // 마지막 구간은 짧아질 수 있음
+    assert_eq!(config.windows(220).last().unwrap().1, 200..220);
+    assert!(config.windows(100).is_empty());
+
+    let mut config = WalkForwardConfig::new(100, 50);
+    config.anchored = true;
+    assert_eq!(config.windows(200), vec![(0..100, 100..150), (0..150, 150..200)]);
+
+    assert!(WalkForwardConfig::new(100, 0).windows(1000).is_empty());
+}
+
+#[test]
+fn test_walk_forward_stitches_out_of_sample() {
+    let candles = create_candles(1200);
+    let config = WalkForwardConfig::new(400, 200);
+    let optimize_config = OptimizeConfig::new("KRW-BTC", "candle_pattern", Objective::TotalReturn);
+    let report = walk_forward(&candles, &create_space(), &config, &optimize_config).unwrap();
+
+    assert_eq!(report.windows.len(), 4);
+    for window in &report.windows {
+        assert_eq!(window.in_sample.end, window.out_of_sample.start);
+        assert_eq!(window.params.len(), 2);
+    }
+
+    // 첫 구간 시작 자산에서 각 구간 수익률을 복리로 이어 붙임
+    assert_eq!(report.equity_curve[0].date, candles[400].base.candle_date_time_utc);
+    assert_eq!(report.equity_curve[0].equity, 1000000.0);
+    let compounded = report.windows.iter().fold(1000000.0, |asset, window| asset * (1.0 + window.out_of_sample_report.total_return_pct));
+    assert!((report.report.final_asset - compounded).abs() < 1e-3, "{} != {}", report.report.final_asset, compounded);
+    assert_eq!(report.trades.len() as u32, report.windows.iter().map(|window| window.out_of_sample_report.total_trades).sum::<u32>());
+    assert!(report.trades.iter().all(|trade| trade.entry_date.as_str() >= candles[400].base.candle_date_time_utc.as_str()));
+}
+
+#[test]
+fn test_walk_forward_random_search_and_short_history() {
+    let candles = create_candles(700);
+    let mut config = WalkForwardConfig::new(400, 150);
+    config.search = Search::Random { count: 2, seed: 3 };
+    let optimize_config = OptimizeConfig::new("KRW-BTC", "candle_pattern", Objective::Sharpe);
+    let report = walk_forward(&candles, &create_space(), &config, &optimize_config).unwrap();
+    assert_eq!(report.windows.len(), 2);
+
+    // 구간을 만들 수 없으면 빈 결과
+    let report = walk_forward(&candles[..300], &create_space(), &config, &optimize_config).unwrap();
+    assert!(report.windows.is_empty());
+    assert!(report.efficiency.is_none());
+    assert_eq!(report.report.final_asset, 1000000.0);
+}